@@ -1,16 +1,24 @@
+use crate::app::state::AppState;
+use crate::config::layout::{FooterSettings, LayoutConfig};
 use crate::core::split::{COMPARISON_BEST_SEGMENTS, COMPARISON_PERSONAL_BEST};
-use crate::{app::state::AppState, config::layout::LayoutConfig};
 use chrono::Duration;
 use eframe::egui::{self, Color32, RichText};
 
 impl AppState {
-    pub fn draw_footer(&mut self, ui: &mut egui::Ui, top: bool) {
+    pub fn draw_footer(
+        &mut self,
+        ui: &mut egui::Ui,
+        index: usize,
+        top: bool,
+        settings: &FooterSettings,
+    ) {
         let LayoutConfig {
             font_sizes,
             colors,
             spacings: _,
             options,
-            #[cfg(windows)]
+            components: _,
+            #[cfg(any(windows, target_os = "linux"))]
                 hotkeys: _,
         } = self.layout.clone();
 
@@ -27,73 +35,11 @@ impl AppState {
         let pb_positive_color = Color32::from_hex(&colors.pb_positive).unwrap_or(Color32::GREEN);
         let pb_negative_color = Color32::from_hex(&colors.pb_negative).unwrap_or(Color32::RED);
 
-        let method = self.run.timing_method;
         let selected_comparison = self.run.selected_comparison.clone();
-
-        let sum_of_bests = self
-            .run
-            .splits
-            .iter()
-            .filter_map(|s| s.comparison_time(COMPARISON_BEST_SEGMENTS, method))
-            .fold(Duration::zero(), |acc, d| acc + d);
-
-        let current_time = if self.current_split > 0 {
-            self.splits_display
-                .get(self.current_split - 1)
-                .and_then(|s| s.last_time_for(method))
-                .unwrap_or(Duration::zero())
-        } else {
-            Duration::zero()
-        };
-
-        let remaining_best: Duration = self
-            .run
-            .splits
-            .iter()
-            .skip(self.current_split)
-            .filter_map(|s| s.comparison_time(COMPARISON_BEST_SEGMENTS, method))
-            .sum();
-
-        let best_possible_time = current_time + remaining_best;
-
-        let pb_time: Option<Duration> = self
+        let pb_time = self
             .run
-            .splits
-            .iter()
-            .map(|s| s.comparison_time(COMPARISON_PERSONAL_BEST, method))
-            .collect::<Option<Vec<_>>>()
-            .map(|times| times.into_iter().sum());
-
-        let previous_split_relative = if self.current_split == 1 {
-            self.splits_display
-                .first()
-                .and_then(|s| s.last_time_for(method))
-                .unwrap_or(Duration::zero())
-        } else if self.current_split > 1 {
-            let current = self
-                .splits_display
-                .get(self.current_split - 1)
-                .and_then(|s| s.last_time_for(method))
-                .unwrap_or(Duration::zero());
-
-            let previous = self
-                .splits_display
-                .get(self.current_split - 2)
-                .and_then(|s| s.last_time_for(method))
-                .unwrap_or(Duration::zero());
-
-            current - previous
-        } else {
-            Duration::zero()
-        };
-
-        let previous_segment_comparison = self
-            .splits_display
-            .get(self.current_split.saturating_sub(1))
-            .and_then(|s| s.comparison_time(&selected_comparison, method))
-            .unwrap_or(Duration::zero());
-
-        let delta_vs_selected = previous_split_relative - previous_segment_comparison;
+            .comparison_total(COMPARISON_PERSONAL_BEST, self.run.timing_method);
+        let previous_segment = self.previous_segment();
         // "Best Segments" gets the gold color scheme, everything else (PB,
         // Average, Median, custom) gets the PB one.
         let is_gold_style = selected_comparison == COMPARISON_BEST_SEGMENTS;
@@ -105,12 +51,78 @@ impl AppState {
             self.format_duration(dur, 2) // signo + y -
         };
 
+        // Rows in display order, `None` for the toggled-off ones.
+        let info_rows: Vec<(&str, String)> = [
+            settings.show_attempts.then(|| {
+                (
+                    egui_phosphor::regular::ARROW_COUNTER_CLOCKWISE,
+                    format!("Attempts: {}", self.run.attempts),
+                )
+            }),
+            settings.show_total_playtime.then(|| {
+                (
+                    egui_phosphor::regular::CLOCK,
+                    format!("Total Playtime: {}", format_dur(self.total_playtime())),
+                )
+            }),
+            settings.show_sum_of_best.then(|| {
+                (
+                    egui_phosphor::regular::FLAG_CHECKERED,
+                    format!("Sum of Best: {}", format_dur(self.sum_of_best())),
+                )
+            }),
+            settings.show_best_possible.then(|| {
+                (
+                    egui_phosphor::regular::GAUGE,
+                    format!("Best Possible: {}", format_dur(self.best_possible())),
+                )
+            }),
+            settings.show_current_pace.then(|| {
+                (
+                    egui_phosphor::regular::PERSON_SIMPLE_RUN,
+                    format!(
+                        "Current Pace: {}",
+                        self.current_pace().map_or("-".to_string(), format_dur)
+                    ),
+                )
+            }),
+            pb_time.filter(|_| settings.show_pb).map(|pb| {
+                (
+                    egui_phosphor::regular::CHART_POLAR,
+                    format!("PB: {}", format_dur(pb)),
+                )
+            }),
+            settings.show_pb_chance.then(|| {
+                (
+                    egui_phosphor::regular::PERCENT,
+                    format!(
+                        "PB Chance: {}",
+                        self.pb_chance()
+                            .map_or("-".to_string(), |p| format!("{:.1}%", p * 100.0))
+                    ),
+                )
+            }),
+            settings.show_possible_time_save.then(|| {
+                (
+                    egui_phosphor::regular::HOURGLASS_MEDIUM,
+                    format!(
+                        "Possible Time Save: {}",
+                        self.possible_time_save()
+                            .map_or("-".to_string(), format_dur)
+                    ),
+                )
+            }),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+
         let mut comparison_clicked = false;
 
         let panel = if top {
-            egui::Panel::top("footer")
+            egui::Panel::top(egui::Id::new("footer").with(index))
         } else {
-            egui::Panel::bottom("footer")
+            egui::Panel::bottom(egui::Id::new("footer").with(index))
         };
 
         panel
@@ -130,94 +142,60 @@ impl AppState {
                 ui.painter()
                     .line_segment([egui::pos2(left, top), egui::pos2(right, top)], stroke);
 
-                if options.show_info {
+                if settings.show_info {
                     ui.add_space(4.0);
                     ui.vertical(|ui| {
-                        ui.horizontal(|ui| {
-                            ui.add_space(8.0);
-                            ui.label(
-                                RichText::new(format!(
-                                    "{} Attempts: {}",
-                                    egui_phosphor::regular::ARROW_COUNTER_CLOCKWISE,
-                                    self.run.attempts
-                                ))
-                                .color(info_color)
-                                .size(font_sizes.info),
-                            );
-                        });
-
-                        ui.horizontal(|ui| {
-                            ui.add_space(8.0);
-                            ui.label(
-                                RichText::new(format!(
-                                    "{} Sum of Best: {}",
-                                    egui_phosphor::regular::FLAG_CHECKERED,
-                                    format_dur(sum_of_bests)
-                                ))
-                                .color(info_color)
-                                .size(font_sizes.info),
-                            );
-                        });
-
-                        ui.horizontal(|ui| {
-                            ui.add_space(8.0);
-                            ui.label(
-                                RichText::new(format!(
-                                    "{} Best Possible: {}",
-                                    egui_phosphor::regular::GAUGE,
-                                    format_dur(best_possible_time)
-                                ))
-                                .color(info_color)
-                                .size(font_sizes.info),
-                            );
-                        });
-
-                        if let Some(pb) = pb_time {
+                        for (icon, text) in &info_rows {
                             ui.horizontal(|ui| {
                                 ui.add_space(8.0);
                                 ui.label(
-                                    RichText::new(format!(
-                                        "{} PB: {}",
-                                        egui_phosphor::regular::CHART_POLAR,
-                                        format_dur(pb)
-                                    ))
-                                    .color(info_color)
-                                    .size(font_sizes.info),
+                                    RichText::new(format!("{} {}", icon, text))
+                                        .color(info_color)
+                                        .size(font_sizes.info),
                                 );
                             });
                         }
 
-                        let (delta_icon, delta_color) = if is_gold_style {
-                            (
-                                egui_phosphor::regular::STAR,
-                                if delta_vs_selected < Duration::zero() {
-                                    gold_positive_color
-                                } else {
-                                    gold_negative_color
-                                },
-                            )
-                        } else {
-                            (
-                                egui_phosphor::regular::ARROW_LINE_UP,
-                                if delta_vs_selected < Duration::zero() {
-                                    pb_positive_color
-                                } else {
-                                    pb_negative_color
-                                },
-                            )
-                        };
-
-                        if self.current_split > 0 {
+                        if settings.show_previous_segment
+                            && let Some(previous) = previous_segment
+                        {
+                            let ahead = previous.delta < Duration::zero();
+                            // A gold always shows as one, whatever the
+                            // comparison.
+                            let (delta_icon, delta_color) = if previous.is_gold {
+                                (egui_phosphor::regular::STAR, gold_positive_color)
+                            } else if is_gold_style {
+                                (
+                                    egui_phosphor::regular::STAR,
+                                    if ahead {
+                                        gold_positive_color
+                                    } else {
+                                        gold_negative_color
+                                    },
+                                )
+                            } else {
+                                (
+                                    egui_phosphor::regular::ARROW_LINE_UP,
+                                    if ahead {
+                                        pb_positive_color
+                                    } else {
+                                        pb_negative_color
+                                    },
+                                )
+                            };
+                            let gold_suffix = if previous.is_gold { " (gold)" } else { "" };
+
                             ui.horizontal(|ui| {
                                 ui.add_space(8.0);
                                 let response = ui
                                     .add(
                                         egui::Label::new(
                                             RichText::new(format!(
-                                                "{} Prev {} Segment: {}",
+                                                "{} Prev {} Segment: {}{}",
                                                 delta_icon,
                                                 selected_comparison,
-                                                format_diff(delta_vs_selected)
+                                                format_diff(previous.delta),
+                                                gold_suffix
                                             ))
                                             .color(delta_color)
                                             .size(font_sizes.info),
@@ -1,12 +1,18 @@
 use crate::app::state::AppState;
-use crate::config::layout::LayoutConfig;
+use crate::config::layout::{GraphSettings, LayoutConfig};
 use crate::core::split::COMPARISON_BEST_SEGMENTS;
 use eframe::egui;
 use egui::Color32;
 use egui_plot::{HLine, Line, Plot, PlotPoints};
 
 impl AppState {
-    pub fn draw_graph(&mut self, ui: &mut egui::Ui, top: bool) {
+    pub fn draw_graph(
+        &mut self,
+        ui: &mut egui::Ui,
+        index: usize,
+        top: bool,
+        settings: &GraphSettings,
+    ) {
         let LayoutConfig {
             colors, options, ..
         } = self.layout.clone();
@@ -36,22 +42,22 @@ impl AppState {
         };
 
         let panel = if top {
-            egui::Panel::top("graph")
+            egui::Panel::top(egui::Id::new("graph").with(index))
         } else {
-            egui::Panel::bottom("graph")
+            egui::Panel::bottom(egui::Id::new("graph").with(index))
         };
 
         panel
             .resizable(false)
-            .exact_size(76.0)
+            .exact_size(settings.height)
             .frame(egui::Frame {
                 fill: bg_color,
                 stroke: egui::Stroke::NONE,
                 ..Default::default()
             })
             .show_inside(ui, |ui| {
-                Plot::new("delta_graph")
-                    .height(72.0)
+                Plot::new(("delta_graph", index))
+                    .height(settings.height - 4.0)
                     .show_axes(false)
                     .show_grid(false)
                     .show_background(false)
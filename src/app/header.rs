@@ -1,6 +1,7 @@
+use crate::app::state::AppState;
+use crate::config::layout::{LayoutConfig, TimerSettings, TitleSettings};
 use crate::core::split::TimingMethod;
 use crate::core::timer::TimerState;
-use crate::{app::state::AppState, config::layout::LayoutConfig};
 use eframe::egui::{self, Color32, RichText};
 use egui::{Sense, ViewportCommand};
 
@@ -50,17 +51,18 @@ impl AppState {
             });
     }
 
-    pub fn draw_title(&self, ui: &mut egui::Ui, top: bool) {
+    pub fn draw_title(&self, ui: &mut egui::Ui, index: usize, top: bool, settings: &TitleSettings) {
         let LayoutConfig {
             font_sizes,
             colors,
             spacings: _,
             options,
-            #[cfg(windows)]
+            components: _,
+            #[cfg(any(windows, target_os = "linux"))]
                 hotkeys: _,
         } = self.layout.clone();
 
-        if !options.show_title && !options.show_category {
+        if !settings.show_title && !settings.show_category {
             return;
         }
 
@@ -74,22 +76,22 @@ impl AppState {
 
         let frame = egui::Frame::default().fill(bg_color);
         let panel = if top {
-            egui::Panel::top("title")
+            egui::Panel::top(egui::Id::new("title").with(index))
         } else {
-            egui::Panel::bottom("title")
+            egui::Panel::bottom(egui::Id::new("title").with(index))
         };
 
         panel.frame(frame).show_inside(ui, |ui| {
             ui.add_space(10.0);
             ui.vertical_centered(|ui| {
-                if options.show_title {
+                if settings.show_title {
                     ui.label(
                         RichText::new(&self.run.title)
                             .color(title_color)
                             .size(font_sizes.title),
                     );
                 }
-                if options.show_category {
+                if settings.show_category {
                     ui.label(
                         RichText::new(&self.run.category)
                             .color(category_color)
@@ -101,13 +103,14 @@ impl AppState {
         });
     }
 
-    pub fn draw_timer(&self, ui: &mut egui::Ui, top: bool) {
+    pub fn draw_timer(&self, ui: &mut egui::Ui, index: usize, top: bool, settings: &TimerSettings) {
         let LayoutConfig {
             font_sizes,
             colors,
             spacings: _,
             options,
-            #[cfg(windows)]
+            components: _,
+            #[cfg(any(windows, target_os = "linux"))]
                 hotkeys: _,
         } = self.layout.clone();
 
@@ -142,9 +145,9 @@ impl AppState {
 
         let frame = egui::Frame::default().fill(bg_color);
         let panel = if top {
-            egui::Panel::top("timer")
+            egui::Panel::top(egui::Id::new("timer").with(index))
         } else {
-            egui::Panel::bottom("timer")
+            egui::Panel::bottom(egui::Id::new("timer").with(index))
         };
 
         panel.frame(frame).show_inside(ui, |ui| {
@@ -157,7 +160,7 @@ impl AppState {
                         .strong(),
                 );
 
-                if secondary_active {
+                if settings.show_secondary && secondary_active {
                     let loading_suffix =
                         if method == TimingMethod::RealTime && self.igt_timer.is_paused() {
                             format!(" {}", egui_phosphor::regular::HOURGLASS)
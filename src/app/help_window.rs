@@ -19,6 +19,7 @@ impl AppState {
                         ui.label("[P] Pause");
                         ui.label("[R] Reset");
                         ui.label("[Enter] Split");
+                        ui.label("[K] Skip split");
                         ui.label("[L] Toggle loading (pause/resume IGT only)");
                         ui.label("[C] Cycle comparison");
                         ui.label("[Ctrl + S] Save comparisons");
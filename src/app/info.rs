@@ -0,0 +1,182 @@
+//! The numbers behind the footer's info rows (see
+//! `config::layout::FooterSettings`), shared with `app::overlay` so the
+//! window and the overlay never disagree. All of them are on the run's
+//! timing method, except `total_playtime`, which is real time.
+
+use chrono::Duration;
+
+use crate::app::state::AppState;
+use crate::core::split::{COMPARISON_BEST_SEGMENTS, COMPARISON_PERSONAL_BEST, TimingMethod};
+use crate::core::stats;
+use crate::core::timer::TimerState;
+
+/// The segment that just finished against the selected comparison.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PreviousSegment {
+    /// Negative when time was saved.
+    pub delta: Duration,
+    /// Whether it was a new best segment.
+    pub is_gold: bool,
+}
+
+impl AppState {
+    /// The clock `method` reads from.
+    pub fn current_time_for(&self, method: TimingMethod) -> Duration {
+        match method {
+            TimingMethod::RealTime => self.timer.current_time(),
+            TimingMethod::GameTime => self.igt_timer.current_time(),
+        }
+    }
+
+    fn in_attempt(&self) -> bool {
+        matches!(self.timer.state, TimerState::Running | TimerState::Paused)
+    }
+
+    /// Sum of every split's best segment. Splits without one are left
+    /// out rather than making the whole sum unknown.
+    pub fn sum_of_best(&self) -> Duration {
+        let method = self.run.timing_method;
+        self.run
+            .splits
+            .iter()
+            .filter_map(|s| s.comparison_time(COMPARISON_BEST_SEGMENTS, method))
+            .sum()
+    }
+
+    /// Time so far plus the best segments still to go.
+    pub fn best_possible(&self) -> Duration {
+        let method = self.run.timing_method;
+        let remaining_best: Duration = self
+            .run
+            .splits
+            .iter()
+            .skip(self.current_split)
+            .filter_map(|s| s.comparison_time(COMPARISON_BEST_SEGMENTS, method))
+            .sum();
+        self.time_before_split(self.current_split, method) + remaining_best
+    }
+
+    /// `None` before the first split of an attempt, or right after a
+    /// skipped one.
+    pub fn previous_segment(&self) -> Option<PreviousSegment> {
+        let method = self.run.timing_method;
+        let previous = self.current_split.checked_sub(1)?;
+        let previous_time = self.splits_display.get(previous)?.last_time_for(method)?;
+        let segment = previous_time - self.time_before_split(previous, method);
+        let comparison = self
+            .combined_comparison_time(previous, &self.run.selected_comparison, method)
+            .unwrap_or_else(Duration::zero);
+        Some(PreviousSegment {
+            delta: segment - comparison,
+            is_gold: self.last_segment_is_gold,
+        })
+    }
+
+    /// How much faster than the selected comparison the current segment
+    /// could go, going by its best segment. `None` once the run is over
+    /// or if either time is missing.
+    pub fn possible_time_save(&self) -> Option<Duration> {
+        if self.current_split >= self.splits_display.len() {
+            return None;
+        }
+        let method = self.run.timing_method;
+        let comparison = self.combined_comparison_time(
+            self.current_split,
+            &self.run.selected_comparison,
+            method,
+        )?;
+        let best =
+            self.combined_comparison_time(self.current_split, COMPARISON_BEST_SEGMENTS, method)?;
+        Some((comparison - best).max(Duration::zero()))
+    }
+
+    /// The final time this attempt is heading for: time so far plus the
+    /// selected comparison's remaining segments. A current segment already
+    /// running longer than its comparison counts at its live length. The
+    /// final time once finished; `None` if a comparison segment is missing.
+    pub fn current_pace(&self) -> Option<Duration> {
+        let method = self.run.timing_method;
+        let total = self.splits_display.len();
+        if self.current_split >= total {
+            return self
+                .splits_display
+                .last()
+                .and_then(|s| s.last_time_for(method));
+        }
+
+        let comparison = &self.run.selected_comparison;
+        let current_segment =
+            self.combined_comparison_time(self.current_split, comparison, method)?;
+        let rest: Duration = self
+            .run
+            .splits
+            .get(self.current_split + 1..)?
+            .iter()
+            .map(|s| s.comparison_time(comparison, method))
+            .sum::<Option<Duration>>()?;
+
+        let mut current_segment_end =
+            self.time_before_split(self.current_split, method) + current_segment;
+        if self.in_attempt() {
+            current_segment_end = current_segment_end.max(self.current_time_for(method));
+        }
+        Some(current_segment_end + rest)
+    }
+
+    /// Real time spent in every recorded attempt, finished or reset, plus
+    /// the one in progress.
+    pub fn total_playtime(&self) -> Duration {
+        let recorded: Duration = self
+            .run
+            .attempt_history
+            .iter()
+            .filter_map(|a| a.real_time)
+            .sum();
+        if self.in_attempt() {
+            recorded + self.timer.current_time().max(Duration::zero())
+        } else {
+            recorded
+        }
+    }
+
+    /// Chance (`0.0..=1.0`) of this attempt beating the Personal Best,
+    /// from the segment history of the splits still to go (see
+    /// `core::stats::pb_chance`). `None` without a PB, or while a
+    /// remaining split has no history.
+    pub fn pb_chance(&self) -> Option<f64> {
+        let method = self.run.timing_method;
+        let pb = self
+            .run
+            .comparison_total(COMPARISON_PERSONAL_BEST, method)?;
+        if self.in_attempt() && self.current_time_for(method) > pb {
+            return Some(0.0);
+        }
+
+        // Resume from the last split actually reached, so any splits
+        // skipped since still count as to go.
+        let reached = self.current_split.min(self.splits_display.len());
+        let from = (0..reached)
+            .rev()
+            .find(|&i| self.splits_display[i].last_time_for(method).is_some())
+            .map_or(0, |i| i + 1);
+
+        let remaining: Vec<Vec<Duration>> = self
+            .run
+            .splits
+            .get(from..)?
+            .iter()
+            .map(|split| {
+                let mut times: Vec<Duration> = split
+                    .segment_history
+                    .iter()
+                    .filter(|entry| !entry.combined)
+                    .filter_map(|entry| entry.get(method))
+                    .collect();
+                times.sort();
+                times
+            })
+            .collect();
+
+        stats::pb_chance(self.time_before_split(from, method), &remaining, pb)
+    }
+}
@@ -3,7 +3,10 @@ pub mod footer;
 pub mod graph;
 pub mod header;
 pub mod help_window;
+pub mod info;
 pub mod overlay;
+pub mod practice;
+pub mod recovery_window;
 pub mod resize;
 pub mod splits_panel;
 pub mod state;
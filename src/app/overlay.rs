@@ -2,13 +2,13 @@
 //! `app::websocket_server`) streams to connected clients — typically an OBS
 //! browser-source overlay. Kept pure (just reads `AppState`, no I/O) so it's
 //! testable without a live socket; the same numbers this produces are what
-//! `app::footer`/`app::splits_panel` already render, just as structured
-//! data instead of `egui` widgets.
+//! `app::footer`/`app::splits_panel` already render (the stats come from
+//! `app::info`), just as structured data instead of `egui` widgets.
 
 use serde::Serialize;
 
 use crate::app::state::AppState;
-use crate::core::split::{COMPARISON_BEST_SEGMENTS, COMPARISON_PERSONAL_BEST, TimingMethod};
+use crate::core::split::{COMPARISON_PERSONAL_BEST, TimingMethod};
 use crate::core::timer::TimerState;
 
 #[derive(Debug, Clone, Serialize)]
@@ -22,7 +22,8 @@ pub struct OverlaySplit {
     /// previous split's), or `None` if not reached yet.
     pub segment_time_ms: Option<i64>,
     /// The selected comparison's segment (not cumulative) time for this
-    /// split, if set.
+    /// split, if set — including any skipped splits right before it, so it
+    /// lines up with a combined `segment_time_ms`.
     pub segment_comparison_ms: Option<i64>,
     /// `segment_time_ms - segment_comparison_ms`; only present once both are.
     pub delta_ms: Option<i64>,
@@ -50,8 +51,20 @@ pub struct OverlaySnapshot {
     pub pb_time_ms: Option<i64>,
     /// The just-finished split's delta against the selected comparison —
     /// what the footer's "Prev Segment" line shows. `None` before the first
-    /// split of an attempt.
+    /// split of an attempt, or right after a skipped one.
     pub previous_segment_delta_ms: Option<i64>,
+    /// Whether that split was a new best segment.
+    pub previous_segment_is_gold: bool,
+    /// How much the current segment could save against the selected
+    /// comparison, going by its best segment.
+    pub possible_time_save_ms: Option<i64>,
+    /// The final time the attempt is heading for on the selected
+    /// comparison.
+    pub current_pace_ms: Option<i64>,
+    /// Real time across every recorded attempt plus the current one.
+    pub total_playtime_ms: i64,
+    /// `0.0..=1.0`; `None` without a PB or enough segment history.
+    pub pb_chance: Option<f64>,
     pub splits: Vec<OverlaySplit>,
 }
 
@@ -84,73 +97,11 @@ pub fn build_snapshot(app: &AppState) -> OverlaySnapshot {
         ),
     };
 
-    // Always a best-effort partial sum, same as the footer's "Sum of Best" —
-    // unlike Best Possible/PB below, a gap here doesn't make the total
-    // meaningless, so this never needs to be `None`.
-    let sum_of_best_ms: i64 = ms(app
-        .run
-        .splits
-        .iter()
-        .filter_map(|s| s.comparison_time(COMPARISON_BEST_SEGMENTS, method))
-        .sum());
-
-    let elapsed_so_far = if app.current_split > 0 {
-        app.splits_display
-            .get(app.current_split - 1)
-            .and_then(|s| s.last_time_for(method))
-            .unwrap_or_else(chrono::Duration::zero)
-    } else {
-        chrono::Duration::zero()
-    };
-    let remaining_best: chrono::Duration = app
-        .run
-        .splits
-        .iter()
-        .skip(app.current_split)
-        .filter_map(|s| s.comparison_time(COMPARISON_BEST_SEGMENTS, method))
-        .sum();
-    let best_possible_time_ms = ms(elapsed_so_far + remaining_best);
-
     let pb_time_ms = app
         .run
-        .splits
-        .iter()
-        .map(|s| s.comparison_time(COMPARISON_PERSONAL_BEST, method))
-        .collect::<Option<Vec<_>>>()
-        .map(|times| {
-            ms(times
-                .into_iter()
-                .fold(chrono::Duration::zero(), |a, b| a + b))
-        });
-
-    let previous_segment_delta_ms = if app.current_split > 0 {
-        let previous_split_relative = if app.current_split == 1 {
-            app.splits_display
-                .first()
-                .and_then(|s| s.last_time_for(method))
-                .unwrap_or_else(chrono::Duration::zero)
-        } else {
-            let current = app
-                .splits_display
-                .get(app.current_split - 1)
-                .and_then(|s| s.last_time_for(method))
-                .unwrap_or_else(chrono::Duration::zero);
-            let previous = app
-                .splits_display
-                .get(app.current_split - 2)
-                .and_then(|s| s.last_time_for(method))
-                .unwrap_or_else(chrono::Duration::zero);
-            current - previous
-        };
-        let previous_segment_comparison = app
-            .splits_display
-            .get(app.current_split - 1)
-            .and_then(|s| s.comparison_time(&selected_comparison, method))
-            .unwrap_or_else(chrono::Duration::zero);
-        Some(ms(previous_split_relative - previous_segment_comparison))
-    } else {
-        None
-    };
+        .comparison_total(COMPARISON_PERSONAL_BEST, method)
+        .map(ms);
+    let previous_segment = app.previous_segment();
 
     let mut previous_cumulative = chrono::Duration::zero();
     let splits = app
@@ -164,11 +115,7 @@ pub fn build_snapshot(app: &AppState) -> OverlaySnapshot {
                 previous_cumulative = c;
             }
 
-            let segment_comparison = app
-                .run
-                .splits
-                .get(i)
-                .and_then(|s| s.comparison_time(&selected_comparison, method));
+            let segment_comparison = app.combined_comparison_time(i, &selected_comparison, method);
 
             let delta_ms = match (segment_time, segment_comparison) {
                 (Some(t), Some(c)) => Some(ms(t - c)),
@@ -201,10 +148,15 @@ pub fn build_snapshot(app: &AppState) -> OverlaySnapshot {
         secondary_time_ms,
         current_split_index: app.current_split,
         total_splits: app.splits_display.len(),
-        sum_of_best_ms,
-        best_possible_time_ms,
+        sum_of_best_ms: ms(app.sum_of_best()),
+        best_possible_time_ms: ms(app.best_possible()),
         pb_time_ms,
-        previous_segment_delta_ms,
+        previous_segment_delta_ms: previous_segment.map(|p| ms(p.delta)),
+        previous_segment_is_gold: previous_segment.is_some_and(|p| p.is_gold),
+        possible_time_save_ms: app.possible_time_save().map(ms),
+        current_pace_ms: app.current_pace().map(ms),
+        total_playtime_ms: ms(app.total_playtime()),
+        pb_chance: app.pb_chance(),
         splits,
     }
 }
@@ -0,0 +1,136 @@
+//! Practice mode: timing segments from any split without it counting as
+//! an attempt. Nothing here touches `Run::attempts`, `attempt_history`,
+//! `segment_history`, the PB or (unless `Run::promote_practice_golds` is
+//! on) Best Segments; each practiced segment goes to its split's
+//! `practice_history` instead. A reset ends the session.
+
+use chrono::Duration;
+
+use crate::app::state::AppState;
+use crate::core::split::{
+    COMPARISON_AVERAGE_SEGMENTS, COMPARISON_BEST_SEGMENTS, PracticeEntry, TimingMethod,
+};
+use crate::core::timer::TimerState;
+
+/// A practiced segment (finished, or in progress) against the split's
+/// gold and average.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PracticeDeltas {
+    pub segment: Duration,
+    /// Negative when faster than the Best Segment.
+    pub vs_gold: Option<Duration>,
+    /// Against the run's Average Segments.
+    pub vs_average: Option<Duration>,
+}
+
+impl AppState {
+    /// Starts practising from split `index`: both clocks start from zero
+    /// there, as if the split before it had just been hit. Only from a
+    /// stopped timer; does nothing otherwise.
+    pub fn start_practice(&mut self, index: usize) {
+        if self.timer.state != TimerState::NotStarted || index >= self.splits_display.len() {
+            return;
+        }
+        self.practice_from = Some(index);
+        self.current_split = index;
+        self.splits_backup = self.run.splits.clone();
+        self.last_segment_is_gold = false;
+        self.start_clocks(0);
+        self.update_page();
+    }
+
+    pub fn in_practice(&self) -> bool {
+        self.practice_from.is_some()
+    }
+
+    /// `split` while practising: logs the segment to `practice_history`
+    /// and moves on. A segment spanning a skipped split isn't logged.
+    pub fn record_practice_split(&mut self) {
+        let index = self.current_split;
+        if index >= self.splits_display.len() {
+            return;
+        }
+        let now = self.timer.current_time();
+        let now_game = self.igt_timer.current_time();
+
+        let method = self.run.timing_method;
+        let start = self.practice_from.unwrap_or(index);
+        let combined = index > start && self.splits_display[index - 1].last_time.is_none();
+        let entry = PracticeEntry {
+            real_time: Some(now - self.time_before_split(index, TimingMethod::RealTime)),
+            game_time: Some(now_game - self.time_before_split(index, TimingMethod::GameTime)),
+            date: Some(self.clock.wall_now()),
+        };
+
+        self.splits_display[index].last_time = Some(now);
+        self.splits_display[index].last_time_game = Some(now_game);
+        self.last_segment_is_gold = false;
+
+        if !combined {
+            let promote = self.run.promote_practice_golds;
+            let split = &mut self.run.splits[index];
+            let best = split
+                .comparisons
+                .entry(COMPARISON_BEST_SEGMENTS.to_string())
+                .or_default();
+            self.last_segment_is_gold = entry
+                .get(method)
+                .is_some_and(|time| best.get(method).is_none_or(|gold| time < gold));
+            if promote {
+                for method in [TimingMethod::RealTime, TimingMethod::GameTime] {
+                    if let Some(time) = entry.get(method)
+                        && best.get(method).is_none_or(|gold| time < gold)
+                    {
+                        best.set(method, Some(time));
+                    }
+                }
+            }
+            split.practice_history.push(entry);
+
+            if let Err(e) = self.save_comparisons() {
+                eprintln!("Error saving practice segment: {}", e);
+            }
+        }
+
+        self.current_split += 1;
+        if self.current_split >= self.splits_display.len() {
+            self.timer.end();
+            self.igt_timer.end();
+        }
+        self.update_page();
+    }
+
+    /// The practiced segment at split `index` this session — finished, or
+    /// live for the current split — against its gold and average. `None`
+    /// outside practice, for splits not practised, and after a skip.
+    pub fn practice_deltas(&self, index: usize) -> Option<PracticeDeltas> {
+        let start = self.practice_from?;
+        if index < start || (index > start && self.splits_display[index - 1].last_time.is_none()) {
+            return None;
+        }
+
+        let method = self.run.timing_method;
+        let end = match self.splits_display.get(index)?.last_time_for(method) {
+            Some(time) => time,
+            None if index == self.current_split
+                && matches!(self.timer.state, TimerState::Running | TimerState::Paused) =>
+            {
+                self.current_time_for(method)
+            }
+            None => return None,
+        };
+        let segment = end - self.time_before_split(index, method);
+
+        let split = self.run.splits.get(index)?;
+        let against = |comparison: &str| {
+            split
+                .comparison_time(comparison, method)
+                .map(|time| segment - time)
+        };
+        Some(PracticeDeltas {
+            segment,
+            vs_gold: against(COMPARISON_BEST_SEGMENTS),
+            vs_average: against(COMPARISON_AVERAGE_SEGMENTS),
+        })
+    }
+}
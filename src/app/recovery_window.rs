@@ -0,0 +1,59 @@
+use crate::app::state::AppState;
+use chrono::{Duration, Local};
+use eframe::egui;
+
+impl AppState {
+    /// Offers to resume (or record as a reset) an attempt the journal says
+    /// was interrupted by a crash — see `core::journal`.
+    pub fn draw_recovery_window(&mut self, ctx: &egui::Context) {
+        let Some(journal) = &self.pending_journal else {
+            return;
+        };
+
+        let split_name = self
+            .run
+            .splits
+            .get(journal.current_split)
+            .map(|s| s.name.clone())
+            .unwrap_or_default();
+        let elapsed = self.format_duration(Duration::milliseconds(journal.real_elapsed_ms), 1);
+        let run_index = journal.run_index;
+        let saved_at = journal
+            .written_at
+            .map(|d| {
+                d.with_timezone(&Local)
+                    .format("%Y-%m-%d %H:%M:%S")
+                    .to_string()
+            })
+            .unwrap_or_default();
+
+        egui::Window::new("Interrupted Attempt")
+            .resizable(false)
+            .collapsible(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                ui.vertical_centered(|ui| {
+                    ui.heading(format!(
+                        "{} Interrupted Attempt",
+                        egui_phosphor::regular::WARNING
+                    ));
+                    ui.separator();
+                    ui.label(format!(
+                        "Attempt #{} stopped at {} during \"{}\" (last saved {}).",
+                        run_index, elapsed, split_name, saved_at
+                    ));
+                    ui.label("Starting a new attempt records it as a reset.");
+
+                    ui.add_space(10.0);
+                    ui.horizontal(|ui| {
+                        if ui.button("Resume").clicked() {
+                            self.resume_interrupted_attempt();
+                        }
+                        if ui.button("Record as reset").clicked() {
+                            self.record_interrupted_attempt_as_reset();
+                        }
+                    });
+                });
+            });
+    }
+}
@@ -1,17 +1,73 @@
-use crate::core::split::{COMPARISON_BEST_SEGMENTS, TimingMethod};
-use crate::{app::state::AppState, config::layout::LayoutConfig, core::timer::TimerState};
+use crate::config::layout::{FontSizes, LayoutConfig, SplitsSettings};
+use crate::core::split::{COMPARISON_BEST_SEGMENTS, SplitSection, TimingMethod};
+use crate::{app::state::AppState, core::timer::TimerState};
 use chrono::Duration;
 use eframe::egui::{self, Color32, RichText};
 
+/// What a section's header row shows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SectionTimes {
+    /// Time spent in the section: final once its last split is reached,
+    /// live while the attempt is inside it, `None` otherwise.
+    pub segment: Option<Duration>,
+    /// The attempt's time at the section's last split.
+    pub cumulative: Option<Duration>,
+    /// The selected comparison's segments over the section, summed.
+    pub comparison: Option<Duration>,
+    /// `segment - comparison`, once the section is done.
+    pub delta: Option<Duration>,
+}
+
 impl AppState {
-    pub fn draw_splits_panel(&mut self, ui: &mut egui::Ui) {
+    /// Totals for `section` on the run's timing method, against the
+    /// selected comparison.
+    pub fn section_times(&self, section: &SplitSection) -> SectionTimes {
+        let method = self.run.timing_method;
+        let start = self.time_before_split(section.range.start, method);
+        let cumulative = self
+            .splits_display
+            .get(section.last())
+            .and_then(|s| s.last_time_for(method));
+
+        let in_section = section.range.contains(&self.current_split)
+            && matches!(self.timer.state, TimerState::Running | TimerState::Paused);
+        let segment = match cumulative {
+            Some(end) => Some(end - start),
+            None if in_section => Some(self.current_time_for(method) - start),
+            None => None,
+        };
+
+        let comparison_name = &self.run.selected_comparison;
+        let comparison = self
+            .combined_comparison_time(section.range.start, comparison_name, method)
+            .and_then(|first| {
+                self.run.splits[section.range.start + 1..section.range.end]
+                    .iter()
+                    .map(|s| s.comparison_time(comparison_name, method))
+                    .sum::<Option<Duration>>()
+                    .map(|rest| first + rest)
+            });
+
+        SectionTimes {
+            segment,
+            cumulative,
+            comparison,
+            delta: cumulative
+                .and(segment)
+                .zip(comparison)
+                .map(|(segment, comparison)| segment - comparison),
+        }
+    }
+
+    pub fn draw_splits_panel(&mut self, ui: &mut egui::Ui, settings: &SplitsSettings) {
         let ctx = ui.ctx().clone();
         let LayoutConfig {
             colors,
             font_sizes,
             spacings,
             options,
-            #[cfg(windows)]
+            components: _,
+            #[cfg(any(windows, target_os = "linux"))]
                 hotkeys: _,
         } = self.layout.clone();
 
@@ -38,18 +94,31 @@ impl AppState {
         egui::CentralPanel::default()
             .frame(egui::Frame::default().fill(bg_color))
             .show_inside(ui, |ui| {
-                if options.show_splits {
+                if settings.show_rows {
                     egui::ScrollArea::vertical().show(ui, |ui| {
                         let total_splits = self.run.splits.len();
                         let page_start = self.current_page * self.splits_per_page;
                         let page_end = (page_start + self.splits_per_page).min(total_splits);
                         let splits = self.splits_display.clone();
                         let current_split = self.current_split;
+                        let sections = self.run.sections();
+
+                        if let Some(from) = self.practice_from {
+                            ui.label(
+                                RichText::new(format!("Practice from {}", splits[from].name))
+                                    .size(font_sizes.split)
+                                    .color(split_selected_color),
+                            );
+                        }
 
                         for (i, split) in splits.iter().enumerate().take(page_end).skip(page_start)
                         {
                             let is_current = i == current_split;
                             let is_first = i == page_start;
+                            let section = sections
+                                .iter()
+                                .find(|s| s.range.contains(&i))
+                                .filter(|s| s.name.is_some());
 
                             // Determine if this is the first split on the current page
                             if is_first {
@@ -63,6 +132,24 @@ impl AppState {
                                 );
                             }
 
+                            if let Some(section) = section {
+                                let collapsed = settings.collapse_sections
+                                    && !section.range.contains(&current_split);
+                                if i == section.range.start || is_first {
+                                    self.draw_section_header(
+                                        ui,
+                                        section,
+                                        collapsed,
+                                        settings,
+                                        &font_sizes,
+                                        spacings.split_top,
+                                    );
+                                }
+                                if collapsed {
+                                    continue;
+                                }
+                            }
+
                             ui.add_space(spacings.split_top);
 
                             // Reserve a fixed-size slot for the icon —
@@ -77,7 +164,7 @@ impl AppState {
                             let row = ui.horizontal(|ui| {
                                 ui.set_min_height(icon_slot + 12.0);
                                 ui.set_min_width(ui.available_width());
-                                ui.add_space(10.0);
+                                ui.add_space(if section.is_some() { 26.0 } else { 10.0 });
                                 let texture = split
                                     .icon_path
                                     .as_ref()
@@ -126,25 +213,57 @@ impl AppState {
                                 ui.with_layout(
                                     egui::Layout::right_to_left(egui::Align::Center),
                                     |ui| {
-                                        if let Some(last) = split.last_time_for(method) {
+                                        if let Some(practice) = self.practice_deltas(i) {
+                                            // Practice rows show just the
+                                            // segment, against its gold and
+                                            // average, whatever comparison
+                                            // is selected.
+                                            ui.label(
+                                                RichText::new(
+                                                    self.format_duration(practice.segment, 0),
+                                                )
+                                                .size(font_sizes.split)
+                                                .color(split_timer_color),
+                                            );
+                                            let deltas = [
+                                                (
+                                                    practice.vs_average,
+                                                    pb_positive_color,
+                                                    pb_negative_color,
+                                                ),
+                                                (
+                                                    practice.vs_gold,
+                                                    gold_positive_color,
+                                                    gold_negative_color,
+                                                ),
+                                            ];
+                                            for (delta, ahead, behind) in deltas {
+                                                let Some(delta) = delta else {
+                                                    continue;
+                                                };
+                                                let color = if delta < Duration::zero() {
+                                                    ahead
+                                                } else {
+                                                    behind
+                                                };
+                                                ui.label(
+                                                    RichText::new(self.format_duration(delta, 2))
+                                                        .size(font_sizes.split_gold)
+                                                        .color(color),
+                                                );
+                                            }
+                                        } else if let Some(last) = split.last_time_for(method) {
                                             // Show total time
                                             let time_text = self.format_duration(last, 0);
 
-                                            let prev = if i == 0 {
-                                                Duration::zero()
-                                            } else {
-                                                self.splits_display
-                                                    .get(i - 1)
-                                                    .and_then(|s| s.last_time_for(method))
-                                                    .unwrap_or(Duration::zero())
-                                            };
+                                            let prev = self.time_before_split(i, method);
 
                                             let relative = last - prev;
 
                                             let relative_time_text =
                                                 self.format_duration(relative, 3);
 
-                                            if options.show_last_relative_time {
+                                            if settings.show_last_relative_time {
                                                 ui.label(
                                                     RichText::new(relative_time_text)
                                                         .size(font_sizes.split)
@@ -158,9 +277,11 @@ impl AppState {
                                                 );
                                             }
 
-                                            if let Some(comparison) =
-                                                split.comparison_time(&selected_comparison, method)
-                                                && comparison.num_milliseconds() > 0
+                                            if let Some(comparison) = self.combined_comparison_time(
+                                                i,
+                                                &selected_comparison,
+                                                method,
+                                            ) && comparison.num_milliseconds() > 0
                                             {
                                                 let diff = relative - comparison;
                                                 if !is_gold_style || diff != Duration::zero() {
@@ -187,18 +308,11 @@ impl AppState {
                                             }
                                             // Show relative time if applicable
                                         } else if is_current
-                                            && options.show_relative_times
+                                            && settings.show_relative_times
                                             && self.timer.state == TimerState::Running
                                             && self.timer.current_time() >= Duration::zero()
                                         {
-                                            let start_of_split = if i == 0 {
-                                                Duration::zero()
-                                            } else {
-                                                self.splits_display
-                                                    .get(i - 1)
-                                                    .and_then(|s| s.last_time_for(method))
-                                                    .unwrap_or(Duration::zero())
-                                            };
+                                            let start_of_split = self.time_before_split(i, method);
                                             let current_time = match method {
                                                 TimingMethod::RealTime => self.timer.current_time(),
                                                 TimingMethod::GameTime => {
@@ -216,9 +330,11 @@ impl AppState {
 
                                             let threshold = Duration::seconds(5);
 
-                                            if let Some(comparison) =
-                                                split.comparison_time(&selected_comparison, method)
-                                                && comparison.num_milliseconds() > 0
+                                            if let Some(comparison) = self.combined_comparison_time(
+                                                i,
+                                                &selected_comparison,
+                                                method,
+                                            ) && comparison.num_milliseconds() > 0
                                             {
                                                 let diff = live_relative - comparison;
                                                 if diff >= -threshold {
@@ -252,6 +368,25 @@ impl AppState {
                                 );
                             });
 
+                            let can_practice = self.timer.state == TimerState::NotStarted;
+                            ui.interact(
+                                row.response.rect,
+                                ui.id().with(("split_row", i)),
+                                egui::Sense::click(),
+                            )
+                            .context_menu(|ui| {
+                                if ui
+                                    .add_enabled(
+                                        can_practice,
+                                        egui::Button::new("Practice from here"),
+                                    )
+                                    .clicked()
+                                {
+                                    self.start_practice(i);
+                                    ui.close();
+                                }
+                            });
+
                             if is_current {
                                 row.response.scroll_to_me(Some(egui::Align::Center));
 
@@ -287,4 +422,73 @@ impl AppState {
                 }
             });
     }
+
+    /// A section's header row: its name with a fold marker, and the
+    /// section's time (or cumulative time, like the split rows) and delta.
+    fn draw_section_header(
+        &self,
+        ui: &mut egui::Ui,
+        section: &SplitSection,
+        collapsed: bool,
+        settings: &SplitsSettings,
+        font_sizes: &FontSizes,
+        top_space: f32,
+    ) {
+        let colors = &self.layout.colors;
+        let split_color = Color32::from_hex(&colors.split).unwrap_or(Color32::WHITE);
+        let split_timer_color =
+            Color32::from_hex(&colors.split_timer).unwrap_or(Color32::from_rgb(0, 0, 255));
+        let pb_positive_color = Color32::from_hex(&colors.pb_positive).unwrap_or(Color32::GREEN);
+        let pb_negative_color = Color32::from_hex(&colors.pb_negative).unwrap_or(Color32::RED);
+
+        let times = self.section_times(section);
+        let marker = if collapsed {
+            egui_phosphor::regular::CARET_RIGHT
+        } else {
+            egui_phosphor::regular::CARET_DOWN
+        };
+        let name = section.name.as_deref().unwrap_or_default();
+
+        ui.add_space(top_space);
+        ui.horizontal(|ui| {
+            ui.set_min_width(ui.available_width());
+            ui.add_space(10.0);
+            ui.label(
+                RichText::new(format!("{marker} {name}"))
+                    .color(split_color)
+                    .strong()
+                    .size(font_sizes.split),
+            );
+
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                let shown = if settings.show_last_relative_time {
+                    times.segment
+                } else {
+                    times.cumulative.or(times.segment)
+                };
+                let text = match shown {
+                    Some(time) => self.format_duration(time, 0),
+                    None => "--:--.---".to_string(),
+                };
+                ui.label(
+                    RichText::new(text)
+                        .size(font_sizes.split)
+                        .color(split_timer_color),
+                );
+
+                if let Some(delta) = times.delta {
+                    let color = if delta < Duration::zero() {
+                        pb_positive_color
+                    } else {
+                        pb_negative_color
+                    };
+                    ui.label(
+                        RichText::new(self.format_duration(delta, 2))
+                            .size(font_sizes.split_gold)
+                            .color(color),
+                    );
+                }
+            });
+        });
+    }
 }
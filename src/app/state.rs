@@ -1,4 +1,4 @@
-use chrono::{Duration, Utc};
+use chrono::{DateTime, Duration, Utc};
 use eframe::glow::Context;
 use eframe::{egui, glow};
 use egui::{FontDefinitions, TextureHandle};
@@ -9,12 +9,14 @@ use std::sync::{Arc, Mutex};
 use crate::config::layout::LayoutConfig;
 use crate::config::load::{AppConfig, config_base_dir};
 use crate::config::shaders::{ShaderBackground, ShaderChannel, load_shader_channels};
+use crate::core::journal::{AttemptJournal, IgtPause, JournalSplit, JournalTimerState};
 #[cfg(unix)]
 use crate::core::server::UICommand;
 use crate::core::split::{
     AttemptHistoryEntry, COMPARISON_BEST_SEGMENTS, COMPARISON_PERSONAL_BEST, Run,
     SegmentHistoryEntry, Split, TimingMethod,
 };
+use crate::core::time_source::{TimeSource, default_clock};
 use crate::core::timer::{Timer, TimerState};
 #[cfg(windows)]
 use crate::core::winserver::UICommand;
@@ -26,6 +28,9 @@ pub struct AppState {
     /// (there's no autosplitter, so this is manual — same as LiveSplit's
     /// manual game-time mode).
     pub igt_timer: Timer,
+    /// Shared by `timer` and `igt_timer` (so the two can't disagree about
+    /// "now") and used to date attempt history. Swap it with `set_clock`.
+    pub clock: Arc<dyn TimeSource>,
     pub run: Run,
     pub layout: LayoutConfig,
     pub current_split: usize,
@@ -62,6 +67,25 @@ pub struct AppState {
     /// Whether the most recently *finished* run beat the existing Personal
     /// Best. Sticky until the next run finishes (or a reset).
     pub last_run_is_pb: bool,
+    /// Wall-clock start of the attempt in progress, for the journal.
+    pub attempt_started_at: Option<DateTime<Utc>>,
+    /// Loads (IGT-only pauses) so far this attempt, for the journal.
+    pub igt_pauses: Vec<IgtPause>,
+    /// Set by `init_game_time` (or any other external game-time command):
+    /// the IGT clock is driven by an autosplitter/load remover, so manual
+    /// load toggles are ignored until the next reset.
+    pub game_time_external: bool,
+    /// An attempt interrupted by a crash, found on disk at startup and
+    /// waiting for `resume_interrupted_attempt` or
+    /// `record_interrupted_attempt_as_reset`.
+    pub pending_journal: Option<AttemptJournal>,
+    /// The split a practice session started at (see `app::practice`), or
+    /// `None` outside practice.
+    pub practice_from: Option<usize>,
+    /// Note and tags given (`note`/`tag` commands) during the attempt in
+    /// progress, saved with it once it finishes or is reset.
+    pub pending_note: Option<String>,
+    pub pending_tags: Vec<String>,
 }
 
 impl Default for AppState {
@@ -80,10 +104,13 @@ impl Default for AppState {
         let layout = LayoutConfig::load_or_default(layout_path.to_str().unwrap());
 
         let splits = run.splits.clone();
+        let clock = default_clock();
+        let pending_journal = AttemptJournal::load(&split_base_path);
 
         Self {
-            timer: Timer::new(),
-            igt_timer: Timer::new(),
+            timer: Timer::with_clock(clock.clone()),
+            igt_timer: Timer::with_clock(clock.clone()),
+            clock,
             run,
             layout,
             current_split: 0,
@@ -108,6 +135,13 @@ impl Default for AppState {
             loaded_fonts: None,
             last_segment_is_gold: false,
             last_run_is_pb: false,
+            attempt_started_at: None,
+            igt_pauses: Vec::new(),
+            game_time_external: false,
+            pending_journal,
+            practice_from: None,
+            pending_note: None,
+            pending_tags: Vec::new(),
         }
     }
 }
@@ -126,9 +160,11 @@ impl AppState {
     /// those directly, rather than pull in `default()`'s I/O for fields they
     /// don't even look at.
     pub fn empty_for_test() -> Self {
+        let clock = default_clock();
         Self {
-            timer: Timer::new(),
-            igt_timer: Timer::new(),
+            timer: Timer::with_clock(clock.clone()),
+            igt_timer: Timer::with_clock(clock.clone()),
+            clock,
             run: Run::default(),
             layout: LayoutConfig::default(),
             current_split: 0,
@@ -153,33 +189,77 @@ impl AppState {
             loaded_fonts: None,
             last_segment_is_gold: false,
             last_run_is_pb: false,
+            attempt_started_at: None,
+            igt_pauses: Vec::new(),
+            game_time_external: false,
+            pending_journal: None,
+            practice_from: None,
+            pending_note: None,
+            pending_tags: Vec::new(),
         }
     }
 }
 
 impl AppState {
+    /// Replaces the time source behind both timers (and attempt dates) —
+    /// e.g. with a `FakeClock` in tests. Only meaningful before a run
+    /// starts: readings from the old and new clocks aren't comparable, so
+    /// any in-progress `start_time` would be nonsense afterwards.
+    pub fn set_clock(&mut self, clock: Arc<dyn TimeSource>) {
+        self.timer.clock = clock.clone();
+        self.igt_timer.clock = clock.clone();
+        self.clock = clock;
+    }
+
     pub fn split(&mut self) {
         match self.timer.state {
-            TimerState::NotStarted => self.start_run(),
+            TimerState::NotStarted => self.start_timers(),
+            TimerState::Running if self.practice_from.is_some() => self.record_practice_split(),
             TimerState::Running => self.record_split(),
             _ => {}
         }
     }
 
-    /// Starts (or resumes, if paused) both the RTA and IGT clocks together
-    /// — the only place that should ever call `Timer::start_with_offset`,
-    /// so the two clocks can't drift out of sync by one being started
-    /// without the other. Safe to call from any timer state.
+    /// Starts (or resumes, if paused) both the RTA and IGT clocks together.
+    /// Safe to call from any timer state; from `NotStarted` it begins a new
+    /// attempt. Resuming a run paused in the middle of a load leaves the
+    /// IGT clock stopped until the load ends.
     pub fn start_timers(&mut self) {
-        let offset = self.run.start_offset.unwrap_or(0);
+        if self.timer.state == TimerState::NotStarted {
+            self.begin_attempt();
+        }
+        self.start_clocks(self.run.start_offset.unwrap_or(0));
+        self.write_journal();
+    }
+
+    /// Apart from `end_load` resuming the IGT clock after a load, the only
+    /// place that should ever call `Timer::start_with_offset`, so the two
+    /// clocks can't drift out of sync by one being started without the
+    /// other. Used by `start_timers` and `start_practice`.
+    pub fn start_clocks(&mut self, offset: i64) {
+        let loading = self.timer.is_paused() && self.is_loading();
         self.timer.start_with_offset(offset);
-        self.igt_timer.start_with_offset(offset);
+        if !loading {
+            self.igt_timer.start_with_offset(offset);
+        }
     }
 
-    fn start_run(&mut self) {
+    /// Sets up a new attempt. It's numbered right away (`Run::attempts` is
+    /// also the in-progress attempt's `run_index`), so every segment it
+    /// records and its eventual attempt-history entry — finished or reset —
+    /// share the same index. Starting over an interrupted attempt that was
+    /// never resolved records it as a reset, rather than losing it to this
+    /// attempt's journal.
+    fn begin_attempt(&mut self) {
+        if self.pending_journal.is_some() {
+            self.record_interrupted_attempt_as_reset();
+        }
         self.splits_backup = self.run.splits.clone();
-        self.start_timers();
+        self.run.attempts += 1;
         self.current_split = 0;
+        self.igt_pauses.clear();
+        let offset = Duration::seconds(self.run.start_offset.unwrap_or(0));
+        self.attempt_started_at = Some(self.clock.wall_now() + offset);
     }
 
     /// Pauses both clocks together (the whole run is on hold, as opposed to
@@ -187,6 +267,7 @@ impl AppState {
     pub fn pause_timers(&mut self) {
         self.timer.pause();
         self.igt_timer.pause();
+        self.write_journal();
     }
 
     /// Freezes both clocks for good — unlike `pause_timers`, this can't be
@@ -195,6 +276,7 @@ impl AppState {
     fn end_run(&mut self) {
         self.timer.end();
         self.igt_timer.end();
+        AttemptJournal::remove(&self.split_base_path);
     }
 
     fn record_split(&mut self) {
@@ -226,24 +308,191 @@ impl AppState {
         self.update_page();
         self.save_history();
         self.update_comparisons();
+        if self.current_split >= self.splits_display.len() {
+            // The finished attempt is now the latest run, and may be a
+            // new PB or best split time.
+            self.run.generate_comparisons();
+        }
+        self.write_journal();
+    }
+
+    /// Moves past the current split without recording a time for it — for a
+    /// missed trigger, so the segment isn't split late and poison history.
+    /// The skipped split's `last_time` stays empty; the next split recorded
+    /// spans both and is stored as a combined segment (see
+    /// `SegmentHistoryEntry::combined`). The final split can't be skipped,
+    /// since the run would have no end time.
+    pub fn skip_split(&mut self) {
+        if self.timer.state != TimerState::Running
+            || self.current_split + 1 >= self.splits_display.len()
+        {
+            return;
+        }
+
+        if let Some(split) = self.splits_display.get_mut(self.current_split) {
+            split.last_time = None;
+            split.last_time_game = None;
+        }
+
+        self.current_split += 1;
+        self.last_segment_is_gold = false;
+        self.update_page();
+        self.write_journal();
+    }
+
+    /// Whether any split this attempt has already passed was skipped rather
+    /// than split. Such a run can't become the Personal Best comparison:
+    /// there'd be no per-split time to measure future runs against at the
+    /// skipped split.
+    pub fn attempt_has_skipped_splits(&self) -> bool {
+        self.splits_display
+            .iter()
+            .take(self.current_split)
+            .any(|s| s.last_time.is_none())
+    }
+
+    /// Time-from-start of the latest split before `index` that was actually
+    /// reached this attempt (skipped splits have none), or zero if there
+    /// isn't one — where the segment ending at `index` began.
+    pub fn time_before_split(&self, index: usize, method: TimingMethod) -> Duration {
+        self.splits_display
+            .iter()
+            .take(index)
+            .rev()
+            .find_map(|s| s.last_time_for(method))
+            .unwrap_or_else(Duration::zero)
+    }
+
+    /// `comparison`'s segment time for split `index`, plus those of any
+    /// skipped splits directly before it — what this attempt's (possibly
+    /// combined) segment ending at `index` should be measured against.
+    /// `None` if any of those splits has no such comparison.
+    pub fn combined_comparison_time(
+        &self,
+        index: usize,
+        comparison: &str,
+        method: TimingMethod,
+    ) -> Option<Duration> {
+        // Only a segment that actually ends here (reached, or in progress)
+        // can span earlier skips — a skipped or future split stands alone.
+        let ends_here = index == self.current_split
+            || (index < self.current_split
+                && self
+                    .splits_display
+                    .get(index)
+                    .is_some_and(|s| s.last_time_for(method).is_some()));
+
+        let mut start = index;
+        if ends_here {
+            while start > 0
+                && self
+                    .splits_display
+                    .get(start - 1)
+                    .is_some_and(|s| s.last_time_for(method).is_none())
+            {
+                start -= 1;
+            }
+        }
+
+        self.run
+            .splits
+            .get(start..=index)?
+            .iter()
+            .map(|s| s.comparison_time(comparison, method))
+            .sum()
     }
 
     /// Toggles the IGT clock only, representing "a load is happening" —
-    /// there's no autosplitter, so this is driven by a hotkey.
+    /// driven by a hotkey. Ignored once game time is externally controlled
+    /// (see `init_game_time`), where a stray toggle would fight the
+    /// autosplitter.
     pub fn toggle_igt_pause(&mut self) {
-        if self.timer.state != TimerState::Running {
+        if self.timer.state != TimerState::Running || self.game_time_external {
+            return;
+        }
+        if self.is_loading() {
+            self.end_load();
+        } else {
+            self.begin_load();
+        }
+    }
+
+    /// Whether the IGT clock is stopped for a load right now.
+    pub fn is_loading(&self) -> bool {
+        self.igt_pauses.last().is_some_and(|p| p.end_ms.is_none())
+    }
+
+    fn begin_load(&mut self) {
+        if self.is_loading() {
             return;
         }
-        if self.igt_timer.is_running() {
-            self.igt_timer.pause();
-        } else if self.igt_timer.is_paused() {
+        self.igt_timer.pause();
+        self.igt_pauses.push(IgtPause {
+            start_ms: self.timer.current_time().num_milliseconds(),
+            end_ms: None,
+        });
+        self.write_journal();
+    }
+
+    /// Ends the current load. The IGT clock only restarts if the run itself
+    /// isn't paused — otherwise `start_timers` picks it up on resume.
+    fn end_load(&mut self) {
+        let at = self.timer.current_time().num_milliseconds();
+        let Some(pause) = self.igt_pauses.last_mut().filter(|p| p.end_ms.is_none()) else {
+            return;
+        };
+        pause.end_ms = Some(at);
+        if self.timer.is_running() {
             self.igt_timer.start_with_offset(0);
         }
+        self.write_journal();
+    }
+
+    /// Hands the IGT clock over to an external load remover or
+    /// autosplitter for the rest of this attempt (or the next one, if sent
+    /// before the start). The current game time is kept; from here on only
+    /// `set_game_time`, `pause_game_time` and `resume_game_time` move it.
+    pub fn init_game_time(&mut self) {
+        self.game_time_external = true;
+    }
+
+    /// Overwrites the game time with an absolute value, e.g. the game's own
+    /// IGT counter, leaving the IGT clock running or stopped as it was.
+    /// Unlike the other game-time commands this isn't journaled, as it may
+    /// arrive on every poll; the next split or pause catches it up.
+    pub fn set_game_time(&mut self, time: Duration) {
+        if !matches!(self.timer.state, TimerState::Running | TimerState::Paused) {
+            return;
+        }
+        self.game_time_external = true;
+        let running = self.igt_timer.is_running();
+        self.igt_timer.restore(time, running);
+    }
+
+    /// Stops the IGT clock for a load. Unlike `toggle_igt_pause` this is
+    /// idempotent, so a repeated or missed message can't flip it the wrong
+    /// way.
+    pub fn pause_game_time(&mut self) {
+        if !matches!(self.timer.state, TimerState::Running | TimerState::Paused) {
+            return;
+        }
+        self.game_time_external = true;
+        self.begin_load();
+    }
+
+    /// Ends a load started by `pause_game_time`. Idempotent.
+    pub fn resume_game_time(&mut self) {
+        if !matches!(self.timer.state, TimerState::Running | TimerState::Paused) {
+            return;
+        }
+        self.game_time_external = true;
+        self.end_load();
     }
 
     /// Switches to the next available comparison (Personal Best -> Best
-    /// Segments -> Average Segments -> Median Segments -> any custom ones
-    /// -> back to Personal Best), wrapping around. Reachable from the timer
+    /// Segments -> Average/Median/Worst Segments -> Latest Run -> Best
+    /// Split Times -> Balanced PB -> any custom ones -> back to Personal
+    /// Best), wrapping around. Reachable from the timer
     /// itself (click on the delta label, or a hotkey) instead of only from
     /// the config app's split editor.
     pub fn cycle_comparison(&mut self) {
@@ -264,18 +513,20 @@ impl AppState {
 
     fn save_history(&mut self) {
         if self.current_split >= self.splits_display.len() {
-            self.run.attempts += 1;
-
             let real_time = self.splits_display.last().and_then(|s| s.last_time);
             let game_time = self.splits_display.last().and_then(|s| s.last_time_game);
 
-            self.run.attempt_history.push(AttemptHistoryEntry {
+            let attempt = AttemptHistoryEntry {
                 run_index: self.run.attempts,
                 real_time,
                 game_time,
                 ended: true,
-                date: Some(Utc::now()),
-            });
+                date: Some(self.clock.wall_now()),
+                reset_split_index: None,
+                note: self.pending_note.take(),
+                tags: std::mem::take(&mut self.pending_tags),
+            };
+            self.run.attempt_history.push(attempt.clone());
 
             let method = self.run.timing_method;
             let pb_total_time = self.run.comparison_total(COMPARISON_PERSONAL_BEST, method);
@@ -285,19 +536,20 @@ impl AppState {
                 TimingMethod::GameTime => game_time,
             };
 
-            let is_new_pb = match (current_total, pb_total_time) {
-                (Some(current), Some(existing)) => current < existing,
-                (Some(_), None) => true,
-                (None, _) => false,
-            };
+            let is_new_pb = !self.attempt_has_skipped_splits()
+                && match (current_total, pb_total_time) {
+                    (Some(current), Some(existing)) => current < existing,
+                    (Some(_), None) => true,
+                    (None, _) => false,
+                };
 
+            // The note and tags live on the `attempt_history` copy only, so
+            // later edits to them have one place to go.
             if is_new_pb {
                 self.run.pb_history.push(AttemptHistoryEntry {
-                    run_index: self.run.attempts,
-                    real_time,
-                    game_time,
-                    ended: true,
-                    date: Some(Utc::now()),
+                    note: None,
+                    tags: Vec::new(),
+                    ..attempt
                 });
             }
 
@@ -307,7 +559,8 @@ impl AppState {
         }
     }
 
-    fn update_page(&mut self) {
+    /// Turns to the page holding the current split.
+    pub fn update_page(&mut self) {
         let next_page = self.current_split / self.splits_per_page;
         let max_page = (self.splits_display.len().saturating_sub(1)) / self.splits_per_page;
         self.current_page = next_page.min(max_page);
@@ -332,25 +585,29 @@ impl AppState {
         {
             let i = self.current_split - 1;
             if let Some(current_time) = current.last_time {
-                let prev_time = if i == 0 {
-                    Duration::zero()
-                } else {
-                    self.splits_display[i - 1]
-                        .last_time
-                        .unwrap_or(Duration::zero())
-                };
-                let relative_real = current_time - prev_time;
-
-                let relative_game = current.last_time_game.map(|current_game| {
-                    let prev_game = if i == 0 {
-                        Duration::zero()
-                    } else {
-                        self.splits_display[i - 1]
-                            .last_time_game
-                            .unwrap_or(Duration::zero())
-                    };
-                    current_game - prev_game
-                });
+                let relative_real =
+                    current_time - self.time_before_split(i, TimingMethod::RealTime);
+                let relative_game = current
+                    .last_time_game
+                    .map(|g| g - self.time_before_split(i, TimingMethod::GameTime));
+
+                // Splits skipped since the last one actually reached: each
+                // gets an empty entry, and this segment (which spans them
+                // all) is stored as a combined one.
+                let skipped: Vec<usize> = (0..i)
+                    .rev()
+                    .take_while(|&j| self.splits_display[j].last_time.is_none())
+                    .collect();
+                let combined = !skipped.is_empty();
+
+                for j in skipped.into_iter().rev() {
+                    self.run.splits[j]
+                        .segment_history
+                        .push(SegmentHistoryEntry {
+                            run_index: self.run.attempts,
+                            ..Default::default()
+                        });
+                }
 
                 let target = &mut self.run.splits[i];
 
@@ -358,28 +615,33 @@ impl AppState {
                     run_index: self.run.attempts,
                     real_time: Some(relative_real),
                     game_time: relative_game,
+                    combined,
                 });
 
-                let best = target
-                    .comparisons
-                    .entry(COMPARISON_BEST_SEGMENTS.to_string())
-                    .or_default();
-                let prev_best = best.get(method);
+                if combined {
+                    self.last_segment_is_gold = false;
+                } else {
+                    let best = target
+                        .comparisons
+                        .entry(COMPARISON_BEST_SEGMENTS.to_string())
+                        .or_default();
+                    let prev_best = best.get(method);
 
-                self.last_segment_is_gold = match method {
-                    TimingMethod::RealTime => prev_best.is_none_or(|gold| relative_real < gold),
-                    TimingMethod::GameTime => {
-                        relative_game.is_some_and(|rg| prev_best.is_none_or(|gold| rg < gold))
-                    }
-                };
+                    self.last_segment_is_gold = match method {
+                        TimingMethod::RealTime => prev_best.is_none_or(|gold| relative_real < gold),
+                        TimingMethod::GameTime => {
+                            relative_game.is_some_and(|rg| prev_best.is_none_or(|gold| rg < gold))
+                        }
+                    };
 
-                if best.real_time.is_none_or(|gold| relative_real < gold) {
-                    best.real_time = Some(relative_real);
-                }
-                if let Some(relative_game) = relative_game
-                    && best.game_time.is_none_or(|gold| relative_game < gold)
-                {
-                    best.game_time = Some(relative_game);
+                    if best.real_time.is_none_or(|gold| relative_real < gold) {
+                        best.real_time = Some(relative_real);
+                    }
+                    if let Some(relative_game) = relative_game
+                        && best.game_time.is_none_or(|gold| relative_game < gold)
+                    {
+                        best.game_time = Some(relative_game);
+                    }
                 }
             }
         }
@@ -392,11 +654,12 @@ impl AppState {
 
             let existing_pb_total = self.run.comparison_total(COMPARISON_PERSONAL_BEST, method);
 
-            let is_new_pb = match (current_total, existing_pb_total) {
-                (Some(current), Some(existing)) => current < existing,
-                (Some(_), None) => true,
-                (None, _) => false,
-            };
+            let is_new_pb = !self.attempt_has_skipped_splits()
+                && match (current_total, existing_pb_total) {
+                    (Some(current), Some(existing)) => current < existing,
+                    (Some(_), None) => true,
+                    (None, _) => false,
+                };
             self.last_run_is_pb = is_new_pb;
 
             if is_new_pb && self.run.auto_update_pb {
@@ -448,6 +711,8 @@ impl AppState {
     }
 
     pub fn reset_splits(&mut self) {
+        self.record_reset_attempt();
+
         // `sync_splits` below reloads `splits_display` from disk wholesale,
         // so there's nothing to selectively preserve here — just clear the
         // in-progress attempt times.
@@ -461,11 +726,116 @@ impl AppState {
         self.igt_timer.reset();
         self.last_segment_is_gold = false;
         self.last_run_is_pb = false;
+        self.attempt_started_at = None;
+        self.igt_pauses.clear();
+        self.game_time_external = false;
+        self.practice_from = None;
+        self.pending_note = None;
+        self.pending_tags.clear();
+        // An unresolved interrupted attempt's journal must survive until
+        // it's resumed or recorded.
+        if self.pending_journal.is_none() {
+            AttemptJournal::remove(&self.split_base_path);
+        }
         self.sync_splits();
     }
 
+    /// Logs the attempt being reset (if one was started and hasn't already
+    /// been logged as finished) to `attempt_history`, with how far it got
+    /// and for how long, and persists it before `reset_splits` reloads the
+    /// run from disk.
+    fn record_reset_attempt(&mut self) {
+        if self.practice_from.is_some()
+            || !matches!(self.timer.state, TimerState::Running | TimerState::Paused)
+        {
+            return;
+        }
+
+        self.run.attempt_history.push(AttemptHistoryEntry {
+            run_index: self.run.attempts,
+            real_time: Some(self.timer.current_time().max(Duration::zero())),
+            game_time: Some(self.igt_timer.current_time().max(Duration::zero())),
+            ended: false,
+            date: Some(self.clock.wall_now()),
+            reset_split_index: Some(self.current_split),
+            note: self.pending_note.take(),
+            tags: std::mem::take(&mut self.pending_tags),
+        });
+
+        if let Err(e) = self.save() {
+            eprintln!("Error saving reset attempt: {}", e);
+        }
+    }
+
+    /// Adds `text` to the note of the attempt in progress, or if none is,
+    /// of the last one logged (saving it right away). Successive notes go
+    /// on separate lines.
+    pub fn note_attempt(&mut self, text: &str) -> Result<(), String> {
+        let note = self.annotated_attempt()?.0;
+        *note = Some(match note.take() {
+            Some(existing) => format!("{existing}\n{text}"),
+            None => text.to_string(),
+        });
+        self.save_annotation()
+    }
+
+    /// Tags the attempt in progress, or if none is, the last one logged.
+    pub fn tag_attempt(&mut self, tag: &str) -> Result<(), String> {
+        let tags = self.annotated_attempt()?.1;
+        if !tags.iter().any(|t| t == tag) {
+            tags.push(tag.to_string());
+        }
+        self.save_annotation()
+    }
+
+    /// The note and tags `note_attempt`/`tag_attempt` should change.
+    fn annotated_attempt(&mut self) -> Result<(&mut Option<String>, &mut Vec<String>), String> {
+        if self.practice_from.is_some() {
+            return Err("practice isn't an attempt".to_string());
+        }
+        if matches!(self.timer.state, TimerState::Running | TimerState::Paused) {
+            return Ok((&mut self.pending_note, &mut self.pending_tags));
+        }
+        self.run
+            .attempt_history
+            .iter_mut()
+            .max_by_key(|a| a.run_index)
+            .map(|a| (&mut a.note, &mut a.tags))
+            .ok_or_else(|| "no attempt recorded yet".to_string())
+    }
+
+    /// Saves a note or tag just put on a logged attempt; pending ones wait
+    /// for the attempt to be logged.
+    fn save_annotation(&mut self) -> Result<(), String> {
+        if matches!(self.timer.state, TimerState::Running | TimerState::Paused) {
+            return Ok(());
+        }
+        self.save()
+            .map_err(|e| format!("saving the attempt failed: {e}"))
+    }
+
+    /// Saves attempt `run_index` as a comparison to race (see
+    /// `Run::add_attempt_comparison`). Not during a run, whose comparisons
+    /// are already in use.
+    pub fn add_attempt_comparison(&mut self, run_index: u32) -> Result<String, String> {
+        if matches!(self.timer.state, TimerState::Running | TimerState::Paused) {
+            return Err("can't add a comparison during a run".to_string());
+        }
+        let name = self.run.add_attempt_comparison(run_index)?;
+        for (display, split) in self.splits_display.iter_mut().zip(&self.run.splits) {
+            if let Some(time) = split.comparisons.get(&name) {
+                display.comparisons.insert(name.clone(), *time);
+            }
+        }
+        self.save()
+            .map_err(|e| format!("saving the comparison failed: {e}"))?;
+        Ok(name)
+    }
+
+    /// Steps back one split, restoring that split's comparisons. Not
+    /// available in practice, where nothing was added to undo.
     pub fn undo_split(&mut self) {
-        if self.current_split > 0 {
+        if self.current_split > 0 && self.practice_from.is_none() {
             self.current_split -= 1;
             self.last_segment_is_gold = false;
             self.last_run_is_pb = false;
@@ -497,9 +867,117 @@ impl AppState {
             if let Err(e) = saved_run.save_to_file(path.to_str().unwrap()) {
                 eprintln!("Error saving after undo split: {}", e);
             }
+            self.write_journal();
+        }
+    }
+
+    /// Snapshots the attempt in progress to `core::journal`, so a crash
+    /// can't lose it. A no-op unless the timer is running or paused, and
+    /// in practice, which isn't an attempt.
+    fn write_journal(&self) {
+        if self.practice_from.is_some() {
+            return;
+        }
+        let timer_state = match self.timer.state {
+            TimerState::Running => JournalTimerState::Running,
+            TimerState::Paused => JournalTimerState::Paused,
+            _ => return,
+        };
+
+        let journal = AttemptJournal {
+            run_index: self.run.attempts,
+            timer_state,
+            started_at: self.attempt_started_at,
+            written_at: Some(self.clock.wall_now()),
+            real_elapsed_ms: self.timer.current_time().num_milliseconds(),
+            game_elapsed_ms: self.igt_timer.current_time().num_milliseconds(),
+            current_split: self.current_split,
+            splits: self
+                .splits_display
+                .iter()
+                .map(|s| JournalSplit {
+                    real_time: s.last_time,
+                    game_time: s.last_time_game,
+                })
+                .collect(),
+            igt_pauses: self.igt_pauses.clone(),
+            game_time_external: self.game_time_external,
+        };
+
+        if let Err(e) = journal.save(&self.split_base_path) {
+            eprintln!("Error writing attempt journal: {}", e);
         }
     }
 
+    /// Picks `pending_journal`'s attempt back up where it was interrupted.
+    /// A running timer is credited with the time the app was down, as that
+    /// still passed in real time; a paused one resumes paused. Undo can't
+    /// reach back past the resume point. A journal that no longer fits the
+    /// run (its splits were edited meanwhile) is recorded as a reset
+    /// instead.
+    pub fn resume_interrupted_attempt(&mut self) {
+        let Some(journal) = self.pending_journal.take() else {
+            return;
+        };
+        if self.timer.state != TimerState::NotStarted
+            || journal.splits.len() != self.splits_display.len()
+            || journal.current_split >= self.splits_display.len()
+        {
+            eprintln!("Interrupted attempt doesn't match this run, recording it as a reset");
+            self.pending_journal = Some(journal);
+            self.record_interrupted_attempt_as_reset();
+            return;
+        }
+
+        for (display, saved) in self.splits_display.iter_mut().zip(&journal.splits) {
+            display.last_time = saved.real_time;
+            display.last_time_game = saved.game_time;
+        }
+        self.run.attempts = self.run.attempts.max(journal.run_index);
+        self.splits_backup = self.run.splits.clone();
+        self.current_split = journal.current_split;
+        self.attempt_started_at = journal.started_at;
+        self.igt_pauses = journal.igt_pauses.clone();
+        self.game_time_external = journal.game_time_external;
+        self.last_segment_is_gold = false;
+        self.last_run_is_pb = false;
+
+        let now = self.clock.wall_now();
+        let running = journal.timer_state == JournalTimerState::Running;
+        self.timer.restore(journal.real_elapsed_at(now), running);
+        self.igt_timer.restore(
+            journal.game_elapsed_at(now),
+            running && !journal.igt_paused(),
+        );
+
+        self.update_page();
+        self.write_journal();
+    }
+
+    /// Logs `pending_journal`'s attempt to `attempt_history` as reset where
+    /// it was interrupted, and discards the journal.
+    pub fn record_interrupted_attempt_as_reset(&mut self) {
+        let Some(journal) = self.pending_journal.take() else {
+            return;
+        };
+
+        self.run.attempts = self.run.attempts.max(journal.run_index);
+        self.run.attempt_history.push(AttemptHistoryEntry {
+            run_index: journal.run_index,
+            real_time: Some(Duration::milliseconds(journal.real_elapsed_ms).max(Duration::zero())),
+            game_time: Some(Duration::milliseconds(journal.game_elapsed_ms).max(Duration::zero())),
+            ended: false,
+            date: journal.written_at,
+            reset_split_index: Some(journal.current_split),
+            ..Default::default()
+        });
+
+        if let Err(e) = self.save() {
+            eprintln!("Error saving interrupted attempt: {}", e);
+        }
+        AttemptJournal::remove(&self.split_base_path);
+    }
+
     fn sync_splits(&mut self) {
         let run =
             Run::load_from_file(self.split_base_path.join("split.json").to_str().unwrap()).unwrap();
@@ -523,6 +1001,7 @@ impl AppState {
         {
             saved_split.comparisons = current_split.comparisons.clone();
             saved_split.segment_history = current_split.segment_history.clone();
+            saved_split.practice_history = current_split.practice_history.clone();
         }
 
         saved_run.save_to_file(path.to_str().unwrap())
@@ -553,22 +1032,17 @@ impl AppState {
             let Some(actual_total) = self.splits_display[i].last_time_for(method) else {
                 continue;
             };
-            let prev_actual = if i == 0 {
-                Duration::zero()
-            } else {
-                self.splits_display[i - 1]
-                    .last_time_for(method)
-                    .unwrap_or(Duration::zero())
-            };
+            let prev_actual = self.time_before_split(i, method);
 
-            if let Some(cmp_segment) = self.run.splits[i].comparison_time(comparison, method) {
+            if let Some(cmp_segment) = self.combined_comparison_time(i, comparison, method) {
                 delta += (actual_total - prev_actual) - cmp_segment;
                 series.push((i, delta.as_seconds_f32()));
             }
         }
 
-        if let Some(split) = self.run.splits.get(self.current_split)
-            && let Some(cmp_segment) = split.comparison_time(comparison, method)
+        if self.current_split < self.run.splits.len()
+            && let Some(cmp_segment) =
+                self.combined_comparison_time(self.current_split, comparison, method)
         {
             let live_segment = Duration::milliseconds((elapsed_split_time * 1000.0) as i64);
             let live_total = delta + live_segment - cmp_segment;
@@ -597,10 +1071,8 @@ impl AppState {
         let elapsed_time = self.timer.current_time().as_seconds_f32();
         let last_split_time =
             if self.current_split > 0 && self.current_split < self.run.splits.len() {
-                self.splits_display[self.current_split - 1]
-                    .last_time
-                    .map(|t| t.as_seconds_f32())
-                    .unwrap_or(0.0)
+                self.time_before_split(self.current_split, TimingMethod::RealTime)
+                    .as_seconds_f32()
             } else {
                 0.0
             };
@@ -651,6 +1123,7 @@ impl AppState {
             self.current_page = 0;
             self.splits_per_page = self.run.splits_per_page.unwrap_or(5);
             self.update_page();
+            self.pending_journal = AttemptJournal::load(&split_base_path);
 
             // Icon textures are cached by `icon_path` string (see
             // `get_or_load_texture`), and re-importing a `.lss` in the
@@ -1,7 +1,7 @@
 use crate::app::AppWrapper;
 use crate::app::resize::draw_resize_borders;
 use crate::app::state::AppState;
-use crate::config::layout::SectionKind;
+use crate::config::layout::Component;
 use crate::config::load::config_base_dir;
 use crate::config::shaders::{ShaderBackground, load_shader_channels};
 #[cfg(unix)]
@@ -25,7 +25,6 @@ impl AppState {
             self.pause_timers();
         }
         if ctx.input(|i| i.key_pressed(egui::Key::R)) {
-            self.timer.reset();
             self.reset_splits();
         }
         if ctx.input(|i| i.key_pressed(egui::Key::Enter)) {
@@ -44,6 +43,10 @@ impl AppState {
             self.undo_split();
         }
 
+        if ctx.input(|i| i.key_pressed(egui::Key::K)) {
+            self.skip_split();
+        }
+
         if ctx.input(|i| i.key_pressed(egui::Key::L)) {
             self.toggle_igt_pause();
         }
@@ -70,41 +73,48 @@ impl AppState {
     pub fn draw_ui(&mut self, ui: &mut egui::Ui) {
         self.draw_drag_handle(ui);
 
-        let order = self.layout.options.section_order.clone();
-        let splits_pos = order.iter().position(|s| *s == SectionKind::Splits);
+        let components: Vec<(usize, Component)> =
+            self.layout.components.iter().cloned().enumerate().collect();
+        let splits_pos = components
+            .iter()
+            .position(|(_, c)| matches!(c, Component::Splits(_)));
         let (before, after) = match splits_pos {
-            Some(pos) => (&order[..pos], &order[pos + 1..]),
-            None => (&order[..], &[][..]),
+            Some(pos) => (&components[..pos], &components[pos + 1..]),
+            None => (&components[..], &[][..]),
         };
 
-        for section in before {
-            self.draw_section(ui, *section, true);
+        for (index, component) in before {
+            self.draw_component(ui, *index, component, true);
         }
-        for section in after.iter().rev() {
-            self.draw_section(ui, *section, false);
+        for (index, component) in after.iter().rev() {
+            self.draw_component(ui, *index, component, false);
         }
-        if splits_pos.is_some() && self.layout.options.show_body {
-            self.draw_splits_panel(ui);
+        if let Some(pos) = splits_pos
+            && let (_, Component::Splits(settings)) = &components[pos]
+        {
+            self.draw_splits_panel(ui, settings);
         }
 
         self.draw_help_window(ui.ctx());
+        self.draw_recovery_window(ui.ctx());
     }
 
-    fn draw_section(&mut self, ui: &mut egui::Ui, section: SectionKind, top: bool) {
-        match section {
-            SectionKind::Title => self.draw_title(ui, top),
-            SectionKind::Timer => self.draw_timer(ui, top),
-            SectionKind::Footer => {
-                if self.layout.options.show_footer {
-                    self.draw_footer(ui, top);
-                }
-            }
-            SectionKind::Graph => {
-                if self.layout.options.show_graph {
-                    self.draw_graph(ui, top);
-                }
-            }
-            SectionKind::Splits => {}
+    /// `index` is the component's place in the layout, to tell apart the
+    /// panels of several instances of the same kind.
+    fn draw_component(
+        &mut self,
+        ui: &mut egui::Ui,
+        index: usize,
+        component: &Component,
+        top: bool,
+    ) {
+        match component {
+            Component::Title(settings) => self.draw_title(ui, index, top, settings),
+            Component::Timer(settings) => self.draw_timer(ui, index, top, settings),
+            Component::Footer(settings) => self.draw_footer(ui, index, top, settings),
+            Component::Graph(settings) => self.draw_graph(ui, index, top, settings),
+            // Only the first one is drawn, in the central space.
+            Component::Splits(_) => {}
         }
     }
 }
@@ -1,4 +1,9 @@
-use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::autosplitter::expr::{Expression, Scope, Type, Value};
 
 /// On-disk shape of `autosplitter.json`, sitting next to `split.json`.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -7,6 +12,22 @@ pub struct AutosplitterConfig {
     #[serde(default = "default_poll_interval_ms")]
     pub poll_interval_ms: u64,
     pub watches: Vec<Watch>,
+    /// Actions fired from conditions on several watches at once.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub triggers: Vec<Trigger>,
+    /// When to start the timer, while it isn't running.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start: Option<Compound>,
+    /// When to reset the timer, while it is running or finished.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reset: Option<Compound>,
+    /// One entry per split of the run, in order: only the entry for the
+    /// split the timer is on can fire a `split`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub splits: Vec<SplitTrigger>,
+    /// Values expressions can keep across polls, updated in this order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub variables: Vec<Variable>,
 }
 
 fn default_poll_interval_ms() -> u64 {
@@ -17,8 +38,164 @@ impl AutosplitterConfig {
     pub fn load_from_file(path: &str) -> Result<Self, String> {
         let text =
             std::fs::read_to_string(path).map_err(|e| format!("Failed to read {path}: {e}"))?;
-        serde_json::from_str(&text)
-            .map_err(|e| format!("Invalid autosplitter config in {path}: {e}"))
+        Self::from_json(&text).map_err(|e| format!("Invalid autosplitter config in {path}: {e}"))
+    }
+
+    /// Parses and `validate`s a config.
+    pub fn from_json(text: &str) -> Result<Self, String> {
+        let config: Self = serde_json::from_str(text).map_err(|e| e.to_string())?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// The mistakes serde can't catch: a watch with a condition but no
+    /// action (or the other way round), conditions naming watches that
+    /// don't exist, expressions that don't parse or type-check, and guards
+    /// that can never pass.
+    pub fn validate(&self) -> Result<(), String> {
+        for (i, variable) in self.variables.iter().enumerate() {
+            let name = &variable.name;
+            let is_identifier = name
+                .chars()
+                .next()
+                .is_some_and(|c| c.is_alphabetic() || c == '_')
+                && name.chars().all(|c| c.is_alphanumeric() || c == '_');
+            if !is_identifier || ["old", "current", "true", "false"].contains(&name.as_str()) {
+                return Err(format!("'{name}' can't be used as a variable name"));
+            }
+            if self.watches.iter().any(|w| &w.name == name)
+                || self.variables[..i].iter().any(|v| &v.name == name)
+            {
+                return Err(format!(
+                    "there's more than one watch or variable named '{name}'"
+                ));
+            }
+            let ty = variable
+                .update
+                .check(&|name, old| self.lookup(name, old, Some(i)))
+                .map_err(|e| {
+                    format!(
+                        "variable '{name}': {}",
+                        e.pointing_into(variable.update.source())
+                    )
+                })?;
+            if ty != variable.initial.ty() {
+                return Err(format!(
+                    "variable '{name}' starts as {} but its update gives {ty}",
+                    variable.initial.ty()
+                ));
+            }
+        }
+
+        for watch in &self.watches {
+            watch
+                .value_type
+                .validate()
+                .map_err(|e| format!("watch '{}': {e}", watch.name))?;
+            match (&watch.condition, watch.action) {
+                (Some(_), None) => {
+                    return Err(format!(
+                        "watch '{}' has a condition but no action",
+                        watch.name
+                    ));
+                }
+                (None, Some(_)) => {
+                    return Err(format!(
+                        "watch '{}' has an action but no condition",
+                        watch.name
+                    ));
+                }
+                _ => {}
+            }
+            let ty = watch.value_type.ty();
+            if let Some(condition) = &watch.condition {
+                condition
+                    .check_type(ty)
+                    .map_err(|e| format!("watch '{}': {e}", watch.name))?;
+            }
+            if matches!(watch.action, Some(Action::SetGameTime)) && !ty.is_number() {
+                return Err(format!(
+                    "watch '{}': set_game_time needs a number, but this is {ty}",
+                    watch.name
+                ));
+            }
+            watch
+                .guard
+                .validate()
+                .map_err(|e| format!("watch '{}': {e}", watch.name))?;
+        }
+        for trigger in &self.triggers {
+            self.check_when(&trigger.when, &format!("trigger '{}'", trigger.name))?;
+            if matches!(trigger.action, Action::SetGameTime) {
+                return Err(format!(
+                    "trigger '{}': set_game_time needs a single watch's value, put it on the watch",
+                    trigger.name
+                ));
+            }
+            trigger
+                .guard
+                .validate()
+                .map_err(|e| format!("trigger '{}': {e}", trigger.name))?;
+        }
+        if let Some(start) = &self.start {
+            self.check_when(start, "start")?;
+        }
+        if let Some(reset) = &self.reset {
+            self.check_when(reset, "reset")?;
+        }
+        for (i, split) in self.splits.iter().enumerate() {
+            if let Some(when) = &split.when {
+                self.check_when(when, &format!("split {i} ('{}')", split.name))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn check_when(&self, when: &Compound, what: &str) -> Result<(), String> {
+        match when {
+            Compound::All { all: parts } | Compound::Any { any: parts } => parts
+                .iter()
+                .try_for_each(|part| self.check_when(part, what)),
+            Compound::Not { not } => self.check_when(not, what),
+            Compound::Watch { watch, condition } => {
+                let Some(found) = self.watches.iter().find(|w| &w.name == watch) else {
+                    return Err(format!("{what} uses watch '{watch}', which doesn't exist"));
+                };
+                condition
+                    .check_type(found.value_type.ty())
+                    .map_err(|e| format!("{what}, on watch '{watch}': {e}"))
+            }
+            Compound::Expression(expression) => expression
+                .check_condition(&|name, old| self.lookup(name, old, None))
+                .map_err(|e| format!("{what}: {}", e.pointing_into(expression.source()))),
+        }
+    }
+
+    /// The type of watch or variable `name`, for type-checking an
+    /// expression. While variable `updating` is being updated, it and the
+    /// ones after it only have their `old.` values.
+    pub fn lookup(&self, name: &str, old: bool, updating: Option<usize>) -> Result<Type, String> {
+        if let Some(watch) = self.watches.iter().find(|w| w.name == name) {
+            return Ok(watch.value_type.ty());
+        }
+        let Some(i) = self.variables.iter().position(|v| v.name == name) else {
+            return Err(format!("there's no watch or variable named '{name}'"));
+        };
+        match updating {
+            Some(updating) if !old && i == updating => Err(format!(
+                "'{name}' is what's being worked out here, use old.{name} for its last value"
+            )),
+            Some(updating) if !old && i > updating => Err(format!(
+                "'{name}' is updated after this, so only old.{name} can be read here"
+            )),
+            _ => Ok(self.variables[i].initial.ty()),
+        }
+    }
+
+    /// Whether `start`, `reset` or `splits` are used, which makes the
+    /// autosplitter follow the timer's current split.
+    pub fn has_sequence(&self) -> bool {
+        self.start.is_some() || self.reset.is_some() || !self.splits.is_empty()
     }
 }
 
@@ -65,6 +242,9 @@ pub enum Endian {
     Big,
 }
 
+/// How a watch's bytes are read. Integers and floats are written as just
+/// their name (`"u16"`, `"f32"`); the others take their length:
+/// `{"string": {"len": 16, "encoding": "utf16"}}`, `{"bytes": {"len": 4}}`.
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub enum ValueType {
@@ -76,6 +256,65 @@ pub enum ValueType {
     I16,
     I32,
     I64,
+    F32,
+    F64,
+    /// Fixed-length text, `len` counted in the encoding's units (bytes, or
+    /// two-byte units for UTF-16). It ends early at the first NUL, since
+    /// games pad shorter strings with them.
+    String {
+        len: usize,
+        #[serde(default)]
+        encoding: StringEncoding,
+    },
+    /// Raw bytes, kept as they are.
+    Bytes {
+        len: usize,
+    },
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StringEncoding {
+    /// Anything outside ASCII becomes U+FFFD.
+    Ascii,
+    #[default]
+    Utf8,
+    /// In the watch's `endian`.
+    Utf16,
+}
+
+impl StringEncoding {
+    fn unit(self) -> usize {
+        match self {
+            StringEncoding::Ascii | StringEncoding::Utf8 => 1,
+            StringEncoding::Utf16 => 2,
+        }
+    }
+
+    fn decode(self, bytes: &[u8], endian: Endian) -> String {
+        match self {
+            StringEncoding::Ascii => bytes
+                .iter()
+                .take_while(|&&b| b != 0)
+                .map(|&b| if b.is_ascii() { b as char } else { '\u{FFFD}' })
+                .collect(),
+            StringEncoding::Utf8 => {
+                let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+                String::from_utf8_lossy(&bytes[..end]).into_owned()
+            }
+            StringEncoding::Utf16 => {
+                let units: Vec<u16> = bytes
+                    .chunks_exact(2)
+                    .map(|pair| match endian {
+                        Endian::Little => u16::from_le_bytes([pair[0], pair[1]]),
+                        Endian::Big => u16::from_be_bytes([pair[0], pair[1]]),
+                    })
+                    .take_while(|&unit| unit != 0)
+                    .collect();
+                String::from_utf16_lossy(&units)
+            }
+        }
+    }
 }
 
 impl ValueType {
@@ -83,22 +322,44 @@ impl ValueType {
         match self {
             ValueType::U8 | ValueType::I8 => 1,
             ValueType::U16 | ValueType::I16 => 2,
-            ValueType::U32 | ValueType::I32 => 4,
-            ValueType::U64 | ValueType::I64 => 8,
+            ValueType::U32 | ValueType::I32 | ValueType::F32 => 4,
+            ValueType::U64 | ValueType::I64 | ValueType::F64 => 8,
+            ValueType::String { len, encoding } => len * encoding.unit(),
+            ValueType::Bytes { len } => len,
+        }
+    }
+
+    /// What the decoded value is, to expressions.
+    pub fn ty(self) -> Type {
+        match self {
+            ValueType::F32 | ValueType::F64 => Type::Float,
+            ValueType::String { .. } => Type::Str,
+            ValueType::Bytes { .. } => Type::Bytes,
+            _ => Type::Int,
+        }
+    }
+
+    fn validate(self) -> Result<(), String> {
+        match self {
+            ValueType::String { len: 0, .. } | ValueType::Bytes { len: 0 } => {
+                Err("a string or bytes watch needs a len of at least 1".to_string())
+            }
+            _ => Ok(()),
         }
     }
 
-    /// Decodes the first `self.size()` bytes of `bytes` as this type, widened
-    /// to `i128` so every variant (signed or not, up to 64 bits) fits in one
-    /// return type. Returns `None` if `bytes` is shorter than `self.size()`.
-    pub fn decode(self, bytes: &[u8], endian: Endian) -> Option<i128> {
+    /// Decodes the first `self.size()` bytes of `bytes` as this type.
+    /// Integers are widened to `i128` so every variant (signed or not, up
+    /// to 64 bits) fits in one `Value::Int`, and `f32`s to `f64`. Returns
+    /// `None` if `bytes` is shorter than `self.size()`.
+    pub fn decode(self, bytes: &[u8], endian: Endian) -> Option<Value> {
         let n = self.size();
         if bytes.len() < n {
             return None;
         }
         let b = &bytes[..n];
 
-        Some(match (self, endian) {
+        let int = match (self, endian) {
             (ValueType::U8, _) => b[0] as i128,
             (ValueType::I8, _) => (b[0] as i8) as i128,
             (ValueType::U16, Endian::Little) => u16::from_le_bytes(b.try_into().ok()?) as i128,
@@ -113,7 +374,24 @@ impl ValueType {
             (ValueType::U64, Endian::Big) => u64::from_be_bytes(b.try_into().ok()?) as i128,
             (ValueType::I64, Endian::Little) => i64::from_le_bytes(b.try_into().ok()?) as i128,
             (ValueType::I64, Endian::Big) => i64::from_be_bytes(b.try_into().ok()?) as i128,
-        })
+            (ValueType::F32, Endian::Little) => {
+                return Some(Value::Float(f32::from_le_bytes(b.try_into().ok()?) as f64));
+            }
+            (ValueType::F32, Endian::Big) => {
+                return Some(Value::Float(f32::from_be_bytes(b.try_into().ok()?) as f64));
+            }
+            (ValueType::F64, Endian::Little) => {
+                return Some(Value::Float(f64::from_le_bytes(b.try_into().ok()?)));
+            }
+            (ValueType::F64, Endian::Big) => {
+                return Some(Value::Float(f64::from_be_bytes(b.try_into().ok()?)));
+            }
+            (ValueType::String { encoding, .. }, _) => {
+                return Some(Value::Str(encoding.decode(b, endian)));
+            }
+            (ValueType::Bytes { .. }, _) => return Some(Value::Bytes(b.to_vec())),
+        };
+        Some(Value::Int(int))
     }
 }
 
@@ -145,8 +423,23 @@ pub struct Watch {
     pub value_type: ValueType,
     #[serde(default)]
     pub endian: Endian,
-    pub condition: Condition,
-    pub action: Action,
+    /// With `action`, fires on its own. A watch with neither is only read,
+    /// for `triggers` to look at.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub condition: Option<Condition>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub action: Option<Action>,
+    /// `set_game_time` only: milliseconds per unit of the watched value —
+    /// `1` for a millisecond counter, `1000` for seconds, `16.6667` for a
+    /// 60 fps frame counter.
+    #[serde(default = "default_ms_per_unit")]
+    pub ms_per_unit: f64,
+    #[serde(flatten)]
+    pub guard: SplitGuard,
+}
+
+fn default_ms_per_unit() -> f64 {
+    1.0
 }
 
 impl Watch {
@@ -157,6 +450,21 @@ impl Watch {
     pub fn pointer_path_values(&self) -> Result<Vec<u64>, String> {
         self.pointer_path.iter().map(|s| parse_hex_u64(s)).collect()
     }
+
+    /// The command line to send when this watch fires on `current` —
+    /// `action`'s command, plus the value converted to milliseconds for
+    /// `set_game_time`. `None` for a watch without an action, or
+    /// `set_game_time` on something that isn't a number.
+    pub fn command(&self, current: &Value) -> Option<String> {
+        Some(match self.action? {
+            Action::SetGameTime => format!(
+                "{} {}",
+                Action::SetGameTime.as_command(),
+                (current.as_f64()? * self.ms_per_unit).round() as i64
+            ),
+            action => action.as_command().to_string(),
+        })
+    }
 }
 
 fn parse_hex_u64(raw: &str) -> Result<u64, String> {
@@ -168,16 +476,109 @@ fn parse_hex_u64(raw: &str) -> Result<u64, String> {
     u64::from_str_radix(s, 16).map_err(|e| format!("invalid hex value '{raw}': {e}"))
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+// `value`s go through serde's internal buffering (the `kind` tag, and
+// `#[serde(flatten)]` in `Compound`), which has no 128-bit integers, so they
+// are read as whatever JSON integer they are and widened here. A `"0x12"`
+// string is taken as hex, since that's how addresses and RAM maps write them.
+fn wide_int<'de, D: Deserializer<'de>>(deserializer: D) -> Result<i128, D::Error> {
+    struct WideInt;
+
+    impl serde::de::Visitor<'_> for WideInt {
+        type Value = i128;
+
+        fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            f.write_str("an integer or a \"0x\"-prefixed hex string")
+        }
+
+        fn visit_i64<E: serde::de::Error>(self, v: i64) -> Result<i128, E> {
+            Ok(v as i128)
+        }
+
+        fn visit_u64<E: serde::de::Error>(self, v: u64) -> Result<i128, E> {
+            Ok(v as i128)
+        }
+
+        fn visit_i128<E: serde::de::Error>(self, v: i128) -> Result<i128, E> {
+            Ok(v)
+        }
+
+        fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<i128, E> {
+            let hex = v
+                .strip_prefix("0x")
+                .or_else(|| v.strip_prefix("0X"))
+                .ok_or_else(|| E::custom(format!("'{v}' isn't a number or 0x-prefixed hex")))?;
+            i128::from_str_radix(hex, 16).map_err(|e| E::custom(format!("invalid hex '{v}': {e}")))
+        }
+    }
+
+    deserializer.deserialize_any(WideInt)
+}
+
+/// What a watch's value is tested for. `equals`, `not_equals` and `bitmask`
+/// are for integers; `greater_than`, `less_than`, `increased`, `decreased`
+/// and `approx_equals` for integers and floats; `string_equals` and
+/// `string_changed` for strings; `changed` for anything.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "kind", rename_all = "snake_case")]
 pub enum Condition {
-    Equals { value: i128 },
-    NotEquals { value: i128 },
-    GreaterThan { value: i128 },
-    LessThan { value: i128 },
+    Equals {
+        #[serde(deserialize_with = "wide_int")]
+        value: i128,
+    },
+    NotEquals {
+        #[serde(deserialize_with = "wide_int")]
+        value: i128,
+    },
+    GreaterThan {
+        #[serde(deserialize_with = "wide_int")]
+        value: i128,
+    },
+    LessThan {
+        #[serde(deserialize_with = "wide_int")]
+        value: i128,
+    },
     Increased,
     Decreased,
     Changed,
+    /// Within `tolerance` of `value` — floats are rarely exactly equal to
+    /// anything.
+    ApproxEquals {
+        value: f64,
+        #[serde(default = "default_tolerance")]
+        tolerance: f64,
+    },
+    StringEquals {
+        value: String,
+    },
+    /// Changed to a different, non-empty string. Games often blank a
+    /// level's name while loading the next one, which `changed` would fire
+    /// on too.
+    StringChanged,
+    /// All of `mask`'s bits set, or with `"set": false` all of them clear:
+    /// `{"kind": "bitmask", "mask": "0x40"}`.
+    Bitmask {
+        #[serde(deserialize_with = "wide_int")]
+        mask: i128,
+        #[serde(default = "default_set")]
+        set: bool,
+    },
+}
+
+fn default_tolerance() -> f64 {
+    0.001
+}
+
+fn default_set() -> bool {
+    true
+}
+
+/// How two numbers compare; integers exactly, anything with a float as
+/// floats. `None` for non-numbers (or NaN).
+fn order(a: &Value, b: &Value) -> Option<Ordering> {
+    match (a, b) {
+        (Value::Int(a), Value::Int(b)) => Some(a.cmp(b)),
+        _ => a.as_f64()?.partial_cmp(&b.as_f64()?),
+    }
 }
 
 impl Condition {
@@ -188,19 +589,275 @@ impl Condition {
     /// the very first sample — with no `previous` to compare against, there's
     /// no way to tell a genuine transition from "this is just where the
     /// value happened to be when we attached".
-    pub fn triggered(&self, previous: Option<i128>, current: i128) -> bool {
+    pub fn triggered(&self, previous: Option<&Value>, current: &Value) -> bool {
         let Some(previous) = previous else {
             return false;
         };
-        match *self {
-            Condition::Equals { value } => previous != value && current == value,
-            Condition::NotEquals { value } => previous == value && current != value,
-            Condition::GreaterThan { value } => previous <= value && current > value,
-            Condition::LessThan { value } => previous >= value && current < value,
-            Condition::Increased => current > previous,
-            Condition::Decreased => current < previous,
-            Condition::Changed => current != previous,
+        match self {
+            // Already about a transition.
+            Condition::Increased
+            | Condition::Decreased
+            | Condition::Changed
+            | Condition::StringChanged => self.holds(Some(previous), current),
+            _ => !self.holds(None, previous) && self.holds(None, current),
+        }
+    }
+
+    /// Whether the condition is true on this sample, as a state rather
+    /// than a transition: `Equals` holds for as long as the value stays
+    /// there, `Increased` on each sample that went up. What `Compound`s are
+    /// built from.
+    pub fn holds(&self, previous: Option<&Value>, current: &Value) -> bool {
+        let compared = |value: i128| order(current, &Value::Int(value));
+        let went = |way: Ordering| previous.is_some_and(|p| order(current, p) == Some(way));
+        match self {
+            Condition::Equals { value } => compared(*value) == Some(Ordering::Equal),
+            Condition::NotEquals { value } => {
+                compared(*value).is_some_and(|o| o != Ordering::Equal)
+            }
+            Condition::GreaterThan { value } => compared(*value) == Some(Ordering::Greater),
+            Condition::LessThan { value } => compared(*value) == Some(Ordering::Less),
+            Condition::Increased => went(Ordering::Greater),
+            Condition::Decreased => went(Ordering::Less),
+            Condition::Changed => previous.is_some_and(|p| !current.same_as(p)),
+            Condition::ApproxEquals { value, tolerance } => current
+                .as_f64()
+                .is_some_and(|x| (x - value).abs() <= *tolerance),
+            Condition::StringEquals { value } => matches!(current, Value::Str(s) if s == value),
+            Condition::StringChanged => match (previous, current) {
+                (Some(Value::Str(previous)), Value::Str(current)) => {
+                    !current.is_empty() && current != previous
+                }
+                _ => false,
+            },
+            Condition::Bitmask { mask, set } => match current {
+                Value::Int(n) if *set => n & mask == *mask,
+                Value::Int(n) => n & mask == 0,
+                _ => false,
+            },
+        }
+    }
+
+    /// The `kind` it's written with.
+    fn kind(&self) -> &'static str {
+        match self {
+            Condition::Equals { .. } => "equals",
+            Condition::NotEquals { .. } => "not_equals",
+            Condition::GreaterThan { .. } => "greater_than",
+            Condition::LessThan { .. } => "less_than",
+            Condition::Increased => "increased",
+            Condition::Decreased => "decreased",
+            Condition::Changed => "changed",
+            Condition::ApproxEquals { .. } => "approx_equals",
+            Condition::StringEquals { .. } => "string_equals",
+            Condition::StringChanged => "string_changed",
+            Condition::Bitmask { .. } => "bitmask",
+        }
+    }
+
+    /// Whether this can test a watch whose values are `ty`.
+    fn check_type(&self, ty: Type) -> Result<(), String> {
+        let fits = match self {
+            Condition::Equals { .. } | Condition::NotEquals { .. } | Condition::Bitmask { .. } => {
+                ty == Type::Int
+            }
+            Condition::GreaterThan { .. }
+            | Condition::LessThan { .. }
+            | Condition::Increased
+            | Condition::Decreased
+            | Condition::ApproxEquals { .. } => ty.is_number(),
+            Condition::StringEquals { .. } | Condition::StringChanged => ty == Type::Str,
+            Condition::Changed => true,
+        };
+        if fits {
+            return Ok(());
+        }
+        Err(match (self, ty) {
+            (Condition::Equals { .. } | Condition::NotEquals { .. }, Type::Float) => format!(
+                "'{}' can't be used on a float, which is rarely exactly equal to anything; use approx_equals",
+                self.kind()
+            ),
+            (_, Type::Str) => format!(
+                "'{}' can't be used on a string; use string_equals or string_changed",
+                self.kind()
+            ),
+            _ => format!("'{}' can't be used on {ty}", self.kind()),
+        })
+    }
+}
+
+/// A boolean combination of conditions on several watches, e.g.
+/// `{"all": [{"watch": "room", "kind": "equals", "value": "0x12"},
+/// {"watch": "boss_hp", "kind": "equals", "value": 0}]}`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Compound {
+    All {
+        all: Vec<Compound>,
+    },
+    Any {
+        any: Vec<Compound>,
+    },
+    Not {
+        not: Box<Compound>,
+    },
+    Watch {
+        watch: String,
+        #[serde(flatten)]
+        condition: Condition,
+    },
+    /// A string is an expression (see `autosplitter::expr`), like
+    /// `"room == 0x12 && old.boss_hp > 0 && boss_hp == 0"`.
+    Expression(Expression),
+}
+
+impl Compound {
+    /// Whether this holds on the `current` sample (see `Condition::holds`).
+    /// `None` when it depends on a watch that couldn't be read — unless
+    /// the rest settles it anyway, like one `false` in an `all`.
+    pub fn holds(
+        &self,
+        previous: &HashMap<String, Value>,
+        current: &HashMap<String, Value>,
+    ) -> Option<bool> {
+        self.holds_in(&Samples { previous, current })
+    }
+
+    /// `holds`, reading watches (and variables) from `scope`.
+    pub fn holds_in(&self, scope: &impl Scope) -> Option<bool> {
+        match self {
+            Compound::All { all } => {
+                let values: Vec<_> = all.iter().map(|c| c.holds_in(scope)).collect();
+                if values.contains(&Some(false)) {
+                    Some(false)
+                } else if values.contains(&None) {
+                    None
+                } else {
+                    Some(true)
+                }
+            }
+            Compound::Any { any } => {
+                let values: Vec<_> = any.iter().map(|c| c.holds_in(scope)).collect();
+                if values.contains(&Some(true)) {
+                    Some(true)
+                } else if values.contains(&None) {
+                    None
+                } else {
+                    Some(false)
+                }
+            }
+            Compound::Not { not } => not.holds_in(scope).map(|held| !held),
+            Compound::Watch { watch, condition } => {
+                let current = scope.value(watch, false)?;
+                let previous = scope.value(watch, true);
+                Some(condition.holds(previous.as_ref(), &current))
+            }
+            Compound::Expression(expression) => match expression.eval(scope)? {
+                Value::Bool(held) => Some(held),
+                _ => None,
+            },
+        }
+    }
+}
+
+/// Watch values alone as a `Scope`.
+struct Samples<'a> {
+    previous: &'a HashMap<String, Value>,
+    current: &'a HashMap<String, Value>,
+}
+
+impl Scope for Samples<'_> {
+    fn value(&self, name: &str, old: bool) -> Option<Value> {
+        let values = if old { self.previous } else { self.current };
+        values.get(name).cloned()
+    }
+}
+
+/// A value kept from poll to poll, like a death counter:
+/// `{"name": "deaths", "initial": 0,
+/// "update": "old.deaths + (old.hp > 0 && hp == 0 ? 1 : 0)"}`. Each poll,
+/// before any condition, `update` gives the new value (the old one stays if
+/// it can't be worked out). Back to `initial` whenever the autosplitter
+/// sends a `start` or `reset`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Variable {
+    pub name: String,
+    pub initial: Value,
+    pub update: Expression,
+}
+
+/// Fires `action` the sample `when` goes from false to true — like a
+/// watch's own condition, never on the first sample, and not again until
+/// `when` has been false in between.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Trigger {
+    /// For logging only.
+    pub name: String,
+    pub when: Compound,
+    pub action: Action,
+    #[serde(flatten)]
+    pub guard: SplitGuard,
+}
+
+/// The autosplit condition for one split of the run.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SplitTrigger {
+    /// The split's name, so the file reads on its own and a split list that
+    /// has moved on from it can be spotted; the position is what counts.
+    #[serde(default)]
+    pub name: String,
+    /// `None` leaves this split to be done by hand.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub when: Option<Compound>,
+}
+
+/// Restricts a watch or trigger to some of the timer's splits, counted
+/// from 0 like `getsplitindex`. Both can be set; then both must pass. An
+/// empty guard lets everything through; a non-empty one blocks everything
+/// while no run is going, so it doesn't belong on a `start`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SplitGuard {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub only_on_split: Option<usize>,
+    /// `[first, last]`, both included.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub split_range: Option<(usize, usize)>,
+}
+
+impl SplitGuard {
+    pub fn is_empty(&self) -> bool {
+        self.only_on_split.is_none() && self.split_range.is_none()
+    }
+
+    /// Whether the timer being on `split_index` (`None`: not running, or
+    /// the timer couldn't be asked) lets an action through.
+    pub fn allows(&self, split_index: Option<usize>) -> bool {
+        if self.is_empty() {
+            return true;
+        }
+        let Some(index) = split_index else {
+            return false;
+        };
+        self.only_on_split.is_none_or(|only| index == only)
+            && self
+                .split_range
+                .is_none_or(|(first, last)| (first..=last).contains(&index))
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if let Some((first, last)) = self.split_range
+            && first > last
+        {
+            return Err(format!("split_range [{first}, {last}] is backwards"));
+        }
+        if let (Some(only), Some((first, last))) = (self.only_on_split, self.split_range)
+            && !(first..=last).contains(&only)
+        {
+            return Err(format!(
+                "only_on_split {only} is outside split_range [{first}, {last}]"
+            ));
         }
+        Ok(())
     }
 }
 
@@ -211,17 +868,30 @@ pub enum Action {
     Split,
     Reset,
     Pause,
+    /// Hands game time over to the autosplitter (see
+    /// `AppState::init_game_time`).
+    InitGameTime,
+    /// Feeds the watched value (scaled by `Watch::ms_per_unit`) into the
+    /// game time. Pair it with the `changed` condition to follow a game's
+    /// own IGT counter.
+    SetGameTime,
+    PauseGameTime,
+    ResumeGameTime,
 }
 
 impl Action {
-    /// The exact string `core::server::listen_for_commands` matches on —
-    /// see `cli/main.rs` for the same vocabulary.
+    /// The exact command name `core::protocol::handle_command` matches on
+    /// — see `core::protocol::COMMANDS` for the same vocabulary.
     pub fn as_command(self) -> &'static str {
         match self {
             Action::Start => "start",
             Action::Split => "split",
             Action::Reset => "reset",
             Action::Pause => "pause",
+            Action::InitGameTime => "initgametime",
+            Action::SetGameTime => "setgametime",
+            Action::PauseGameTime => "pausegametime",
+            Action::ResumeGameTime => "resumegametime",
         }
     }
 }
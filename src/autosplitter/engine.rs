@@ -0,0 +1,253 @@
+//! The part of the autosplitter that decides what to send: given one
+//! sample of every watch's value, which watches and triggers fire. Kept
+//! apart from the reading (`retroarch`, `process_memory`) and the sending
+//! (`core::client`) so both targets share it and it can be tested without
+//! a game or a timer.
+
+use std::collections::HashMap;
+
+use crate::autosplitter::config::{Action, AutosplitterConfig, SplitGuard};
+use crate::autosplitter::expr::{Scope, Value};
+
+/// One command to send, and which watch or trigger it came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fired {
+    /// `"watch 'room_id'"` or `"trigger 'boss_dead'"`, for logging.
+    pub source: String,
+    pub command: String,
+}
+
+pub struct Engine {
+    config: AutosplitterConfig,
+    previous: HashMap<String, Value>,
+    /// The variables as of the last poll, which `old.` reads.
+    variables: HashMap<String, Value>,
+    /// Per trigger, whether its `when` held on the last sample it could be
+    /// worked out on.
+    held: Vec<Option<bool>>,
+    sequence: Sequence,
+}
+
+/// Whether `holds` is the false-to-true edge, updating `held`.
+fn rose(held: &mut Option<bool>, holds: Option<bool>) -> bool {
+    let Some(holds) = holds else {
+        return false;
+    };
+    let rose = holds && *held == Some(false);
+    *held = Some(holds);
+    rose
+}
+
+/// Watches and variables on this poll and the last one.
+struct Poll<'a> {
+    previous: &'a HashMap<String, Value>,
+    current: &'a HashMap<String, Value>,
+    old_variables: &'a HashMap<String, Value>,
+    variables: &'a HashMap<String, Value>,
+}
+
+impl Scope for Poll<'_> {
+    fn value(&self, name: &str, old: bool) -> Option<Value> {
+        let (watches, variables) = if old {
+            (self.previous, self.old_variables)
+        } else {
+            (self.current, self.variables)
+        };
+        watches.get(name).or_else(|| variables.get(name)).cloned()
+    }
+}
+
+/// Where the split sequence is, and its conditions' edge state.
+#[derive(Default)]
+struct Sequence {
+    /// The split index the timer is on, or `None` before a run. Moves on
+    /// as soon as a `split` is sent, and follows the timer from the next
+    /// poll (manual splits, undos, skips).
+    pointer: Option<usize>,
+    /// The same as `Engine::held`, for the `start`/current split's
+    /// condition and for `reset`. Cleared whenever `pointer` moves, so a
+    /// condition that's already true on arrival has to turn false and back
+    /// before it fires.
+    held: Option<bool>,
+    reset_held: Option<bool>,
+}
+
+impl Sequence {
+    fn move_to(&mut self, pointer: Option<usize>) {
+        if self.pointer != pointer {
+            *self = Sequence {
+                pointer,
+                ..Sequence::default()
+            };
+        }
+    }
+
+    fn step(&mut self, config: &AutosplitterConfig, scope: &Poll) -> Option<Fired> {
+        let Some(split) = self.pointer else {
+            let start = config.start.as_ref().and_then(|w| w.holds_in(scope));
+            if !rose(&mut self.held, start) {
+                return None;
+            }
+            self.move_to(Some(0));
+            return Some(Fired {
+                source: "start".to_string(),
+                command: Action::Start.as_command().to_string(),
+            });
+        };
+
+        let reset = config.reset.as_ref().and_then(|w| w.holds_in(scope));
+        if rose(&mut self.reset_held, reset) {
+            self.move_to(None);
+            return Some(Fired {
+                source: "reset".to_string(),
+                command: Action::Reset.as_command().to_string(),
+            });
+        }
+
+        let trigger = config.splits.get(split)?;
+        let holds = trigger.when.as_ref().and_then(|w| w.holds_in(scope));
+        if !rose(&mut self.held, holds) {
+            return None;
+        }
+        self.move_to(Some(split + 1));
+        Some(Fired {
+            source: format!("split {split} ('{}')", trigger.name),
+            command: Action::Split.as_command().to_string(),
+        })
+    }
+}
+
+impl Engine {
+    pub fn new(config: AutosplitterConfig) -> Self {
+        let held = vec![None; config.triggers.len()];
+        let mut engine = Self {
+            config,
+            previous: HashMap::new(),
+            variables: HashMap::new(),
+            held,
+            sequence: Sequence::default(),
+        };
+        engine.reset_variables();
+        engine
+    }
+
+    pub fn config(&self) -> &AutosplitterConfig {
+        &self.config
+    }
+
+    /// Starts over as if nothing had been read yet, for when the game
+    /// process went away and its old values mean nothing.
+    pub fn forget(&mut self) {
+        self.previous.clear();
+        self.held.fill(None);
+        self.sequence.held = None;
+        self.sequence.reset_held = None;
+        self.reset_variables();
+    }
+
+    /// The split index the sequence is waiting on (see `Sequence::pointer`).
+    pub fn pointer(&self) -> Option<usize> {
+        self.sequence.pointer
+    }
+
+    /// A variable's value as of the last poll.
+    pub fn variable(&self, name: &str) -> Option<&Value> {
+        self.variables.get(name)
+    }
+
+    fn reset_variables(&mut self) {
+        self.variables = self
+            .config
+            .variables
+            .iter()
+            .map(|v| (v.name.clone(), v.initial.clone()))
+            .collect();
+    }
+
+    /// Takes one poll's values, keyed by watch name (watches that couldn't
+    /// be read are just missing), and returns what fired, in config order:
+    /// watches first, then triggers, then the split sequence.
+    /// `split_index` (`None` meaning the timer isn't running) is called at
+    /// most once, and only if a guard or the split sequence needs it.
+    pub fn step(
+        &mut self,
+        sample: &HashMap<String, Value>,
+        mut split_index: impl FnMut() -> Option<usize>,
+    ) -> Vec<Fired> {
+        let mut index = None;
+        let mut allows = |guard: &SplitGuard| {
+            guard.is_empty() || guard.allows(*index.get_or_insert_with(&mut split_index))
+        };
+
+        let mut variables = HashMap::new();
+        for variable in &self.config.variables {
+            let scope = Poll {
+                previous: &self.previous,
+                current: sample,
+                old_variables: &self.variables,
+                variables: &variables,
+            };
+            let value = variable
+                .update
+                .eval(&scope)
+                .or_else(|| self.variables.get(&variable.name).cloned())
+                .unwrap_or_else(|| variable.initial.clone());
+            variables.insert(variable.name.clone(), value);
+        }
+        let scope = Poll {
+            previous: &self.previous,
+            current: sample,
+            old_variables: &self.variables,
+            variables: &variables,
+        };
+
+        let mut fired = Vec::new();
+        for watch in &self.config.watches {
+            let (Some(condition), Some(current)) = (&watch.condition, sample.get(&watch.name))
+            else {
+                continue;
+            };
+            if condition.triggered(self.previous.get(&watch.name), current)
+                && allows(&watch.guard)
+                && let Some(command) = watch.command(current)
+            {
+                fired.push(Fired {
+                    source: format!("watch '{}'", watch.name),
+                    command,
+                });
+            }
+        }
+
+        for (trigger, held) in self.config.triggers.iter().zip(&mut self.held) {
+            if rose(held, trigger.when.holds_in(&scope)) && allows(&trigger.guard) {
+                fired.push(Fired {
+                    source: format!("trigger '{}'", trigger.name),
+                    command: trigger.action.as_command().to_string(),
+                });
+            }
+        }
+
+        if self.config.has_sequence() {
+            let position = *index.get_or_insert_with(&mut split_index);
+            self.sequence.move_to(position);
+            if let Some(fire) = self.sequence.step(&self.config, &scope) {
+                fired.push(fire);
+            }
+        }
+
+        self.previous.extend(
+            sample
+                .iter()
+                .map(|(name, value)| (name.clone(), value.clone())),
+        );
+        let restarted = fired.iter().any(|f| {
+            f.command == Action::Start.as_command() || f.command == Action::Reset.as_command()
+        });
+        if restarted {
+            self.reset_variables();
+        } else {
+            self.variables = variables;
+        }
+        fired
+    }
+}
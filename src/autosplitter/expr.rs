@@ -0,0 +1,825 @@
+//! The autosplitter's expression language, for conditions the JSON
+//! `Condition`s can't say: `room == 0x12 && old.boss_hp > 0 && boss_hp == 0`,
+//! `(frames / 60) > 10`, `(flags & 0x40) != 0`.
+//!
+//! Watches and variables are read by name (their value on this poll), or as
+//! `old.name` (the previous poll) and `current.name` (the same as the bare
+//! name). Integers are written in decimal, `0x` hex or `0b` binary, with
+//! optional `_` separators; floats with a decimal point (`0.5`); strings in
+//! double quotes (`"W1-2"`, with `\"` and `\\` escapes); and there are
+//! `true`/`false`. Integers and floats mix freely, giving a float. Strings
+//! and byte arrays (from `bytes` watches) can only be compared with `==`
+//! and `!=`. Operators, from loosest to tightest binding:
+//!
+//! - `c ? a : b`
+//! - `||`, then `&&`
+//! - `==` `!=` `<` `<=` `>` `>=` (not chained: `a < b < c` is an error)
+//! - `|`, then `^`, then `&`
+//! - `<<` `>>`, then `+` `-`, then `*` `/` `%`
+//! - prefix `!` (bools), `-` (numbers) and `~` (integers)
+//!
+//! Everything is type-checked before it runs (`Expression::check`), so
+//! `room + true` or a condition that's a number are caught at load time,
+//! with the column they're at. Evaluation (`Expression::eval`) is pure:
+//! it only reads a `Scope`, and gives `None` instead of a value when it
+//! can't know one — an `old.` value on the first poll, a watch that
+//! couldn't be read, a division by zero or an overflow (or a float result
+//! that isn't a finite number).
+
+use std::fmt;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Type {
+    Int,
+    Float,
+    Bool,
+    Str,
+    Bytes,
+}
+
+impl Type {
+    pub fn is_number(self) -> bool {
+        matches!(self, Type::Int | Type::Float)
+    }
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Type::Int => "an integer",
+            Type::Float => "a float",
+            Type::Bool => "a bool",
+            Type::Str => "a string",
+            Type::Bytes => "a byte array",
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Int(i128),
+    Float(f64),
+    Bool(bool),
+    Str(String),
+    Bytes(Vec<u8>),
+}
+
+impl Value {
+    pub fn ty(&self) -> Type {
+        match self {
+            Value::Int(_) => Type::Int,
+            Value::Float(_) => Type::Float,
+            Value::Bool(_) => Type::Bool,
+            Value::Str(_) => Type::Str,
+            Value::Bytes(_) => Type::Bytes,
+        }
+    }
+
+    /// An integer or float as a float, `None` for anything else.
+    pub fn as_f64(&self) -> Option<f64> {
+        match *self {
+            Value::Int(n) => Some(n as f64),
+            Value::Float(x) => Some(x),
+            _ => None,
+        }
+    }
+
+    /// Equality with integers and floats compared by value, so `1 == 1.0`.
+    pub fn same_as(&self, other: &Value) -> bool {
+        match (self, other) {
+            (Value::Int(_), Value::Float(_)) | (Value::Float(_), Value::Int(_)) => {
+                self.as_f64() == other.as_f64()
+            }
+            _ => self == other,
+        }
+    }
+}
+
+impl Serialize for Value {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Value::Int(n) => serializer.serialize_i128(*n),
+            Value::Float(x) => serializer.serialize_f64(*x),
+            Value::Bool(b) => serializer.serialize_bool(*b),
+            Value::Str(text) => serializer.serialize_str(text),
+            Value::Bytes(bytes) => bytes.serialize(serializer),
+        }
+    }
+}
+
+// Like `config::wide_int`, through serde's buffering, plus the other types.
+// A string is a hex integer if it starts with "0x", as `wide_int` reads it,
+// and a string otherwise; an array of numbers is a byte array.
+impl<'de> Deserialize<'de> for Value {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct ValueVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for ValueVisitor {
+            type Value = Value;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a number, a bool, a string or an array of bytes")
+            }
+
+            fn visit_bool<E: serde::de::Error>(self, v: bool) -> Result<Value, E> {
+                Ok(Value::Bool(v))
+            }
+
+            fn visit_i64<E: serde::de::Error>(self, v: i64) -> Result<Value, E> {
+                Ok(Value::Int(v as i128))
+            }
+
+            fn visit_u64<E: serde::de::Error>(self, v: u64) -> Result<Value, E> {
+                Ok(Value::Int(v as i128))
+            }
+
+            fn visit_i128<E: serde::de::Error>(self, v: i128) -> Result<Value, E> {
+                Ok(Value::Int(v))
+            }
+
+            fn visit_f64<E: serde::de::Error>(self, v: f64) -> Result<Value, E> {
+                Ok(Value::Float(v))
+            }
+
+            fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Value, E> {
+                let Some(hex) = v.strip_prefix("0x").or_else(|| v.strip_prefix("0X")) else {
+                    return Ok(Value::Str(v.to_string()));
+                };
+                i128::from_str_radix(hex, 16)
+                    .map(Value::Int)
+                    .map_err(|e| E::custom(format!("invalid hex '{v}': {e}")))
+            }
+
+            fn visit_seq<A: serde::de::SeqAccess<'de>>(
+                self,
+                mut seq: A,
+            ) -> Result<Value, A::Error> {
+                let mut bytes = Vec::new();
+                while let Some(byte) = seq.next_element::<u8>()? {
+                    bytes.push(byte);
+                }
+                Ok(Value::Bytes(bytes))
+            }
+        }
+
+        deserializer.deserialize_any(ValueVisitor)
+    }
+}
+
+/// Where in an expression something went wrong.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExprError {
+    /// 1-based, counted in characters.
+    pub column: usize,
+    pub message: String,
+}
+
+impl ExprError {
+    fn at(source: &str, pos: usize, message: impl Into<String>) -> Self {
+        Self {
+            column: source[..pos].chars().count() + 1,
+            message: message.into(),
+        }
+    }
+
+    /// The error with `source` under it and a caret at the column, for
+    /// printing in a terminal.
+    pub fn pointing_into(&self, source: &str) -> String {
+        format!("{self}\n    {source}\n    {}^", " ".repeat(self.column - 1))
+    }
+}
+
+impl fmt::Display for ExprError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "column {}: {}", self.column, self.message)
+    }
+}
+
+/// What an expression reads its names from.
+pub trait Scope {
+    /// `name`'s value on this poll, or on the previous one if `old`.
+    fn value(&self, name: &str, old: bool) -> Option<Value>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum UnaryOp {
+    Not,
+    Neg,
+    BitNot,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BinaryOp {
+    Or,
+    And,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    BitOr,
+    BitXor,
+    BitAnd,
+    Shl,
+    Shr,
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+}
+
+impl BinaryOp {
+    /// The operator's token and how tightly it binds.
+    const ALL: [(&'static str, BinaryOp, u8); 18] = [
+        ("||", BinaryOp::Or, 1),
+        ("&&", BinaryOp::And, 2),
+        ("==", BinaryOp::Eq, 3),
+        ("!=", BinaryOp::Ne, 3),
+        ("<", BinaryOp::Lt, 3),
+        ("<=", BinaryOp::Le, 3),
+        (">", BinaryOp::Gt, 3),
+        (">=", BinaryOp::Ge, 3),
+        ("|", BinaryOp::BitOr, 4),
+        ("^", BinaryOp::BitXor, 5),
+        ("&", BinaryOp::BitAnd, 6),
+        ("<<", BinaryOp::Shl, 7),
+        (">>", BinaryOp::Shr, 7),
+        ("+", BinaryOp::Add, 8),
+        ("-", BinaryOp::Sub, 8),
+        ("*", BinaryOp::Mul, 9),
+        ("/", BinaryOp::Div, 9),
+        ("%", BinaryOp::Rem, 9),
+    ];
+
+    fn from_token(token: &str) -> Option<(BinaryOp, u8)> {
+        Self::ALL
+            .iter()
+            .find(|(t, ..)| *t == token)
+            .map(|&(_, op, prec)| (op, prec))
+    }
+
+    fn token(self) -> &'static str {
+        Self::ALL.iter().find(|(_, op, _)| *op == self).unwrap().0
+    }
+
+    fn is_comparison(self) -> bool {
+        matches!(
+            self,
+            BinaryOp::Eq | BinaryOp::Ne | BinaryOp::Lt | BinaryOp::Le | BinaryOp::Gt | BinaryOp::Ge
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Node {
+    /// Byte offset into the source, for errors: where a literal or name
+    /// starts, or the operator's.
+    pos: usize,
+    kind: Kind,
+}
+
+#[derive(Debug, Clone)]
+enum Kind {
+    Literal(Value),
+    Name { name: String, old: bool },
+    Unary(UnaryOp, Box<Node>),
+    Binary(BinaryOp, Box<Node>, Box<Node>),
+    Conditional(Box<Node>, Box<Node>, Box<Node>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Int(i128),
+    Float(f64),
+    Str(String),
+    Name(String),
+    /// Operators and punctuation.
+    Symbol(&'static str),
+}
+
+/// Longest first, so `<<` isn't read as two `<`s.
+const SYMBOLS: [&str; 25] = [
+    "<<", ">>", "<=", ">=", "==", "!=", "&&", "||", "+", "-", "*", "/", "%", "&", "|", "^", "~",
+    "!", "<", ">", "(", ")", "?", ":", ".",
+];
+
+/// The length of the float literal `rest` starts with: decimal digits, a
+/// `.` and at least one more digit.
+fn float_len(rest: &str) -> Option<usize> {
+    let whole = rest
+        .find(|c: char| !(c.is_ascii_digit() || c == '_'))
+        .unwrap_or(rest.len());
+    let fraction = rest[whole..].strip_prefix('.')?;
+    let digits = fraction
+        .find(|c: char| !(c.is_ascii_digit() || c == '_'))
+        .unwrap_or(fraction.len());
+    (whole > 0 && fraction.starts_with(|c: char| c.is_ascii_digit())).then_some(whole + 1 + digits)
+}
+
+fn tokenize(source: &str) -> Result<Vec<(usize, Token)>, ExprError> {
+    let mut tokens = Vec::new();
+    let mut rest = source;
+    while let Some(c) = rest.chars().next() {
+        let pos = source.len() - rest.len();
+        if c.is_whitespace() {
+            rest = &rest[c.len_utf8()..];
+        } else if c == '"' {
+            let mut text = String::new();
+            let mut chars = rest.char_indices().skip(1);
+            let len = loop {
+                match chars.next() {
+                    Some((i, '"')) => break i + 1,
+                    Some((_, '\\')) => match chars.next() {
+                        Some((_, c @ ('"' | '\\'))) => text.push(c),
+                        Some((i, c)) => {
+                            return Err(ExprError::at(
+                                source,
+                                pos + i - 1,
+                                format!(
+                                    "unknown escape '\\{c}', only '\\\"' and '\\\\' are allowed"
+                                ),
+                            ));
+                        }
+                        None => {
+                            return Err(ExprError::at(
+                                source,
+                                pos,
+                                "this string has no closing '\"'",
+                            ));
+                        }
+                    },
+                    Some((_, c)) => text.push(c),
+                    None => {
+                        return Err(ExprError::at(
+                            source,
+                            pos,
+                            "this string has no closing '\"'",
+                        ));
+                    }
+                }
+            };
+            tokens.push((pos, Token::Str(text)));
+            rest = &rest[len..];
+        } else if let Some(len) = float_len(rest) {
+            let value = rest[..len].replace('_', "").parse().map_err(|_| {
+                ExprError::at(
+                    source,
+                    pos,
+                    format!("'{}' isn't a valid number", &rest[..len]),
+                )
+            })?;
+            tokens.push((pos, Token::Float(value)));
+            rest = &rest[len..];
+        } else if c.is_ascii_digit() {
+            let len = rest
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                .unwrap_or(rest.len());
+            let text = rest[..len].replace('_', "");
+            let (digits, radix) = if let Some(hex) = text.strip_prefix("0x") {
+                (hex, 16)
+            } else if let Some(binary) = text.strip_prefix("0b") {
+                (binary, 2)
+            } else {
+                (text.as_str(), 10)
+            };
+            let value = i128::from_str_radix(digits, radix).map_err(|e| {
+                let problem = match e.kind() {
+                    std::num::IntErrorKind::PosOverflow => "is too large",
+                    _ => "isn't a valid number",
+                };
+                ExprError::at(source, pos, format!("'{}' {problem}", &rest[..len]))
+            })?;
+            tokens.push((pos, Token::Int(value)));
+            rest = &rest[len..];
+        } else if c.is_alphabetic() || c == '_' {
+            let len = rest
+                .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+                .unwrap_or(rest.len());
+            tokens.push((pos, Token::Name(rest[..len].to_string())));
+            rest = &rest[len..];
+        } else if let Some(symbol) = SYMBOLS.iter().find(|s| rest.starts_with(**s)) {
+            tokens.push((pos, Token::Symbol(symbol)));
+            rest = &rest[symbol.len()..];
+        } else {
+            return Err(ExprError::at(
+                source,
+                pos,
+                format!("unexpected character '{c}'"),
+            ));
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    source: &'a str,
+    tokens: Vec<(usize, Token)>,
+    next: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.next).map(|(_, t)| t)
+    }
+
+    /// Where the next token starts, or the end of the source.
+    fn pos(&self) -> usize {
+        self.tokens
+            .get(self.next)
+            .map_or(self.source.len(), |(pos, _)| *pos)
+    }
+
+    fn error(&self, message: impl Into<String>) -> ExprError {
+        ExprError::at(self.source, self.pos(), message)
+    }
+
+    fn eat(&mut self, symbol: &str) -> bool {
+        if matches!(self.peek(), Some(Token::Symbol(s)) if *s == symbol) {
+            self.next += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, symbol: &str, context: &str) -> Result<(), ExprError> {
+        if self.eat(symbol) {
+            Ok(())
+        } else {
+            Err(self.error(format!("expected '{symbol}' {context}")))
+        }
+    }
+
+    fn conditional(&mut self) -> Result<Node, ExprError> {
+        let condition = self.binary(1)?;
+        let pos = self.pos();
+        if !self.eat("?") {
+            return Ok(condition);
+        }
+        let then = self.conditional()?;
+        self.expect(":", "to go with the '?'")?;
+        let otherwise = self.conditional()?;
+        Ok(Node {
+            pos,
+            kind: Kind::Conditional(Box::new(condition), Box::new(then), Box::new(otherwise)),
+        })
+    }
+
+    /// Binary operators binding at least as tightly as `min_prec`.
+    fn binary(&mut self, min_prec: u8) -> Result<Node, ExprError> {
+        let mut lhs = self.unary()?;
+        while let Some(Token::Symbol(symbol)) = self.peek()
+            && let Some((op, prec)) = BinaryOp::from_token(symbol)
+            && prec >= min_prec
+        {
+            let pos = self.pos();
+            self.next += 1;
+            let rhs = self.binary(prec + 1)?;
+            if op.is_comparison()
+                && let Some(Token::Symbol(next)) = self.peek()
+                && BinaryOp::from_token(next).is_some_and(|(next, _)| next.is_comparison())
+            {
+                return Err(self.error("comparisons can't be chained, join them with '&&'"));
+            }
+            lhs = Node {
+                pos,
+                kind: Kind::Binary(op, Box::new(lhs), Box::new(rhs)),
+            };
+        }
+        Ok(lhs)
+    }
+
+    fn unary(&mut self) -> Result<Node, ExprError> {
+        let pos = self.pos();
+        let op = if self.eat("!") {
+            UnaryOp::Not
+        } else if self.eat("-") {
+            UnaryOp::Neg
+        } else if self.eat("~") {
+            UnaryOp::BitNot
+        } else {
+            return self.primary();
+        };
+        let operand = self.unary()?;
+        Ok(Node {
+            pos,
+            kind: Kind::Unary(op, Box::new(operand)),
+        })
+    }
+
+    fn primary(&mut self) -> Result<Node, ExprError> {
+        let pos = self.pos();
+        let Some(token) = self.peek().cloned() else {
+            return Err(self.error("expected a value, found the end"));
+        };
+        self.next += 1;
+        let kind = match token {
+            Token::Int(n) => Kind::Literal(Value::Int(n)),
+            Token::Float(x) => Kind::Literal(Value::Float(x)),
+            Token::Str(text) => Kind::Literal(Value::Str(text)),
+            Token::Name(name) if name == "true" => Kind::Literal(Value::Bool(true)),
+            Token::Name(name) if name == "false" => Kind::Literal(Value::Bool(false)),
+            Token::Name(prefix) if (prefix == "old" || prefix == "current") && self.eat(".") => {
+                let name_pos = self.pos();
+                let Some(Token::Name(name)) = self.peek().cloned() else {
+                    return Err(self.error(format!(
+                        "expected a watch or variable name after '{prefix}.'"
+                    )));
+                };
+                self.next += 1;
+                return Ok(Node {
+                    pos: name_pos,
+                    kind: Kind::Name {
+                        name,
+                        old: prefix == "old",
+                    },
+                });
+            }
+            Token::Name(name) => Kind::Name { name, old: false },
+            Token::Symbol("(") => {
+                let inner = self.conditional()?;
+                self.expect(")", "to close the '('")?;
+                return Ok(inner);
+            }
+            Token::Symbol(symbol) => {
+                self.next -= 1;
+                return Err(self.error(format!("expected a value, found '{symbol}'")));
+            }
+        };
+        Ok(Node { pos, kind })
+    }
+}
+
+fn parse(source: &str) -> Result<Node, ExprError> {
+    let tokens = tokenize(source)?;
+    let mut parser = Parser {
+        source,
+        tokens,
+        next: 0,
+    };
+    let node = parser.conditional()?;
+    match parser.peek() {
+        None => Ok(node),
+        Some(Token::Symbol(")")) => Err(parser.error("unmatched ')'")),
+        Some(_) => Err(parser.error("expected an operator")),
+    }
+}
+
+/// One expression: its text, and the text parsed. Syntax errors are kept
+/// rather than failing to load, so `check` can report them along with the
+/// type errors, at a position.
+#[derive(Debug, Clone)]
+pub struct Expression {
+    source: String,
+    parsed: Result<Node, ExprError>,
+}
+
+impl Expression {
+    pub fn new(source: impl Into<String>) -> Self {
+        let source = source.into();
+        let parsed = parse(&source);
+        Self { source, parsed }
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// The expression's type, or the first syntax or type error in it.
+    /// `lookup` gives the type of a watch or variable as read plainly or
+    /// as `old.name`, or why it can't be read that way.
+    pub fn check(
+        &self,
+        lookup: &impl Fn(&str, bool) -> Result<Type, String>,
+    ) -> Result<Type, ExprError> {
+        let node = self.parsed.as_ref().map_err(Clone::clone)?;
+        check(&self.source, node, lookup)
+    }
+
+    /// `check`, and that the result is a bool, to be used as a condition.
+    pub fn check_condition(
+        &self,
+        lookup: &impl Fn(&str, bool) -> Result<Type, String>,
+    ) -> Result<(), ExprError> {
+        match self.check(lookup)? {
+            Type::Bool => Ok(()),
+            ty => Err(ExprError::at(
+                &self.source,
+                0,
+                format!(
+                    "a condition must be true or false, but this is {ty} (compare it with something, like '!= 0')"
+                ),
+            )),
+        }
+    }
+
+    /// The value on this poll, `None` if it can't be known (see the module
+    /// docs) or the expression doesn't parse.
+    pub fn eval(&self, scope: &impl Scope) -> Option<Value> {
+        eval(self.parsed.as_ref().ok()?, scope)
+    }
+}
+
+impl Serialize for Expression {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.source)
+    }
+}
+
+impl<'de> Deserialize<'de> for Expression {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(Expression::new)
+    }
+}
+
+fn check(
+    source: &str,
+    node: &Node,
+    lookup: &impl Fn(&str, bool) -> Result<Type, String>,
+) -> Result<Type, ExprError> {
+    let error = |node: &Node, message: String| ExprError::at(source, node.pos, message);
+    let expect = |operand: &Node, wanted: Type, what: &str| -> Result<(), ExprError> {
+        let found = check(source, operand, lookup)?;
+        if found == wanted {
+            Ok(())
+        } else {
+            Err(error(
+                operand,
+                format!("{what} needs {wanted} here, found {found}"),
+            ))
+        }
+    };
+    let number = |operand: &Node, what: &str| -> Result<Type, ExprError> {
+        let found = check(source, operand, lookup)?;
+        if found.is_number() {
+            Ok(found)
+        } else {
+            Err(error(
+                operand,
+                format!("{what} needs a number here, found {found}"),
+            ))
+        }
+    };
+
+    match &node.kind {
+        Kind::Literal(value) => Ok(value.ty()),
+        Kind::Name { name, old } => lookup(name, *old).map_err(|e| error(node, e)),
+        Kind::Unary(UnaryOp::Not, operand) => {
+            expect(operand, Type::Bool, "'!'")?;
+            Ok(Type::Bool)
+        }
+        Kind::Unary(UnaryOp::Neg, operand) => number(operand, "'-'"),
+        Kind::Unary(UnaryOp::BitNot, operand) => {
+            expect(operand, Type::Int, "'~'")?;
+            Ok(Type::Int)
+        }
+        Kind::Binary(op, lhs, rhs) => {
+            let what = format!("'{}'", op.token());
+            match op {
+                BinaryOp::Or | BinaryOp::And => {
+                    expect(lhs, Type::Bool, &what)?;
+                    expect(rhs, Type::Bool, &what)?;
+                    Ok(Type::Bool)
+                }
+                BinaryOp::Eq | BinaryOp::Ne => {
+                    let left = check(source, lhs, lookup)?;
+                    let right = check(source, rhs, lookup)?;
+                    if left != right && !(left.is_number() && right.is_number()) {
+                        return Err(error(
+                            node,
+                            format!("{what} can't compare {left} with {right}"),
+                        ));
+                    }
+                    Ok(Type::Bool)
+                }
+                BinaryOp::Lt | BinaryOp::Le | BinaryOp::Gt | BinaryOp::Ge => {
+                    number(lhs, &what)?;
+                    number(rhs, &what)?;
+                    Ok(Type::Bool)
+                }
+                BinaryOp::Add | BinaryOp::Sub | BinaryOp::Mul | BinaryOp::Div | BinaryOp::Rem => {
+                    let left = number(lhs, &what)?;
+                    let right = number(rhs, &what)?;
+                    Ok(if left == Type::Float || right == Type::Float {
+                        Type::Float
+                    } else {
+                        Type::Int
+                    })
+                }
+                BinaryOp::BitOr
+                | BinaryOp::BitXor
+                | BinaryOp::BitAnd
+                | BinaryOp::Shl
+                | BinaryOp::Shr => {
+                    expect(lhs, Type::Int, &what)?;
+                    expect(rhs, Type::Int, &what)?;
+                    Ok(Type::Int)
+                }
+            }
+        }
+        Kind::Conditional(condition, then, otherwise) => {
+            expect(condition, Type::Bool, "'?'")?;
+            let then_type = check(source, then, lookup)?;
+            let otherwise_type = check(source, otherwise, lookup)?;
+            if then_type != otherwise_type {
+                return Err(error(
+                    otherwise,
+                    format!(
+                        "both sides of ':' must have the same type, found {then_type} and {otherwise_type}"
+                    ),
+                ));
+            }
+            Ok(then_type)
+        }
+    }
+}
+
+/// A float result, or `None` if it isn't a finite number.
+fn finite(x: f64) -> Option<Value> {
+    x.is_finite().then_some(Value::Float(x))
+}
+
+fn eval(node: &Node, scope: &impl Scope) -> Option<Value> {
+    let int = |node: &Node| match eval(node, scope)? {
+        Value::Int(n) => Some(n),
+        _ => None,
+    };
+    let bool = |node: &Node| match eval(node, scope)? {
+        Value::Bool(b) => Some(b),
+        _ => None,
+    };
+
+    Some(match &node.kind {
+        Kind::Literal(value) => value.clone(),
+        Kind::Name { name, old } => scope.value(name, *old)?,
+        Kind::Unary(UnaryOp::Not, operand) => Value::Bool(!bool(operand)?),
+        Kind::Unary(UnaryOp::Neg, operand) => match eval(operand, scope)? {
+            Value::Int(n) => Value::Int(n.checked_neg()?),
+            Value::Float(x) => Value::Float(-x),
+            _ => return None,
+        },
+        Kind::Unary(UnaryOp::BitNot, operand) => Value::Int(!int(operand)?),
+        // A known side decides these even when the other isn't known.
+        Kind::Binary(BinaryOp::And, lhs, rhs) => match (bool(lhs), bool(rhs)) {
+            (Some(false), _) | (_, Some(false)) => Value::Bool(false),
+            (Some(true), Some(true)) => Value::Bool(true),
+            _ => return None,
+        },
+        Kind::Binary(BinaryOp::Or, lhs, rhs) => match (bool(lhs), bool(rhs)) {
+            (Some(true), _) | (_, Some(true)) => Value::Bool(true),
+            (Some(false), Some(false)) => Value::Bool(false),
+            _ => return None,
+        },
+        Kind::Binary(BinaryOp::Eq, lhs, rhs) => {
+            Value::Bool(eval(lhs, scope)?.same_as(&eval(rhs, scope)?))
+        }
+        Kind::Binary(BinaryOp::Ne, lhs, rhs) => {
+            Value::Bool(!eval(lhs, scope)?.same_as(&eval(rhs, scope)?))
+        }
+        Kind::Binary(op, lhs, rhs) => match (eval(lhs, scope)?, eval(rhs, scope)?) {
+            (Value::Int(a), Value::Int(b)) => match op {
+                BinaryOp::Lt => Value::Bool(a < b),
+                BinaryOp::Le => Value::Bool(a <= b),
+                BinaryOp::Gt => Value::Bool(a > b),
+                BinaryOp::Ge => Value::Bool(a >= b),
+                BinaryOp::BitOr => Value::Int(a | b),
+                BinaryOp::BitXor => Value::Int(a ^ b),
+                BinaryOp::BitAnd => Value::Int(a & b),
+                BinaryOp::Shl => Value::Int(a.checked_shl(u32::try_from(b).ok()?)?),
+                BinaryOp::Shr => Value::Int(a.checked_shr(u32::try_from(b).ok()?)?),
+                BinaryOp::Add => Value::Int(a.checked_add(b)?),
+                BinaryOp::Sub => Value::Int(a.checked_sub(b)?),
+                BinaryOp::Mul => Value::Int(a.checked_mul(b)?),
+                BinaryOp::Div => Value::Int(a.checked_div(b)?),
+                BinaryOp::Rem => Value::Int(a.checked_rem(b)?),
+                BinaryOp::And | BinaryOp::Or | BinaryOp::Eq | BinaryOp::Ne => unreachable!(),
+            },
+            (a, b) => {
+                let (a, b) = (a.as_f64()?, b.as_f64()?);
+                match op {
+                    BinaryOp::Lt => Value::Bool(a < b),
+                    BinaryOp::Le => Value::Bool(a <= b),
+                    BinaryOp::Gt => Value::Bool(a > b),
+                    BinaryOp::Ge => Value::Bool(a >= b),
+                    BinaryOp::Add => finite(a + b)?,
+                    BinaryOp::Sub => finite(a - b)?,
+                    BinaryOp::Mul => finite(a * b)?,
+                    BinaryOp::Div => finite(a / b)?,
+                    BinaryOp::Rem => finite(a % b)?,
+                    _ => return None,
+                }
+            }
+        },
+        Kind::Conditional(condition, then, otherwise) => {
+            if bool(condition)? {
+                eval(then, scope)?
+            } else {
+                eval(otherwise, scope)?
+            }
+        }
+    })
+}
@@ -2,40 +2,232 @@
 //! process's memory) and turns configured value transitions into
 //! `openspeedrun` control commands, sent over the same control socket
 //! `openspeedrun-cli` uses. See `openspeedrun::autosplitter` for the
-//! security reasoning behind why native-process reading is opt-in.
+//! security reasoning behind why native-process reading is opt-in. Given a
+//! `.wasm` file instead of a config, runs it as a LiveSplit-style
+//! autosplitter module (`openspeedrun::autosplitter::wasm`), which reads
+//! native processes the same way.
 
 use std::env;
 use std::process;
 
+#[cfg(unix)]
+use openspeedrun::core::client::ControlClient;
+#[cfg(unix)]
+use openspeedrun::core::protocol::Reply;
+
+/// Sends one command over `client`'s connection, opening it first if
+/// needed and once more if openspeedrun was restarted in between.
+#[cfg(unix)]
+fn request(client: &mut Option<ControlClient>, cmd: &str) -> Result<Reply, String> {
+    let mut last_error = String::new();
+    for _ in 0..2 {
+        let connection = match client.take() {
+            Some(c) => c,
+            None => ControlClient::connect()
+                .map_err(|e| format!("Failed to connect to openspeedrun socket: {e}"))?,
+        };
+        let connection = client.insert(connection);
+
+        match connection.send(cmd) {
+            Ok(reply) => return Ok(reply),
+            Err(e) => {
+                last_error = format!("Failed to send '{cmd}': {e}");
+                *client = None;
+            }
+        }
+    }
+    Err(last_error)
+}
+
+#[cfg(target_os = "linux")]
+mod wasm_host {
+    use std::fs;
+
+    use openspeedrun::autosplitter::process_memory::{
+        ProcessMemoryReader, find_module_base, find_module_size, find_pid_by_name,
+    };
+    use openspeedrun::autosplitter::wasm::{Host, Process};
+    use openspeedrun::core::client::ControlClient;
+    use openspeedrun::core::protocol::Reply;
+    use openspeedrun::core::timer::TimerState;
+
+    use super::request;
+
+    pub struct LinuxProcess {
+        pid: u32,
+        reader: ProcessMemoryReader,
+    }
+
+    impl LinuxProcess {
+        fn maps(&self) -> Option<String> {
+            fs::read_to_string(format!("/proc/{}/maps", self.pid)).ok()
+        }
+    }
+
+    impl Process for LinuxProcess {
+        fn read(&self, address: u64, buf: &mut [u8]) -> bool {
+            match self.reader.read_at(address, buf.len()) {
+                Ok(bytes) => {
+                    buf.copy_from_slice(&bytes);
+                    true
+                }
+                Err(_) => false,
+            }
+        }
+
+        fn module_address(&self, name: &str) -> Option<u64> {
+            find_module_base(&self.maps()?, name)
+        }
+
+        fn module_size(&self, name: &str) -> Option<u64> {
+            find_module_size(&self.maps()?, name)
+        }
+
+        fn is_open(&self) -> bool {
+            fs::metadata(format!("/proc/{}", self.pid)).is_ok()
+        }
+    }
+
+    /// Processes from `/proc`, the timer over the control socket.
+    #[derive(Default)]
+    pub struct SocketHost {
+        client: Option<ControlClient>,
+        /// The last reason attaching failed, so a module retrying every
+        /// tick doesn't repeat it.
+        attach_error: Option<String>,
+    }
+
+    impl Host for SocketHost {
+        fn attach(&mut self, name: &str) -> Option<Box<dyn Process>> {
+            let pid = find_pid_by_name(name).ok()??;
+            match ProcessMemoryReader::open(pid) {
+                Ok(reader) => {
+                    println!("Attached to '{name}' (pid {pid})");
+                    self.attach_error = None;
+                    Some(Box::new(LinuxProcess { pid, reader }))
+                }
+                Err(e) => {
+                    let e = e.to_string();
+                    if self.attach_error.as_ref() != Some(&e) {
+                        eprintln!("{e}");
+                        self.attach_error = Some(e);
+                    }
+                    None
+                }
+            }
+        }
+
+        fn timer_state(&mut self) -> TimerState {
+            match request(&mut self.client, "getstate") {
+                Ok(Reply::Ok(Some(state))) => match state.as_str() {
+                    "running" => TimerState::Running,
+                    "paused" => TimerState::Paused,
+                    "ended" => TimerState::Ended,
+                    _ => TimerState::NotStarted,
+                },
+                _ => TimerState::NotStarted,
+            }
+        }
+
+        fn send(&mut self, command: &str) {
+            // Game time is usually set on every tick, so only failures to
+            // reach the timer are worth a line.
+            let quiet = command.starts_with("setgametime");
+            match request(&mut self.client, command) {
+                Ok(Reply::Err(reason)) if !quiet => {
+                    eprintln!("module -> '{command}' refused: {reason}");
+                }
+                Ok(Reply::Ok(_)) if !quiet => println!("module -> sent '{command}'"),
+                Ok(_) => {}
+                Err(e) => eprintln!("{e}"),
+            }
+        }
+
+        fn print(&mut self, message: &str) {
+            println!("[module] {message}");
+        }
+    }
+}
+
+/// Runs a `.wasm` autosplitter until it fails.
+#[cfg(target_os = "linux")]
+fn run_wasm(path: &str) -> ! {
+    use std::thread;
+    use std::time::Duration;
+
+    use openspeedrun::autosplitter::wasm::WasmAutosplitter;
+
+    let wasm = match std::fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            eprintln!("Failed to read {path}: {e}");
+            process::exit(1);
+        }
+    };
+    let mut autosplitter = match WasmAutosplitter::new(&wasm, wasm_host::SocketHost::default()) {
+        Ok(a) => a,
+        Err(e) => {
+            eprintln!("Failed to load autosplitter module: {e}");
+            process::exit(1);
+        }
+    };
+    println!("Running {path}");
+
+    loop {
+        if let Err(e) = autosplitter.update() {
+            eprintln!("Autosplitter module stopped: {e}");
+            process::exit(1);
+        }
+        // LiveSplit's runtime ticks at 120Hz unless told otherwise.
+        let interval = autosplitter
+            .tick_interval()
+            .unwrap_or(Duration::from_secs_f64(1.0 / 120.0));
+        thread::sleep(interval);
+    }
+}
+
 #[cfg(unix)]
 fn main() {
     use std::collections::HashMap;
-    use std::io::Write;
-    use std::os::unix::net::UnixStream;
     use std::thread;
     use std::time::Duration;
 
     #[cfg(target_os = "linux")]
     use openspeedrun::autosplitter::config::Watch;
     use openspeedrun::autosplitter::config::{AutosplitterConfig, Target};
+    use openspeedrun::autosplitter::engine::{Engine, Fired};
+    use openspeedrun::autosplitter::expr::Value;
     // `process_memory`'s process-reading API only compiles on Linux (it's
     // built on `/proc`, which doesn't exist on macOS or *BSD) — see that
     // module's docs. Everything importing from it below is only used inside
     // the `Target::ProcessMemory` arm, which is itself Linux-only for the
     // same reason.
+    use openspeedrun::Run;
     #[cfg(target_os = "linux")]
     use openspeedrun::autosplitter::process_memory::{
         ProcessMemoryReader, find_module_base, find_pid_by_name, resolve_pointer_chain,
     };
     use openspeedrun::autosplitter::retroarch::RetroArchClient;
-    use openspeedrun::core::socket_path;
 
     let args: Vec<String> = env::args().collect();
     let Some(config_path) = args.get(1) else {
-        eprintln!("Usage: {} <autosplitter.json>", args[0]);
+        eprintln!("Usage: {} <autosplitter.json | autosplitter.wasm>", args[0]);
         process::exit(1);
     };
 
+    if config_path.ends_with(".wasm") {
+        #[cfg(target_os = "linux")]
+        run_wasm(config_path);
+        #[cfg(not(target_os = "linux"))]
+        {
+            eprintln!(
+                "WebAssembly autosplitters read native processes through /proc, which only \
+                 exists on Linux (not macOS or *BSD)."
+            );
+            process::exit(1);
+        }
+    }
+
     let config = match AutosplitterConfig::load_from_file(config_path) {
         Ok(c) => c,
         Err(e) => {
@@ -44,21 +236,49 @@ fn main() {
         }
     };
 
+    // The split sequence only lines up with the run it was written for.
+    let run_path = std::path::Path::new(config_path).with_file_name("split.json");
+    if !config.splits.is_empty()
+        && let Ok(text) = std::fs::read_to_string(&run_path)
+        && let Ok(run) = serde_json::from_str::<Run>(&text)
+        && run.splits.len() != config.splits.len()
+    {
+        eprintln!(
+            "Warning: {} has {} splits but the autosplitter has conditions for {}",
+            run_path.display(),
+            run.splits.len(),
+            config.splits.len()
+        );
+    }
+
     let poll_interval = Duration::from_millis(config.poll_interval_ms);
-    let mut previous: HashMap<String, i128> = HashMap::new();
-
-    let send_command = |cmd: &str, watch_name: &str| match UnixStream::connect(socket_path()) {
-        Ok(mut stream) => {
-            if let Err(e) = writeln!(stream, "{cmd}") {
-                eprintln!("Failed to send '{cmd}' for watch '{watch_name}': {e}");
-            } else {
-                println!("Watch '{watch_name}' triggered -> sent '{cmd}'");
+    let target = config.target.clone();
+    let mut engine = Engine::new(config);
+
+    // One connection for the whole session, reopened if openspeedrun was
+    // restarted in between.
+    let mut client: Option<ControlClient> = None;
+    let mut request = |cmd: &str| request(&mut client, cmd);
+
+    // Sends what fired this poll; the timer's split index is only asked
+    // for when a guard needs it.
+    let mut run_step = |engine: &mut Engine, sample: &HashMap<String, Value>| {
+        let fired = engine.step(sample, || match request("getsplitindex") {
+            Ok(Reply::Ok(Some(index))) => index.parse().ok(),
+            _ => None,
+        });
+        for Fired { source, command } in fired {
+            match request(&command) {
+                Ok(Reply::Err(reason)) => {
+                    eprintln!("{source} triggered -> '{command}' refused: {reason}");
+                }
+                Ok(_) => println!("{source} triggered -> sent '{command}'"),
+                Err(e) => eprintln!("{e} (for {source})"),
             }
         }
-        Err(e) => eprintln!("Failed to connect to openspeedrun socket: {e}"),
     };
 
-    match &config.target {
+    match &target {
         Target::Retroarch { host, port } => {
             let client = match RetroArchClient::connect(host, *port) {
                 Ok(c) => c,
@@ -69,11 +289,12 @@ fn main() {
             };
             println!(
                 "Connected to RetroArch at {host}:{port}, watching {} value(s)",
-                config.watches.len()
+                engine.config().watches.len()
             );
 
             loop {
-                for watch in &config.watches {
+                let mut sample = HashMap::new();
+                for watch in &engine.config().watches {
                     let address = match watch.address() {
                         Ok(a) => a,
                         Err(e) => {
@@ -94,13 +315,9 @@ fn main() {
                         eprintln!("Short read for watch '{}'", watch.name);
                         continue;
                     };
-
-                    let prev = previous.get(&watch.name).copied();
-                    if watch.condition.triggered(prev, current) {
-                        send_command(watch.action.as_command(), &watch.name);
-                    }
-                    previous.insert(watch.name.clone(), current);
+                    sample.insert(watch.name.clone(), current);
                 }
+                run_step(&mut engine, &sample);
 
                 thread::sleep(poll_interval);
             }
@@ -178,7 +395,7 @@ fn main() {
                 };
                 println!(
                     "Attached to '{process_name}' (pid {pid}), watching {} value(s)",
-                    config.watches.len()
+                    engine.config().watches.len()
                 );
 
                 let mut module_bases: HashMap<String, u64> = HashMap::new();
@@ -190,12 +407,13 @@ fn main() {
                             println!(
                                 "Process {pid} appears to have exited; waiting for it to restart..."
                             );
-                            previous.clear();
+                            engine.forget();
                             continue 'attach;
                         }
                     };
 
-                    for watch in &config.watches {
+                    let mut sample = HashMap::new();
+                    for watch in &engine.config().watches {
                         let address = match resolve_watch_address(
                             watch,
                             &reader,
@@ -221,13 +439,9 @@ fn main() {
                             eprintln!("Short read for watch '{}'", watch.name);
                             continue;
                         };
-
-                        let prev = previous.get(&watch.name).copied();
-                        if watch.condition.triggered(prev, current) {
-                            send_command(watch.action.as_command(), &watch.name);
-                        }
-                        previous.insert(watch.name.clone(), current);
+                        sample.insert(watch.name.clone(), current);
                     }
+                    run_step(&mut engine, &sample);
 
                     thread::sleep(poll_interval);
                 }
@@ -18,14 +18,20 @@
 //!   module's docs) — selecting this target on another OS gets a clear
 //!   "not supported" message instead of a confusing I/O error.
 //!
+//! `wasm` runs LiveSplit-style WebAssembly autosplitter modules on top of
+//! the same process reading, so the same caveats apply to them.
+//!
 //! Either way, this binary only ever *reads* memory and only ever turns a
 //! configured transition into one of the same `start`/`split`/`reset`/
 //! `pause` commands `openspeedrun-cli` sends — see `core::socket_path` for
 //! the shared control socket. If neither target fits your case (a game with
 //! its own scripting/mod support, say), nothing stops you from writing your
 //! own watcher that talks to that same socket directly; the socket protocol
-//! is the integration point, not this module.
+//! (`core::protocol`) is the integration point, not this module.
 
 pub mod config;
+pub mod engine;
+pub mod expr;
 pub mod process_memory;
 pub mod retroarch;
+pub mod wasm;
@@ -132,6 +132,33 @@ pub fn find_module_base(maps_text: &str, module_name: &str) -> Option<u64> {
     None
 }
 
+/// How many bytes `module_name` spans in `/proc/<pid>/maps`-format text:
+/// from the start of its first mapping to the end of its last, matched by
+/// file name the same way as `find_module_base`.
+pub fn find_module_size(maps_text: &str, module_name: &str) -> Option<u64> {
+    let mut span: Option<(u64, u64)> = None;
+    for line in maps_text.lines() {
+        let Some(range) = line.split_whitespace().next() else {
+            continue;
+        };
+        let Some((_, rest)) = line.split_once('/') else {
+            continue;
+        };
+        if rest.rsplit('/').next() != Some(module_name) {
+            continue;
+        }
+
+        let (start, end) = range.split_once('-')?;
+        let start = u64::from_str_radix(start, 16).ok()?;
+        let end = u64::from_str_radix(end, 16).ok()?;
+        span = Some(match span {
+            Some((first, last)) => (first.min(start), last.max(end)),
+            None => (start, end),
+        });
+    }
+    span.map(|(start, end)| end - start)
+}
+
 /// Chases an ASL-style pointer path: `base` is read as a pointer, each
 /// `pointer_path` offset (except the last) is added and re-read as a
 /// pointer, and the final offset is added *without* a further read — that
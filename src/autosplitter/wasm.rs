@@ -0,0 +1,351 @@
+//! Runs autosplitters compiled to WebAssembly against the host functions
+//! LiveSplit One's auto-splitting runtime offers (what the `asr` crate
+//! binds to), so the `.wasm` files communities already publish for it work
+//! here too. A module exports `update`, which is called once per tick, and
+//! reaches the outside world only through the `env` imports below.
+//!
+//! The runtime itself doesn't know about `/proc` or the control socket:
+//! processes and the timer are reached through `Host`/`Process`, which
+//! `autosplitter/main.rs` implements with `process_memory` and
+//! `core::client`, and tests implement with a fake memory image.
+//!
+//! Supported imports, by group:
+//!
+//! - `timer_start`, `timer_split`, `timer_skip_split`, `timer_undo_split`,
+//!   `timer_reset`, `timer_set_game_time`, `timer_pause_game_time`,
+//!   `timer_resume_game_time`, `timer_get_state`, and `timer_set_variable`
+//!   (accepted but ignored, as there's nowhere to show variables);
+//! - `process_attach`, `process_detach`, `process_is_open`, `process_read`,
+//!   `process_get_module_address`, `process_get_module_size`;
+//! - `runtime_set_tick_rate`, `runtime_print_message`, `runtime_get_os`,
+//!   `runtime_get_arch`;
+//! - `user_settings_add_bool` (always answers the default),
+//!   `user_settings_add_title`, `user_settings_set_tooltip`.
+//!
+//! Any other function a module imports (WASI, or a newer part of the API)
+//! still links, but traps with its name if it's ever called, so a module
+//! that only uses it on some path keeps working until then.
+
+use std::time::Duration;
+
+use wasmi::{Caller, Engine, Error, Extern, ExternType, Linker, Memory, Module, Store, TypedFunc};
+
+use crate::core::timer::TimerState;
+
+/// A process a module attached to.
+pub trait Process {
+    /// Fills `buf` from `address`, or returns false if any of it couldn't
+    /// be read.
+    fn read(&self, address: u64, buf: &mut [u8]) -> bool;
+    fn module_address(&self, name: &str) -> Option<u64>;
+    fn module_size(&self, name: &str) -> Option<u64>;
+    fn is_open(&self) -> bool;
+}
+
+/// Everything outside the module it can reach.
+pub trait Host {
+    /// Finds a process by name and opens it for reading.
+    fn attach(&mut self, name: &str) -> Option<Box<dyn Process>>;
+    fn timer_state(&mut self) -> TimerState;
+    /// Sends one control command (`core::protocol`) to the timer.
+    fn send(&mut self, command: &str);
+    /// Shows a message from the module (`runtime_print_message`).
+    fn print(&mut self, message: &str);
+}
+
+struct State<H> {
+    host: H,
+    /// Attached processes. A module's handle for one is its index plus
+    /// one, as 0 stands for "couldn't attach".
+    processes: Vec<Option<Box<dyn Process>>>,
+    tick_rate: Option<f64>,
+}
+
+impl<H> State<H> {
+    fn process(&self, handle: u64) -> Result<&dyn Process, Error> {
+        usize::try_from(handle)
+            .ok()
+            .and_then(|h| self.processes.get(h.checked_sub(1)?)?.as_deref())
+            .ok_or_else(|| Error::new(format!("process handle {handle} isn't attached")))
+    }
+}
+
+pub struct WasmAutosplitter<H> {
+    store: Store<State<H>>,
+    update: TypedFunc<(), ()>,
+}
+
+fn memory<T>(caller: &Caller<'_, T>) -> Result<Memory, Error> {
+    caller
+        .get_export("memory")
+        .and_then(Extern::into_memory)
+        .ok_or_else(|| Error::new("the module doesn't export its memory"))
+}
+
+fn bytes(data: &[u8], ptr: u32, len: u32) -> Result<&[u8], Error> {
+    let (ptr, len) = (ptr as usize, len as usize);
+    data.get(ptr..ptr + len)
+        .ok_or_else(|| Error::new("a pointer passed to the runtime is out of bounds"))
+}
+
+fn bytes_mut(data: &mut [u8], ptr: u32, len: u32) -> Result<&mut [u8], Error> {
+    let (ptr, len) = (ptr as usize, len as usize);
+    data.get_mut(ptr..ptr + len)
+        .ok_or_else(|| Error::new("a pointer passed to the runtime is out of bounds"))
+}
+
+fn read_str<T>(caller: &Caller<'_, T>, ptr: u32, len: u32) -> Result<String, Error> {
+    let memory = memory(caller)?;
+    let bytes = bytes(memory.data(caller), ptr, len)?;
+    std::str::from_utf8(bytes)
+        .map(str::to_string)
+        .map_err(|_| Error::new("a string passed to the runtime isn't UTF-8"))
+}
+
+/// `runtime_get_os`/`runtime_get_arch`: `len_ptr` holds the buffer's size
+/// going in and the name's length coming out; false if it didn't fit.
+fn write_name<T>(
+    caller: &mut Caller<'_, T>,
+    name: &str,
+    ptr: u32,
+    len_ptr: u32,
+) -> Result<u32, Error> {
+    let memory = memory(caller)?;
+    let data = memory.data_mut(caller);
+    let capacity = u32::from_le_bytes(bytes(data, len_ptr, 4)?.try_into().unwrap());
+    let len = name.len() as u32;
+    bytes_mut(data, len_ptr, 4)?.copy_from_slice(&len.to_le_bytes());
+    if capacity < len {
+        return Ok(0);
+    }
+    bytes_mut(data, ptr, len)?.copy_from_slice(name.as_bytes());
+    Ok(1)
+}
+
+fn linker<H: Host + 'static>(engine: &Engine) -> Result<Linker<State<H>>, Error> {
+    let mut linker = Linker::<State<H>>::new(engine);
+
+    for (name, command) in [
+        ("timer_start", "start"),
+        ("timer_split", "split"),
+        ("timer_skip_split", "skipsplit"),
+        ("timer_undo_split", "undolastsplit"),
+        ("timer_reset", "reset"),
+        ("timer_pause_game_time", "pausegametime"),
+        ("timer_resume_game_time", "resumegametime"),
+    ] {
+        linker.func_wrap("env", name, move |mut caller: Caller<'_, State<H>>| {
+            caller.data_mut().host.send(command);
+        })?;
+    }
+    linker.func_wrap(
+        "env",
+        "timer_set_game_time",
+        |mut caller: Caller<'_, State<H>>, secs: i64, nanos: i32| {
+            let ms = secs
+                .saturating_mul(1000)
+                .saturating_add(nanos as i64 / 1_000_000);
+            caller.data_mut().host.send(&format!("setgametime {ms}"));
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "timer_get_state",
+        |mut caller: Caller<'_, State<H>>| match caller.data_mut().host.timer_state() {
+            TimerState::NotStarted => 0u32,
+            TimerState::Running => 1,
+            TimerState::Paused => 2,
+            TimerState::Ended => 3,
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "timer_set_variable",
+        |_: Caller<'_, State<H>>, _: u32, _: u32, _: u32, _: u32| {},
+    )?;
+
+    linker.func_wrap(
+        "env",
+        "process_attach",
+        |mut caller: Caller<'_, State<H>>, ptr: u32, len: u32| -> Result<u64, Error> {
+            let name = read_str(&caller, ptr, len)?;
+            let state = caller.data_mut();
+            let Some(process) = state.host.attach(&name) else {
+                return Ok(0);
+            };
+            state.processes.push(Some(process));
+            Ok(state.processes.len() as u64)
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "process_detach",
+        |mut caller: Caller<'_, State<H>>, handle: u64| -> Result<(), Error> {
+            caller.data().process(handle)?;
+            caller.data_mut().processes[handle as usize - 1] = None;
+            Ok(())
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "process_is_open",
+        |caller: Caller<'_, State<H>>, handle: u64| -> Result<u32, Error> {
+            Ok(caller.data().process(handle)?.is_open() as u32)
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "process_read",
+        |mut caller: Caller<'_, State<H>>,
+         handle: u64,
+         address: u64,
+         ptr: u32,
+         len: u32|
+         -> Result<u32, Error> {
+            let memory = memory(&caller)?;
+            let (data, state) = memory.data_and_store_mut(&mut caller);
+            let process = state.process(handle)?;
+            Ok(process.read(address, bytes_mut(data, ptr, len)?) as u32)
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "process_get_module_address",
+        |caller: Caller<'_, State<H>>, handle: u64, ptr: u32, len: u32| -> Result<u64, Error> {
+            let name = read_str(&caller, ptr, len)?;
+            Ok(caller
+                .data()
+                .process(handle)?
+                .module_address(&name)
+                .unwrap_or(0))
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "process_get_module_size",
+        |caller: Caller<'_, State<H>>, handle: u64, ptr: u32, len: u32| -> Result<u64, Error> {
+            let name = read_str(&caller, ptr, len)?;
+            Ok(caller
+                .data()
+                .process(handle)?
+                .module_size(&name)
+                .unwrap_or(0))
+        },
+    )?;
+
+    linker.func_wrap(
+        "env",
+        "runtime_set_tick_rate",
+        |mut caller: Caller<'_, State<H>>, ticks_per_second: f64| {
+            caller.data_mut().tick_rate = Some(ticks_per_second);
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "runtime_print_message",
+        |mut caller: Caller<'_, State<H>>, ptr: u32, len: u32| -> Result<(), Error> {
+            let message = read_str(&caller, ptr, len)?;
+            caller.data_mut().host.print(&message);
+            Ok(())
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "runtime_get_os",
+        |mut caller: Caller<'_, State<H>>, ptr: u32, len_ptr: u32| {
+            write_name(&mut caller, std::env::consts::OS, ptr, len_ptr)
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "runtime_get_arch",
+        |mut caller: Caller<'_, State<H>>, ptr: u32, len_ptr: u32| {
+            write_name(&mut caller, std::env::consts::ARCH, ptr, len_ptr)
+        },
+    )?;
+
+    linker.func_wrap(
+        "env",
+        "user_settings_add_bool",
+        |_: Caller<'_, State<H>>, _: u32, _: u32, _: u32, _: u32, default: u32| default,
+    )?;
+    linker.func_wrap(
+        "env",
+        "user_settings_add_title",
+        |_: Caller<'_, State<H>>, _: u32, _: u32, _: u32, _: u32, _: u32| {},
+    )?;
+    linker.func_wrap(
+        "env",
+        "user_settings_set_tooltip",
+        |_: Caller<'_, State<H>>, _: u32, _: u32, _: u32, _: u32| {},
+    )?;
+
+    Ok(linker)
+}
+
+impl<H: Host + 'static> WasmAutosplitter<H> {
+    /// Compiles and instantiates a module, running its `_initialize` or
+    /// `_start` export once if it has one.
+    pub fn new(wasm: &[u8], host: H) -> Result<Self, String> {
+        let engine = Engine::default();
+        let module = Module::new(&engine, wasm).map_err(|e| format!("not a valid module: {e}"))?;
+        let mut linker = linker::<H>(&engine).map_err(|e| e.to_string())?;
+        for import in module.imports() {
+            let ExternType::Func(ty) = import.ty() else {
+                continue;
+            };
+            let (module, name) = (import.module().to_string(), import.name().to_string());
+            let message = format!("'{module}::{name}' isn't supported by this runtime");
+            // Only fails for names that are already defined, i.e. supported.
+            let _ = linker.func_new(&module, &name, ty.clone(), move |_, _, _| {
+                Err(Error::new(message.clone()))
+            });
+        }
+
+        let mut store = Store::new(
+            &engine,
+            State {
+                host,
+                processes: Vec::new(),
+                tick_rate: None,
+            },
+        );
+        let instance = linker
+            .instantiate(&mut store, &module)
+            .and_then(|pre| pre.start(&mut store))
+            .map_err(|e| format!("couldn't instantiate the module: {e}"))?;
+        let update = instance
+            .get_typed_func::<(), ()>(&store, "update")
+            .map_err(|_| "the module doesn't export an 'update' function".to_string())?;
+
+        let init = ["_initialize", "_start"]
+            .into_iter()
+            .find_map(|name| instance.get_typed_func::<(), ()>(&store, name).ok());
+        if let Some(init) = init {
+            init.call(&mut store, ())
+                .map_err(|e| format!("the module failed to start: {e}"))?;
+        }
+        Ok(Self { store, update })
+    }
+
+    /// Runs one tick of the module.
+    pub fn update(&mut self) -> Result<(), String> {
+        self.update
+            .call(&mut self.store, ())
+            .map_err(|e| format!("the module failed: {e}"))
+    }
+
+    /// How long to wait between ticks, if the module asked for a rate.
+    pub fn tick_interval(&self) -> Option<Duration> {
+        let rate = self.store.data().tick_rate?;
+        (rate > 0.0 && rate.is_finite()).then(|| Duration::from_secs_f64(1.0 / rate))
+    }
+
+    pub fn host(&self) -> &H {
+        &self.store.data().host
+    }
+
+    pub fn host_mut(&mut self) -> &mut H {
+        &mut self.store.data_mut().host
+    }
+}
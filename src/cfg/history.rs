@@ -1,9 +1,14 @@
+use std::borrow::Cow;
 use std::path::PathBuf;
 
+use egui_plot::{Bar, BarChart, Legend, Line, Plot, PlotPoints};
 use openspeedrun::Run;
+use openspeedrun::core::attempts::{AttemptFilter, Completion, attempt_comparison_name};
+use openspeedrun::core::outliers::OutlierReason;
 use openspeedrun::core::split::{
     COMPARISON_BEST_SEGMENTS, COMPARISON_PERSONAL_BEST, ComparisonTime, TimingMethod,
 };
+use openspeedrun::core::stats::{progression, split_statistics};
 use openspeedrun::formats::csv;
 use rfd::FileDialog;
 
@@ -16,11 +21,16 @@ enum Tab {
     Attempts,
     PbHistory,
     SplitHistory,
+    Outliers,
+    Statistics,
+    Progression,
 }
 
 enum PendingExport {
     Attempts,
     Segments,
+    Statistics,
+    Progression,
 }
 
 pub struct History {
@@ -28,10 +38,96 @@ pub struct History {
     pub run: Run,
     active_tab: Tab,
     confirm_clear: bool,
+    /// Progression chart x axis: dates rather than attempt numbers.
+    progression_by_date: bool,
+    /// Which attempts are listed. Its tag also narrows the statistics,
+    /// progression and exports.
+    filter: AttemptFilter,
+    filter_text: FilterText,
+    /// The attempt open in the details window.
+    details: Option<AttemptDetails>,
+    /// The attempt being edited: run index, note and comma-separated tags.
+    editing_attempt: Option<(u32, String, String)>,
     export_status: Option<(String, bool)>,
     pending_export: Option<(PendingExport, PendingDialog)>,
 }
 
+/// The attempt filters' date and time bounds as typed, so a half-written
+/// date isn't reformatted under the cursor; parsed into `History::filter`.
+#[derive(Default)]
+struct FilterText {
+    from: String,
+    to: String,
+    min_time: String,
+    max_time: String,
+}
+
+/// What the details window puts next to the attempt.
+#[derive(Clone, PartialEq)]
+enum CompareWith {
+    Nothing,
+    Comparison(String),
+    /// Another attempt, used as a comparison just for this window.
+    Attempt(u32),
+}
+
+struct AttemptDetails {
+    run_index: u32,
+    compare: CompareWith,
+}
+
+/// `h:mm:ss[.fff]` or `mm:ss[.fff]`.
+fn parse_duration(text: &str) -> Option<chrono::Duration> {
+    let parts: Vec<&str> = text.trim().split(':').collect();
+    if !(2..=3).contains(&parts.len()) {
+        return None;
+    }
+    let (whole, fraction) = match parts[parts.len() - 1].split_once('.') {
+        Some((whole, fraction)) => (whole, fraction),
+        None => (parts[parts.len() - 1], ""),
+    };
+    let millis: String = fraction
+        .chars()
+        .chain(std::iter::repeat('0'))
+        .take(3)
+        .collect();
+
+    let mut total_secs: i64 = 0;
+    for part in &parts[..parts.len() - 1] {
+        total_secs = total_secs * 60 + part.parse::<i64>().ok()?;
+    }
+    total_secs = total_secs * 60 + whole.parse::<i64>().ok()?;
+    Some(chrono::Duration::milliseconds(
+        total_secs * 1000 + millis.parse::<i64>().ok()?,
+    ))
+}
+
+/// A signed delta: `+00:01.250`, `-01:02.000`.
+fn format_delta(delta: chrono::Duration) -> String {
+    let sign = if delta < chrono::Duration::zero() {
+        '-'
+    } else {
+        '+'
+    };
+    format!("{sign}{}", format_duration(delta.abs()))
+}
+
+fn delta_label(ui: &mut egui::Ui, delta: Option<chrono::Duration>) {
+    match delta {
+        Some(delta) => {
+            let color = if delta < chrono::Duration::zero() {
+                style::SUCCESS
+            } else {
+                style::ERROR
+            };
+            ui.label(egui::RichText::new(format_delta(delta)).color(color));
+        }
+        None => {
+            ui.label("-");
+        }
+    }
+}
+
 fn format_duration(duration: chrono::Duration) -> String {
     let total_millis = duration.num_milliseconds();
     let hours = total_millis / 3_600_000;
@@ -55,6 +151,11 @@ impl History {
             run,
             active_tab: Tab::Attempts,
             confirm_clear: false,
+            progression_by_date: false,
+            filter: AttemptFilter::default(),
+            filter_text: FilterText::default(),
+            details: None,
+            editing_attempt: None,
             export_status: None,
             pending_export: None,
         }
@@ -72,11 +173,31 @@ impl History {
                 ("Attempts", Tab::Attempts),
                 ("PB History", Tab::PbHistory),
                 ("Split History", Tab::SplitHistory),
+                ("Outliers", Tab::Outliers),
+                ("Statistics", Tab::Statistics),
+                ("Progression", Tab::Progression),
             ] {
                 if ui.selectable_label(self.active_tab == tab, label).clicked() {
                     self.active_tab = tab;
                 }
             }
+
+            let tags = self.run.tags();
+            if !tags.is_empty() || self.filter.tag.is_some() {
+                ui.separator();
+                ui.label("Tag:");
+                egui::ComboBox::from_id_salt("history_tag_filter")
+                    .selected_text(self.filter.tag.as_deref().unwrap_or("All attempts"))
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut self.filter.tag, None, "All attempts");
+                        for tag in tags {
+                            let label = tag.clone();
+                            ui.selectable_value(&mut self.filter.tag, Some(tag), label);
+                        }
+                    })
+                    .response
+                    .on_hover_text("Attempts, statistics, progression and exports cover only attempts with this tag");
+            }
         });
 
         ui.horizontal(|ui| {
@@ -121,6 +242,40 @@ impl History {
                     }),
                 ));
             }
+
+            if ui
+                .button(format!("{} Export statistics CSV", egui_phosphor::regular::DOWNLOAD_SIMPLE))
+                .on_hover_text("One row per split: attempts that reached it, resets there, and its segment time spread")
+                .clicked()
+            {
+                let default_name = format!("{}_statistics.csv", self.run.title);
+                self.pending_export = Some((
+                    PendingExport::Statistics,
+                    PendingDialog::spawn(move || {
+                        FileDialog::new()
+                            .set_file_name(default_name)
+                            .add_filter("CSV", &["csv"])
+                            .save_file()
+                    }),
+                ));
+            }
+
+            if ui
+                .button(format!("{} Export progression CSV", egui_phosphor::regular::DOWNLOAD_SIMPLE))
+                .on_hover_text("One row per attempt: the Sum of Best and Personal Best right after it")
+                .clicked()
+            {
+                let default_name = format!("{}_progression.csv", self.run.title);
+                self.pending_export = Some((
+                    PendingExport::Progression,
+                    PendingDialog::spawn(move || {
+                        FileDialog::new()
+                            .set_file_name(default_name)
+                            .add_filter("CSV", &["csv"])
+                            .save_file()
+                    }),
+                ));
+            }
         });
 
         if self.pending_export.is_some() {
@@ -135,8 +290,10 @@ impl History {
         {
             if let Some(path) = path {
                 let csv_text = match kind {
-                    PendingExport::Attempts => csv::attempts_csv(&self.run),
-                    PendingExport::Segments => csv::segments_csv(&self.run),
+                    PendingExport::Attempts => csv::attempts_csv(&self.shown_run()),
+                    PendingExport::Segments => csv::segments_csv(&self.shown_run()),
+                    PendingExport::Statistics => csv::split_statistics_csv(&self.shown_run()),
+                    PendingExport::Progression => csv::progression_csv(&self.shown_run()),
                 };
                 self.export_status = Some(match std::fs::write(&path, csv_text) {
                     Ok(()) => (format!("Exported to {}", path.display()), false),
@@ -192,6 +349,9 @@ impl History {
                 });
         }
 
+        self.edit_attempt_window(ctx);
+        self.details_window(ctx);
+
         ui.separator();
 
         match self.active_tab {
@@ -200,7 +360,13 @@ impl History {
                     ui.label("No attempts recorded yet.");
                     return;
                 }
+                self.filters_ui(ui);
+                let method = self.run.timing_method;
+                let open_details = self.details.as_ref().map(|d| d.run_index);
                 let mut delete_index: Option<u32> = None;
+                let mut edit_index: Option<u32> = None;
+                let mut details_index: Option<u32> = None;
+                let mut compare_index: Option<u32> = None;
                 style::section_card(ui, "Attempts", egui_phosphor::regular::LIST, |ui| {
                     ScrollArea::vertical().show(ui, |ui| {
                         Grid::new("attempt_history_grid")
@@ -213,10 +379,17 @@ impl History {
                                 ui.label("Game Time");
                                 ui.label("Ended");
                                 ui.label("Is PB");
+                                ui.label("Tags");
+                                ui.label("Note");
                                 ui.label("");
                                 ui.end_row();
 
-                                for attempt in &self.run.attempt_history {
+                                for attempt in self
+                                    .run
+                                    .attempt_history
+                                    .iter()
+                                    .filter(|a| self.filter.matches(a, method))
+                                {
                                     ui.label(attempt.run_index.to_string());
                                     let date_str = attempt
                                         .date
@@ -236,7 +409,18 @@ impl History {
                                         .unwrap_or_else(|| "-".to_string());
                                     ui.label(game_time_str);
 
-                                    ui.label(if attempt.ended { "Yes" } else { "No" });
+                                    let ended_text = if attempt.ended {
+                                        "Yes".to_string()
+                                    } else {
+                                        match attempt
+                                            .reset_split_index
+                                            .and_then(|i| self.run.splits.get(i))
+                                        {
+                                            Some(split) => format!("Reset at {}", split.name),
+                                            None => "No".to_string(),
+                                        }
+                                    };
+                                    ui.label(ended_text);
 
                                     let is_pb = self
                                         .run
@@ -250,19 +434,64 @@ impl History {
                                     };
                                     ui.label(pb_text);
 
-                                    if ui
-                                        .small_button(
-                                            RichText::new(egui_phosphor::regular::TRASH)
-                                                .color(style::ERROR),
-                                        )
-                                        .on_hover_text(
-                                            "Delete this attempt (and its segments) everywhere",
-                                        )
-                                        .clicked()
-                                    {
-                                        delete_index = Some(attempt.run_index);
+                                    ui.label(attempt.tags.join(", "));
+                                    match &attempt.note {
+                                        Some(note) => {
+                                            let first_line = note.lines().next().unwrap_or("");
+                                            ui.label(first_line).on_hover_text(note);
+                                        }
+                                        None => {
+                                            ui.label("");
+                                        }
                                     }
 
+                                    ui.horizontal(|ui| {
+                                        if ui
+                                            .small_button(egui_phosphor::regular::MAGNIFYING_GLASS)
+                                            .on_hover_text("Split-by-split breakdown")
+                                            .clicked()
+                                        {
+                                            details_index = Some(attempt.run_index);
+                                        }
+                                        let can_compare = open_details
+                                            .is_some_and(|open| open != attempt.run_index);
+                                        if ui
+                                            .add_enabled(
+                                                can_compare,
+                                                egui::Button::new(
+                                                    egui_phosphor::regular::ARROWS_LEFT_RIGHT,
+                                                )
+                                                .small(),
+                                            )
+                                            .on_hover_text(
+                                                "Compare side by side with the attempt open in \
+                                                 the details window",
+                                            )
+                                            .clicked()
+                                        {
+                                            compare_index = Some(attempt.run_index);
+                                        }
+                                        if ui
+                                            .small_button(egui_phosphor::regular::PENCIL_SIMPLE)
+                                            .on_hover_text("Edit this attempt's note and tags")
+                                            .clicked()
+                                        {
+                                            edit_index = Some(attempt.run_index);
+                                        }
+                                        if ui
+                                            .small_button(
+                                                RichText::new(egui_phosphor::regular::TRASH)
+                                                    .color(style::ERROR),
+                                            )
+                                            .on_hover_text(
+                                                "Delete this attempt (and its segments) everywhere",
+                                            )
+                                            .clicked()
+                                        {
+                                            delete_index = Some(attempt.run_index);
+                                        }
+                                    });
+
                                     ui.end_row();
                                 }
                             });
@@ -272,6 +501,24 @@ impl History {
                 if let Some(run_index) = delete_index {
                     self.delete_attempt(run_index);
                 }
+                if let Some(run_index) = details_index {
+                    self.details = Some(AttemptDetails {
+                        run_index,
+                        compare: CompareWith::Nothing,
+                    });
+                }
+                if let (Some(run_index), Some(details)) = (compare_index, &mut self.details) {
+                    details.compare = CompareWith::Attempt(run_index);
+                }
+                if let Some(attempt) = edit_index
+                    .and_then(|i| self.run.attempt_history.iter().find(|a| a.run_index == i))
+                {
+                    self.editing_attempt = Some((
+                        attempt.run_index,
+                        attempt.note.clone().unwrap_or_default(),
+                        attempt.tags.join(", "),
+                    ));
+                }
             }
 
             Tab::PbHistory => {
@@ -378,12 +625,20 @@ impl History {
 
                                         for entry in &split.segment_history {
                                             ui.label(entry.run_index.to_string());
-                                            ui.label(
-                                                entry
-                                                    .real_time
-                                                    .map(format_duration)
-                                                    .unwrap_or_else(|| "-".to_string()),
-                                            );
+                                            let mut real_time_str = entry
+                                                .real_time
+                                                .map(format_duration)
+                                                .unwrap_or_else(|| "-".to_string());
+                                            if entry.combined {
+                                                real_time_str.push_str(" (combined)");
+                                            }
+                                            let label = ui.label(real_time_str);
+                                            if entry.combined {
+                                                label.on_hover_text(
+                                                    "Spans the skipped split(s) right before \
+                                                     this one too, so it never counts as a gold",
+                                                );
+                                            }
                                             ui.label(
                                                 entry
                                                     .game_time
@@ -417,6 +672,586 @@ impl History {
                     self.delete_segment_entry(split_index, run_index);
                 }
             }
+
+            Tab::Outliers => {
+                let method = self.run.timing_method;
+                let outliers = self.run.segment_outliers(method);
+                if outliers.is_empty() {
+                    ui.label("No suspicious segment times found.");
+                    return;
+                }
+                let mut remove: Vec<(usize, u32)> = Vec::new();
+                style::section_card(ui, "Outliers", egui_phosphor::regular::WARNING, |ui| {
+                    ui.label(
+                        RichText::new(
+                            "Segment times that look like a mis-split: far from the split's \
+                             median, or a combined segment faster than its Best Segments add \
+                             up to. Removing one recomputes Best Segments and the Personal Best.",
+                        )
+                        .color(style::TEXT_MUTED),
+                    );
+                    if ui
+                        .button(format!(
+                            "{} Remove all {}",
+                            egui_phosphor::regular::TRASH,
+                            outliers.len()
+                        ))
+                        .clicked()
+                    {
+                        remove = outliers
+                            .iter()
+                            .map(|o| (o.split_index, o.run_index))
+                            .collect();
+                    }
+
+                    ScrollArea::vertical().show(ui, |ui| {
+                        Grid::new("outliers_grid")
+                            .striped(true)
+                            .min_col_width(100.0)
+                            .show(ui, |ui| {
+                                ui.label("Split");
+                                ui.label("Run #");
+                                ui.label("Time");
+                                ui.label("Why");
+                                ui.label("");
+                                ui.end_row();
+
+                                for outlier in &outliers {
+                                    let split = &self.run.splits[outlier.split_index];
+                                    ui.label(format!(
+                                        "#{}: {}",
+                                        outlier.split_index + 1,
+                                        split.name
+                                    ));
+                                    ui.label(outlier.run_index.to_string());
+                                    ui.label(format_duration(outlier.time));
+                                    ui.label(match &outlier.reason {
+                                        OutlierReason::FarFromMedian { z, median } => format!(
+                                            "{} than usual (median {}, z = {z:.1})",
+                                            if *z < 0.0 {
+                                                "Much faster"
+                                            } else {
+                                                "Much slower"
+                                            },
+                                            format_duration(*median)
+                                        ),
+                                        OutlierReason::FasterThanBestSegments {
+                                            span_start,
+                                            sum_of_best,
+                                        } => format!(
+                                            "Beats the Best Segments from {} ({})",
+                                            self.run.splits[*span_start].name,
+                                            format_duration(*sum_of_best)
+                                        ),
+                                    });
+
+                                    if ui
+                                        .small_button(
+                                            RichText::new(egui_phosphor::regular::TRASH)
+                                                .color(style::ERROR),
+                                        )
+                                        .on_hover_text("Delete this split's time for this run only")
+                                        .clicked()
+                                    {
+                                        remove = vec![(outlier.split_index, outlier.run_index)];
+                                    }
+
+                                    ui.end_row();
+                                }
+                            });
+                    });
+                });
+
+                if !remove.is_empty() {
+                    self.run.remove_segment_entries(&remove);
+                    self.persist_and_reload();
+                }
+            }
+
+            Tab::Progression => self.progression_ui(ui),
+
+            Tab::Statistics => {
+                if self.run.attempt_history.is_empty() {
+                    ui.label("No attempts recorded yet.");
+                    return;
+                }
+                let shown = self.shown_run();
+                let stats = split_statistics(&shown, self.run.timing_method);
+                let finished = shown.attempt_history.iter().filter(|a| a.ended).count();
+                let percent = |rate: Option<f64>| {
+                    rate.map(|r| format!("{:.1}%", r * 100.0))
+                        .unwrap_or_else(|| "-".to_string())
+                };
+
+                ScrollArea::vertical().show(ui, |ui| {
+                    style::section_card(ui, "Survival", egui_phosphor::regular::CHART_BAR, |ui| {
+                        ui.label(
+                            RichText::new(
+                                "Attempts that reached each split; the drop after a bar is \
+                                     how many were reset there.",
+                            )
+                            .color(style::TEXT_MUTED),
+                        );
+                        let mut bars: Vec<Bar> = stats
+                            .iter()
+                            .zip(&self.run.splits)
+                            .enumerate()
+                            .map(|(i, (stats, split))| {
+                                Bar::new(i as f64, stats.reached as f64)
+                                    .name(&split.name)
+                                    .fill(style::ACCENT)
+                            })
+                            .collect();
+                        bars.push(
+                            Bar::new(stats.len() as f64, finished as f64)
+                                .name("Finished")
+                                .fill(style::SUCCESS),
+                        );
+                        Plot::new("survival_funnel")
+                            .height(180.0)
+                            .show_x(false)
+                            .allow_drag(false)
+                            .allow_zoom(false)
+                            .allow_scroll(false)
+                            .allow_boxed_zoom(false)
+                            .show(ui, |plot_ui| {
+                                plot_ui.bar_chart(BarChart::new("Reached", bars).width(0.8));
+                            });
+                    });
+                    ui.add_space(style::SPACE_SM);
+
+                    style::section_card(ui, "Per split", egui_phosphor::regular::TABLE, |ui| {
+                        Grid::new("split_statistics_grid")
+                            .striped(true)
+                            .min_col_width(70.0)
+                            .show(ui, |ui| {
+                                for header in [
+                                    "Split",
+                                    "Reached",
+                                    "Resets",
+                                    "Reset rate",
+                                    "Mean",
+                                    "Median",
+                                    "Std. dev.",
+                                    "Gold rate",
+                                ] {
+                                    ui.label(header);
+                                }
+                                ui.end_row();
+
+                                let duration = |d: Option<chrono::Duration>| {
+                                    d.map(format_duration).unwrap_or_else(|| "-".to_string())
+                                };
+                                for (split, stats) in self.run.splits.iter().zip(&stats) {
+                                    ui.label(&split.name);
+                                    ui.label(stats.reached.to_string());
+                                    ui.label(stats.resets.to_string());
+                                    ui.label(percent(stats.reset_rate));
+                                    ui.label(duration(stats.mean));
+                                    ui.label(duration(stats.median));
+                                    ui.label(duration(stats.std_dev));
+                                    ui.label(percent(stats.gold_rate));
+                                    ui.end_row();
+                                }
+                            });
+                    });
+                });
+            }
+        }
+    }
+
+    fn progression_ui(&mut self, ui: &mut egui::Ui) {
+        let points = progression(&self.shown_run(), self.run.timing_method);
+        if points.is_empty() {
+            ui.label("No attempts recorded yet.");
+            return;
+        }
+
+        ui.horizontal(|ui| {
+            ui.label("X axis:");
+            ui.selectable_value(&mut self.progression_by_date, false, "Attempt #");
+            ui.selectable_value(&mut self.progression_by_date, true, "Date");
+        });
+        let by_date = self.progression_by_date;
+        if by_date && points.iter().all(|p| p.date.is_none()) {
+            ui.label("None of these attempts has a date.");
+            return;
+        }
+
+        // Attempts without a date just drop out of the by-date chart.
+        let series = |value: fn(&_) -> Option<chrono::Duration>| -> Vec<[f64; 2]> {
+            points
+                .iter()
+                .filter_map(|p| {
+                    let x = if by_date {
+                        p.date?.timestamp() as f64
+                    } else {
+                        p.attempts as f64
+                    };
+                    Some([x, value(p)?.num_milliseconds() as f64])
+                })
+                .collect()
+        };
+        let sum_of_best = series(|p| p.sum_of_best);
+        let personal_best = series(|p| p.personal_best);
+
+        style::section_card(
+            ui,
+            "Sum of Best and PB",
+            egui_phosphor::regular::CHART_LINE_DOWN,
+            |ui| {
+                Plot::new("progression")
+                    .height(260.0)
+                    .legend(Legend::default())
+                    .allow_boxed_zoom(false)
+                    .x_axis_formatter(move |mark, _| {
+                        if by_date {
+                            chrono::DateTime::from_timestamp(mark.value as i64, 0)
+                                .map(|d| d.format("%Y-%m-%d").to_string())
+                                .unwrap_or_default()
+                        } else {
+                            format!("#{}", mark.value as i64)
+                        }
+                    })
+                    .y_axis_formatter(|mark, _| {
+                        format_duration(chrono::Duration::milliseconds(mark.value as i64))
+                    })
+                    .label_formatter(|name, point| {
+                        let time = format_duration(chrono::Duration::milliseconds(point.y as i64));
+                        if name.is_empty() {
+                            time
+                        } else {
+                            format!("{name}: {time}")
+                        }
+                    })
+                    .show(ui, |plot_ui| {
+                        plot_ui.line(
+                            Line::new("Sum of Best", PlotPoints::new(sum_of_best))
+                                .color(style::ACCENT)
+                                .width(2.0_f32),
+                        );
+                        plot_ui.line(
+                            Line::new("Personal Best", PlotPoints::new(personal_best))
+                                .color(style::SUCCESS)
+                                .width(2.0_f32),
+                        );
+                    });
+            },
+        );
+    }
+
+    fn filters_ui(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new("Filters")
+            .id_salt("attempt_filters")
+            .show(ui, |ui| {
+                let text = &mut self.filter_text;
+                ui.horizontal(|ui| {
+                    ui.label("From:");
+                    ui.add(
+                        egui::TextEdit::singleline(&mut text.from)
+                            .hint_text("YYYY-MM-DD")
+                            .desired_width(90.0),
+                    );
+                    ui.label("To:");
+                    ui.add(
+                        egui::TextEdit::singleline(&mut text.to)
+                            .hint_text("YYYY-MM-DD")
+                            .desired_width(90.0),
+                    );
+                    ui.separator();
+                    ui.label("Time from:");
+                    ui.add(
+                        egui::TextEdit::singleline(&mut text.min_time)
+                            .hint_text("h:mm:ss")
+                            .desired_width(80.0),
+                    );
+                    ui.label("to:");
+                    ui.add(
+                        egui::TextEdit::singleline(&mut text.max_time)
+                            .hint_text("h:mm:ss")
+                            .desired_width(80.0),
+                    );
+                });
+                ui.horizontal(|ui| {
+                    for (label, completion) in [
+                        ("All", Completion::Any),
+                        ("Finished", Completion::Finished),
+                        ("Reset", Completion::Reset),
+                    ] {
+                        ui.selectable_value(&mut self.filter.completion, completion, label);
+                    }
+                    if ui.button("Clear filters").clicked() {
+                        self.filter_text = FilterText::default();
+                        self.filter = AttemptFilter {
+                            tag: self.filter.tag.take(),
+                            ..AttemptFilter::default()
+                        };
+                    }
+                });
+
+                // A bound that doesn't parse (yet) is just left out.
+                let text = &self.filter_text;
+                let date = |t: &str| chrono::NaiveDate::parse_from_str(t.trim(), "%Y-%m-%d").ok();
+                self.filter.from = date(&text.from);
+                self.filter.to = date(&text.to);
+                self.filter.min_time = parse_duration(&text.min_time);
+                self.filter.max_time = parse_duration(&text.max_time);
+                let invalid = [
+                    (&text.from, self.filter.from.is_none()),
+                    (&text.to, self.filter.to.is_none()),
+                    (&text.min_time, self.filter.min_time.is_none()),
+                    (&text.max_time, self.filter.max_time.is_none()),
+                ]
+                .iter()
+                .any(|(text, unparsed)| !text.trim().is_empty() && *unparsed);
+                if invalid {
+                    style::status_label(
+                        ui,
+                        "Dates are YYYY-MM-DD and times h:mm:ss (or mm:ss); \
+                         anything else is ignored.",
+                        true,
+                    );
+                }
+            });
+    }
+
+    /// Attempt `details.run_index` split by split: segment, split time,
+    /// deltas to the golds and PB it was up against, and optionally a
+    /// comparison or another attempt alongside.
+    fn details_window(&mut self, ctx: &egui::Context) {
+        use egui::{Grid, RichText, ScrollArea};
+
+        let Some(details) = &mut self.details else {
+            return;
+        };
+        let run = &self.run;
+        let method = run.timing_method;
+        let Some(attempt) = run
+            .attempt_history
+            .iter()
+            .find(|a| a.run_index == details.run_index)
+        else {
+            self.details = None;
+            return;
+        };
+
+        let splits = run.attempt_splits(attempt.run_index, method);
+        // The other side's split times, summed from its segments.
+        let other: Option<Vec<Option<chrono::Duration>>> = match &details.compare {
+            CompareWith::Nothing => None,
+            CompareWith::Attempt(other) => Some(
+                run.attempt_splits(*other, method)
+                    .into_iter()
+                    .map(|s| s.time)
+                    .collect(),
+            ),
+            CompareWith::Comparison(name) => {
+                let mut total = chrono::Duration::zero();
+                Some(
+                    run.splits
+                        .iter()
+                        .map(|s| {
+                            let segment = s.comparison_time(name, method)?;
+                            total += segment;
+                            Some(total)
+                        })
+                        .collect(),
+                )
+            }
+        };
+        let other_label = match &details.compare {
+            CompareWith::Nothing => String::new(),
+            CompareWith::Comparison(name) => name.clone(),
+            CompareWith::Attempt(other) => attempt_comparison_name(*other),
+        };
+
+        let mut open = true;
+        let mut save_ghost = false;
+        egui::Window::new(format!("Attempt #{}", attempt.run_index))
+            .open(&mut open)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    if let Some(date) = attempt.date {
+                        ui.label(date.format("%Y-%m-%d %H:%M:%S").to_string());
+                    }
+                    ui.label(if attempt.ended {
+                        "Finished".to_string()
+                    } else {
+                        match attempt.reset_split_index.and_then(|i| run.splits.get(i)) {
+                            Some(split) => format!("Reset at {}", split.name),
+                            None => "Reset".to_string(),
+                        }
+                    });
+                    if !attempt.tags.is_empty() {
+                        ui.label(format!("Tags: {}", attempt.tags.join(", ")));
+                    }
+                });
+                if let Some(note) = &attempt.note {
+                    ui.label(RichText::new(note).color(style::TEXT_MUTED));
+                }
+
+                ui.horizontal(|ui| {
+                    ui.label("Compare with:");
+                    egui::ComboBox::from_id_salt("details_compare")
+                        .selected_text(if other_label.is_empty() {
+                            "Nothing"
+                        } else {
+                            &other_label
+                        })
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(
+                                &mut details.compare,
+                                CompareWith::Nothing,
+                                "Nothing",
+                            );
+                            for name in run.comparison_names() {
+                                let label = name.clone();
+                                ui.selectable_value(
+                                    &mut details.compare,
+                                    CompareWith::Comparison(name),
+                                    label,
+                                );
+                            }
+                            ui.separator();
+                            for other in run.attempt_history.iter().rev() {
+                                if other.run_index == attempt.run_index {
+                                    continue;
+                                }
+                                ui.selectable_value(
+                                    &mut details.compare,
+                                    CompareWith::Attempt(other.run_index),
+                                    format!("Attempt #{}", other.run_index),
+                                );
+                            }
+                        });
+                    if ui
+                        .button("Save as comparison")
+                        .on_hover_text(format!(
+                            "Keep this attempt as the \"{}\" comparison, to race against",
+                            attempt_comparison_name(attempt.run_index)
+                        ))
+                        .clicked()
+                    {
+                        save_ghost = true;
+                    }
+                });
+
+                ScrollArea::vertical().max_height(420.0).show(ui, |ui| {
+                    Grid::new("attempt_details_grid")
+                        .striped(true)
+                        .min_col_width(80.0)
+                        .show(ui, |ui| {
+                            ui.label("Split");
+                            ui.label("Segment");
+                            ui.label("Time");
+                            ui.label("vs Gold then");
+                            ui.label("vs PB then");
+                            if other.is_some() {
+                                ui.label(&other_label);
+                                ui.label("Delta");
+                            }
+                            ui.end_row();
+
+                            let duration = |d: Option<chrono::Duration>| {
+                                d.map(format_duration).unwrap_or_else(|| "-".to_string())
+                            };
+                            for (i, (split, row)) in run.splits.iter().zip(&splits).enumerate() {
+                                ui.label(&split.name);
+                                let mut segment = duration(row.segment);
+                                if row.combined {
+                                    segment.push_str(" (combined)");
+                                }
+                                ui.label(segment);
+                                ui.label(duration(row.time));
+                                if row.combined {
+                                    ui.label("-");
+                                } else {
+                                    delta_label(ui, row.gold_delta());
+                                }
+                                delta_label(ui, row.pb_delta());
+                                if let Some(other) = &other {
+                                    let other_time = other.get(i).copied().flatten();
+                                    ui.label(duration(other_time));
+                                    delta_label(ui, row.time.zip(other_time).map(|(a, b)| a - b));
+                                }
+                                ui.end_row();
+                            }
+                        });
+                });
+            });
+
+        if save_ghost {
+            let run_index = details.run_index;
+            self.export_status = Some(match self.run.add_attempt_comparison(run_index) {
+                Ok(name) => {
+                    self.persist_and_reload();
+                    (format!("Saved the \"{name}\" comparison"), false)
+                }
+                Err(e) => (e, true),
+            });
+        }
+        if !open {
+            self.details = None;
+        }
+    }
+
+    /// The run as the tag filter shows it.
+    fn shown_run(&self) -> Cow<'_, Run> {
+        match &self.filter.tag {
+            Some(tag) => Cow::Owned(self.run.restricted_to_tag(tag)),
+            None => Cow::Borrowed(&self.run),
+        }
+    }
+
+    fn edit_attempt_window(&mut self, ctx: &egui::Context) {
+        let Some((run_index, note, tags)) = &mut self.editing_attempt else {
+            return;
+        };
+        let run_index = *run_index;
+        let mut close = false;
+        let mut save = false;
+        egui::Window::new(format!("Attempt #{run_index}"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.label("Note:");
+                ui.add(egui::TextEdit::multiline(note).desired_rows(3));
+                ui.label("Tags (comma-separated):");
+                ui.text_edit_singleline(tags);
+                ui.horizontal(|ui| {
+                    if ui.button("Cancel").clicked() {
+                        close = true;
+                    }
+                    if ui.button("Save").clicked() {
+                        save = true;
+                    }
+                });
+            });
+
+        if save {
+            let note = note.trim();
+            let note = (!note.is_empty()).then(|| note.to_string());
+            let mut unique: Vec<String> = Vec::new();
+            for tag in tags.split(',').map(str::trim).filter(|t| !t.is_empty()) {
+                if !unique.iter().any(|t| t == tag) {
+                    unique.push(tag.to_string());
+                }
+            }
+            if let Some(attempt) = self
+                .run
+                .attempt_history
+                .iter_mut()
+                .find(|a| a.run_index == run_index)
+            {
+                attempt.note = note;
+                attempt.tags = unique;
+            }
+            self.persist_and_reload();
+        }
+        if save || close {
+            self.editing_attempt = None;
         }
     }
 
@@ -2,8 +2,11 @@ use chrono::Duration;
 use eframe::egui;
 use egui::{Context, RichText, Sense, TextureHandle};
 use image::GenericImageView;
+use openspeedrun::autosplitter::config::{AutosplitterConfig, Compound, SplitTrigger};
+use openspeedrun::autosplitter::expr::Expression;
 use openspeedrun::core::split::{
-    COMPARISON_BEST_SEGMENTS, COMPARISON_PERSONAL_BEST, Run, RunVariable, Split, TimingMethod,
+    COMPARISON_BEST_SEGMENTS, COMPARISON_PERSONAL_BEST, GoalTimeGenerator, Run, RunVariable,
+    TimingMethod,
 };
 use openspeedrun::formats::{lss, native};
 use rfd::FileDialog;
@@ -34,6 +37,117 @@ pub struct SplitEditor {
     import_export_status: Option<(String, bool)>,
     speedrun_com_picker: SpeedrunComPicker,
     pending_action: Option<(PendingAction, PendingDialog)>,
+    /// `autosplitter.json` next to the run, if there is one (or why it
+    /// couldn't be read).
+    autosplitter: Option<Result<AutosplitterEdit, String>>,
+}
+
+/// The split sequence of an `autosplitter.json` being edited. Conditions
+/// are edited as text — an expression, or a JSON condition if it starts
+/// with `{` — empty meaning none; `splits` is kept as long as the run's
+/// split list.
+struct AutosplitterEdit {
+    path: PathBuf,
+    config: AutosplitterConfig,
+    start: String,
+    reset: String,
+    splits: Vec<String>,
+}
+
+impl AutosplitterEdit {
+    /// `None` if there's no `autosplitter.json` next to `run_path`.
+    fn load(run_path: &std::path::Path) -> Option<Result<Self, String>> {
+        let path = run_path.with_file_name("autosplitter.json");
+        if !path.exists() {
+            return None;
+        }
+        let text = |when: Option<&Compound>| match when {
+            Some(Compound::Expression(expression)) => expression.source().to_string(),
+            Some(when) => serde_json::to_string(when).unwrap_or_default(),
+            None => String::new(),
+        };
+        Some(
+            AutosplitterConfig::load_from_file(path.to_str().unwrap()).map(|config| Self {
+                start: text(config.start.as_ref()),
+                reset: text(config.reset.as_ref()),
+                splits: config
+                    .splits
+                    .iter()
+                    .map(|s| text(s.when.as_ref()))
+                    .collect(),
+                path,
+                config,
+            }),
+        )
+    }
+
+    /// Reads a condition's text back; empty is no condition.
+    fn parse(text: &str) -> Result<Option<Compound>, String> {
+        let text = text.trim();
+        if text.is_empty() {
+            Ok(None)
+        } else if text.starts_with('{') {
+            serde_json::from_str(text)
+                .map(Some)
+                .map_err(|e| e.to_string())
+        } else {
+            Ok(Some(Compound::Expression(Expression::new(text))))
+        }
+    }
+
+    /// Writes the file back with the edited conditions, named after `run`'s
+    /// splits. Nothing is written if a condition doesn't parse.
+    fn save(&mut self, run: &Run) -> Result<(), String> {
+        let mut config = self.config.clone();
+        config.start = Self::parse(&self.start).map_err(|e| format!("start: {e}"))?;
+        config.reset = Self::parse(&self.reset).map_err(|e| format!("reset: {e}"))?;
+        config.splits = run
+            .splits
+            .iter()
+            .zip(&self.splits)
+            .map(|(split, text)| {
+                Ok(SplitTrigger {
+                    name: split.name.clone(),
+                    when: Self::parse(text).map_err(|e| format!("{}: {e}", split.name))?,
+                })
+            })
+            .collect::<Result<_, String>>()?;
+        // Drop a sequence with nothing in it rather than write empty entries.
+        if config.splits.iter().all(|s| s.when.is_none()) {
+            config.splits.clear();
+        }
+        config.validate()?;
+
+        let json = serde_json::to_string_pretty(&config).map_err(|e| e.to_string())?;
+        fs::write(&self.path, json).map_err(|e| e.to_string())?;
+        self.config = config;
+        Ok(())
+    }
+}
+
+/// A one-line editor for a condition, flagging text that doesn't parse or
+/// (for expressions) type-check against `config`'s watches and variables.
+fn edit_condition(ui: &mut egui::Ui, label: &str, text: &mut String, config: &AutosplitterConfig) {
+    ui.horizontal(|ui| {
+        ui.label(label);
+        ui.add(
+            egui::TextEdit::singleline(text)
+                .hint_text("By hand")
+                .font(egui::TextStyle::Monospace)
+                .desired_width(f32::INFINITY),
+        );
+    });
+    let error = match AutosplitterEdit::parse(text) {
+        Ok(Some(Compound::Expression(expression))) => expression
+            .check_condition(&|name, old| config.lookup(name, old, None))
+            .err()
+            .map(|e| e.to_string()),
+        Ok(_) => None,
+        Err(e) => Some(e),
+    };
+    if let Some(error) = error {
+        style::status_label(ui, &error, true);
+    }
 }
 
 fn format_duration(duration: chrono::Duration) -> String {
@@ -120,6 +234,7 @@ impl SplitEditor {
     pub fn new(run_path: PathBuf) -> Self {
         let run = Run::load_from_file(run_path.to_str().unwrap())
             .unwrap_or_else(|_| Run::new("New Run", "Category", &["Split 1", "Split 2"]));
+        let autosplitter = AutosplitterEdit::load(&run_path);
         Self {
             run_path,
             run,
@@ -129,7 +244,24 @@ impl SplitEditor {
             import_export_status: None,
             speedrun_com_picker: SpeedrunComPicker::default(),
             pending_action: None,
+            autosplitter,
+        }
+    }
+
+    /// Saves the run, and the autosplitter's split sequence if there is
+    /// one — checked first, so a condition that doesn't parse saves neither.
+    fn save_all(&mut self) -> (String, bool) {
+        if let Some(Ok(autosplitter)) = &mut self.autosplitter
+            && let Err(e) = autosplitter.save(&self.run)
+        {
+            return (format!("Autosplitter not saved: {e}"), true);
         }
+        let status = match self.run.save_to_file(self.run_path.to_str().unwrap()) {
+            Ok(()) => ("Saved".to_string(), false),
+            Err(e) => (format!("Error saving all: {e}"), true),
+        };
+        send_message("reloadrun");
+        status
     }
 
     fn load_textures(&mut self, ctx: &Context) {
@@ -322,6 +454,48 @@ impl SplitEditor {
                                 ui.checkbox(&mut self.run.auto_update_pb, "");
                             });
 
+                            ui.horizontal(|ui| {
+                                ui.label("Promote practice golds:");
+                                ui.checkbox(&mut self.run.promote_practice_golds, "")
+                                    .on_hover_text(
+                                        "Let a segment timed in practice mode become a Best Segment",
+                                    );
+                            });
+
+                            let tags = self.run.tags();
+                            if !tags.is_empty() || self.run.comparison_tag.is_some() {
+                                ui.horizontal(|ui| {
+                                    ui.label("Comparisons from tag:");
+                                    egui::ComboBox::from_id_salt("comparison_tag")
+                                        .selected_text(
+                                            self.run
+                                                .comparison_tag
+                                                .as_deref()
+                                                .unwrap_or("All attempts"),
+                                        )
+                                        .show_ui(ui, |ui| {
+                                            ui.selectable_value(
+                                                &mut self.run.comparison_tag,
+                                                None,
+                                                "All attempts",
+                                            );
+                                            for tag in tags {
+                                                let label = tag.clone();
+                                                ui.selectable_value(
+                                                    &mut self.run.comparison_tag,
+                                                    Some(tag),
+                                                    label,
+                                                );
+                                            }
+                                        })
+                                        .response
+                                        .on_hover_text(
+                                            "Build Average/Median/Worst Segments and the \
+                                             generated comparisons only from attempts with this tag",
+                                        );
+                                });
+                            }
+
                             ui.horizontal(|ui| {
                                 ui.label("Timing method:");
                                 egui::ComboBox::from_id_salt("timing_method")
@@ -410,6 +584,72 @@ impl SplitEditor {
                                     self.run.metadata.variables.push(RunVariable::default());
                                 }
                             });
+
+                            // Each goal is spread over the splits in
+                            // proportion to their Best Segments.
+                            ui.collapsing("Goal comparisons", |ui| {
+                                let mut goal_to_remove = None;
+                                for (i, goal) in self.run.goal_comparisons.iter_mut().enumerate() {
+                                    ui.horizontal(|ui| {
+                                        ui.text_edit_singleline(&mut goal.name);
+                                        if ui
+                                            .button(RichText::new(egui_phosphor::regular::TRASH))
+                                            .clicked()
+                                        {
+                                            goal_to_remove = Some(i);
+                                        }
+                                    });
+                                    ui.horizontal(|ui| {
+                                        ui.label("Real Time:");
+                                        edit_duration(
+                                            ui,
+                                            &format!("goal_real_{i}"),
+                                            &mut goal.goal.real_time,
+                                        );
+                                    });
+                                    ui.horizontal(|ui| {
+                                        ui.label("Game Time:");
+                                        edit_duration(
+                                            ui,
+                                            &format!("goal_game_{i}"),
+                                            &mut goal.goal.game_time,
+                                        );
+                                    });
+                                }
+                                if let Some(i) = goal_to_remove {
+                                    self.run.goal_comparisons.remove(i);
+                                }
+                                if ui.button("Add goal").clicked() {
+                                    self.run.goal_comparisons.push(GoalTimeGenerator {
+                                        name: "Goal".to_string(),
+                                        ..GoalTimeGenerator::default()
+                                    });
+                                }
+                            });
+
+                            // Per-split conditions are edited with each split below.
+                            ui.collapsing("Autosplitter", |ui| match &mut self.autosplitter {
+                                None => {
+                                    ui.label(
+                                        RichText::new(
+                                            "Put an autosplitter.json next to split.json to \
+                                             autosplit from here.",
+                                        )
+                                        .color(style::TEXT_MUTED),
+                                    );
+                                }
+                                Some(Err(e)) => style::status_label(ui, e, true),
+                                Some(Ok(autosplitter)) => {
+                                    edit_condition(ui, "Start when", &mut autosplitter.start, &autosplitter.config);
+                                    edit_condition(ui, "Reset when", &mut autosplitter.reset, &autosplitter.config);
+                                    ui.label(
+                                        RichText::new(
+                                            "Restart openspeedrun-autosplitter after saving.",
+                                        )
+                                        .color(style::TEXT_MUTED),
+                                    );
+                                }
+                            });
                         },
                     );
                 },
@@ -426,10 +666,7 @@ impl SplitEditor {
                                 .button(format!("{} Add split", egui_phosphor::regular::PLUS))
                                 .clicked()
                             {
-                                self.run.splits.push(Split {
-                                    name: "New split".to_string(),
-                                    ..Split::default()
-                                });
+                                self.run.insert_split(self.run.splits.len(), "New split");
                             }
 
                             let save_button = egui::Button::new(format!(
@@ -437,13 +674,7 @@ impl SplitEditor {
                                 egui_phosphor::regular::FLOPPY_DISK
                             ));
                             if style::accent_button(ui, save_button).clicked() {
-                                self.import_export_status = Some(
-                                    match self.run.save_to_file(self.run_path.to_str().unwrap()) {
-                                        Ok(()) => ("Saved".to_string(), false),
-                                        Err(e) => (format!("Error saving all: {e}"), true),
-                                    },
-                                );
-                                send_message("reloadrun");
+                                self.import_export_status = Some(self.save_all());
                             }
                         });
 
@@ -570,9 +801,18 @@ impl SplitEditor {
 
         self.load_textures(ctx);
 
+        // Imports and speedrun.com can replace the split list wholesale.
+        if let Some(Ok(autosplitter)) = &mut self.autosplitter {
+            autosplitter
+                .splits
+                .resize(self.run.splits.len(), String::new());
+        }
+
         egui::ScrollArea::vertical().show(ui, |ui| {
             let mut to_remove = None;
-            let mut swap_request: Option<(usize, usize)> = None;
+            let mut insert_request = None;
+            let mut split_in_two_request = None;
+            let mut move_request: Option<(usize, usize)> = None;
             let splits_len = self.run.splits.len();
 
             for i in 0..splits_len {
@@ -595,7 +835,7 @@ impl SplitEditor {
                     && let Some(from_index) = self.dragging_split_index
                     && from_index != i
                 {
-                    swap_request = Some((from_index, i));
+                    move_request = Some((from_index, i));
                 }
 
                 let split = &mut self.run.splits[i];
@@ -614,7 +854,7 @@ impl SplitEditor {
                             .clicked()
                             && i > 0
                         {
-                            swap_request = Some((i, i - 1));
+                            move_request = Some((i, i - 1));
                         }
 
                         if ui
@@ -622,7 +862,7 @@ impl SplitEditor {
                             .clicked()
                             && i < splits_len - 1
                         {
-                            swap_request = Some((i, i + 1));
+                            move_request = Some((i, i + 1));
                         }
 
                         ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
@@ -667,14 +907,55 @@ impl SplitEditor {
                             self.icon_selection_index = Some(i);
                         }
 
+                        if ui
+                            .button(RichText::new(egui_phosphor::regular::ROWS_PLUS_TOP))
+                            .on_hover_text("Insert a split above")
+                            .clicked()
+                        {
+                            insert_request = Some(i);
+                        }
+
+                        if ui
+                            .button(RichText::new(egui_phosphor::regular::SCISSORS))
+                            .on_hover_text("Split in two")
+                            .clicked()
+                        {
+                            split_in_two_request = Some(i);
+                        }
+
                         if ui
                             .button(RichText::new(egui_phosphor::regular::TRASH))
+                            .on_hover_text("Remove, merging its times into the next split")
                             .clicked()
                         {
                             to_remove = Some(i);
                         }
                     });
 
+                    ui.add_space(5.0);
+
+                    ui.horizontal(|ui| {
+                        ui.label("Section");
+                        let mut section = split.section.clone().unwrap_or_default();
+                        if ui
+                            .add(egui::TextEdit::singleline(&mut section).hint_text("None"))
+                            .changed()
+                        {
+                            let section = section.trim();
+                            split.section = (!section.is_empty()).then(|| section.to_string());
+                            split_changed = true;
+                        }
+                    });
+
+                    if let Some(Ok(autosplitter)) = &mut self.autosplitter {
+                        edit_condition(
+                            ui,
+                            "Split when",
+                            &mut autosplitter.splits[i],
+                            &autosplitter.config,
+                        );
+                    }
+
                     ui.add_space(10.0);
 
                     // Personal Best / Best Segments (Real Time)
@@ -759,12 +1040,43 @@ impl SplitEditor {
                 }
             }
 
+            // The split sequence follows the splits around.
+            let mut conditions = match &mut self.autosplitter {
+                Some(Ok(autosplitter)) => Some(&mut autosplitter.splits),
+                _ => None,
+            };
+
             if let Some(index) = to_remove {
-                self.run.splits.remove(index);
+                if splits_len > 1
+                    && let Some(conditions) = &mut conditions
+                {
+                    conditions.remove(index);
+                }
+                self.run.remove_split(index);
+            }
+
+            if let Some(index) = insert_request {
+                if let Some(conditions) = &mut conditions {
+                    conditions.insert(index, String::new());
+                }
+                self.run.insert_split(index, "New split");
+            }
+
+            // The original split keeps its condition as the second half.
+            if let Some(index) = split_in_two_request {
+                if let Some(conditions) = &mut conditions {
+                    conditions.insert(index, String::new());
+                }
+                let name = format!("{} (part 1)", self.run.splits[index].name);
+                self.run.split_in_two(index, &name, 0.5);
             }
 
-            if let Some((from, to)) = swap_request {
-                self.run.splits.swap(from, to);
+            if let Some((from, to)) = move_request {
+                if let Some(conditions) = &mut conditions {
+                    let condition = conditions.remove(from);
+                    conditions.insert(to, condition);
+                }
+                self.run.move_split(from, to);
             }
         });
 
@@ -1,8 +1,8 @@
 use eframe::egui;
-#[cfg(windows)]
+#[cfg(any(windows, target_os = "linux"))]
 use openspeedrun::config::keys::KeyWrapper;
 use openspeedrun::{
-    config::layout::{LayoutConfig, SectionKind},
+    config::layout::{Component, LayoutConfig},
     config_base_dir,
 };
 use std::{fs, path::PathBuf};
@@ -21,7 +21,7 @@ pub struct ThemeEditor {
     pub layout: LayoutConfig,
     save_status: Option<(String, bool)>,
     pending_pick: Option<(PendingPick, PendingDialog)>,
-    #[cfg(windows)]
+    #[cfg(any(windows, target_os = "linux"))]
     pub waiting_for_key: Option<String>,
 }
 
@@ -36,7 +36,7 @@ impl ThemeEditor {
             layout,
             save_status: None,
             pending_pick: None,
-            #[cfg(windows)]
+            #[cfg(any(windows, target_os = "linux"))]
             waiting_for_key: None,
         }
     }
@@ -277,21 +277,6 @@ impl ThemeEditor {
 
                     style::section_card(ui, "Options", egui_phosphor::regular::SLIDERS, |ui| {
                         ui.vertical(|ui| {
-                            ui.checkbox(&mut self.layout.options.show_title, "Show title");
-                            ui.checkbox(&mut self.layout.options.show_category, "Show category");
-                            ui.checkbox(&mut self.layout.options.show_splits, "Show splits");
-                            ui.checkbox(&mut self.layout.options.show_info, "Show info");
-                            ui.checkbox(&mut self.layout.options.show_body, "Show body");
-                            ui.checkbox(&mut self.layout.options.show_footer, "Show footer");
-                            ui.checkbox(&mut self.layout.options.show_graph, "Show delta graph");
-                            ui.checkbox(
-                                &mut self.layout.options.show_relative_times,
-                                "Show relative times",
-                            );
-                            ui.checkbox(
-                                &mut self.layout.options.show_last_relative_time,
-                                "Show last relative time",
-                            );
                             ui.checkbox(&mut self.layout.options.titlebar, "Titlebar");
                             ui.checkbox(&mut self.layout.options.enable_shader, "Enable shader");
                             ui.checkbox(
@@ -342,48 +327,9 @@ impl ThemeEditor {
 
                     ui.add_space(6.0);
 
-                    style::section_card(ui, "Section order", egui_phosphor::regular::ROWS, |ui| {
+                    style::section_card(ui, "Components", egui_phosphor::regular::ROWS, |ui| {
                         ui.vertical(|ui| {
-                            let mut swap: Option<(usize, usize)> = None;
-                            let last = self.layout.options.section_order.len().saturating_sub(1);
-
-                            for (i, section) in self.layout.options.section_order.iter().enumerate()
-                            {
-                                let name = match section {
-                                    SectionKind::Title => "Title",
-                                    SectionKind::Timer => "Timer",
-                                    SectionKind::Splits => "Splits",
-                                    SectionKind::Footer => "Footer",
-                                    SectionKind::Graph => "Graph",
-                                };
-
-                                ui.horizontal(|ui| {
-                                    ui.label(name);
-                                    ui.add_space(8.0);
-                                    if ui
-                                        .add_enabled(
-                                            i > 0,
-                                            egui::Button::new(egui_phosphor::regular::ARROW_UP),
-                                        )
-                                        .clicked()
-                                    {
-                                        swap = Some((i, i - 1));
-                                    }
-                                    if ui
-                                        .add_enabled(
-                                            i < last,
-                                            egui::Button::new(egui_phosphor::regular::ARROW_DOWN),
-                                        )
-                                        .clicked()
-                                    {
-                                        swap = Some((i, i + 1));
-                                    }
-                                });
-                            }
-
-                            if let Some((a, b)) = swap {
-                                self.layout.options.section_order.swap(a, b);
-                            }
+                            components_editor(ui, &mut self.layout.components);
                         });
                     });
 
@@ -415,12 +361,12 @@ impl ThemeEditor {
                 });
             });
 
-        #[cfg(windows)]
+        #[cfg(any(windows, target_os = "linux"))]
         {
             ui.add_space(6.0);
             style::section_card(
                 ui,
-                "Hotkeys (Windows only)",
+                "Global Hotkeys",
                 egui_phosphor::regular::KEYBOARD,
                 |ui| {
                     ui.vertical(|ui| {
@@ -431,6 +377,13 @@ impl ThemeEditor {
                             ui.horizontal(|ui| {
                                 ui.vertical(|ui| {
                                     hotkey_button(ui, "Split", &hotkeys.split, "split", waiting);
+                                    hotkey_button(
+                                        ui,
+                                        "Skip Split",
+                                        &hotkeys.skip_split,
+                                        "skip_split",
+                                        waiting,
+                                    );
                                     hotkey_button(ui, "Start", &hotkeys.start, "start", waiting);
                                     hotkey_button(ui, "Pause", &hotkeys.pause, "pause", waiting);
                                     hotkey_button(ui, "Reset", &hotkeys.reset, "reset", waiting);
@@ -522,20 +475,27 @@ impl ThemeEditor {
             );
         }
 
-        #[cfg(windows)]
+        #[cfg(any(windows, target_os = "linux"))]
         if let Some(action) = self.waiting_for_key.clone() {
             use egui::Event;
 
+            // Starts following Super before the key is pressed.
+            #[cfg(target_os = "linux")]
+            openspeedrun::core::evdev::held_modifiers();
+
             for event in ui.ctx().input(|i| i.raw.events.clone()) {
                 if let Event::Key {
-                    key, pressed: true, ..
+                    key,
+                    pressed: true,
+                    modifiers,
+                    ..
                 } = event
                 {
-                    let key_str = format!("{:?}", key);
-                    let key_wrapper = KeyWrapper(key_str);
+                    let key_wrapper = recorded_key(key, modifiers);
 
                     match action.as_str() {
                         "split" => self.layout.hotkeys.split = key_wrapper,
+                        "skip_split" => self.layout.hotkeys.skip_split = key_wrapper,
                         "start" => self.layout.hotkeys.start = key_wrapper,
                         "pause" => self.layout.hotkeys.pause = key_wrapper,
                         "reset" => self.layout.hotkeys.reset = key_wrapper,
@@ -568,6 +528,104 @@ impl ThemeEditor {
     }
 }
 
+/// The layout's components in order, each with its settings, buttons
+/// to move or remove it, and a menu to add more.
+fn components_editor(ui: &mut egui::Ui, components: &mut Vec<Component>) {
+    let mut swap: Option<(usize, usize)> = None;
+    let mut remove: Option<usize> = None;
+    let last = components.len().saturating_sub(1);
+
+    for (i, component) in components.iter_mut().enumerate() {
+        ui.push_id(i, |ui| {
+            ui.horizontal(|ui| {
+                ui.strong(component.name());
+                ui.add_space(8.0);
+                if ui
+                    .add_enabled(i > 0, egui::Button::new(egui_phosphor::regular::ARROW_UP))
+                    .clicked()
+                {
+                    swap = Some((i, i - 1));
+                }
+                if ui
+                    .add_enabled(
+                        i < last,
+                        egui::Button::new(egui_phosphor::regular::ARROW_DOWN),
+                    )
+                    .clicked()
+                {
+                    swap = Some((i, i + 1));
+                }
+                if ui
+                    .button(egui_phosphor::regular::TRASH)
+                    .on_hover_text("Remove")
+                    .clicked()
+                {
+                    remove = Some(i);
+                }
+            });
+
+            ui.indent("settings", |ui| match component {
+                Component::Title(settings) => {
+                    ui.checkbox(&mut settings.show_title, "Show title");
+                    ui.checkbox(&mut settings.show_category, "Show category");
+                }
+                Component::Timer(settings) => {
+                    ui.checkbox(&mut settings.show_secondary, "Show the other clock");
+                }
+                Component::Splits(settings) => {
+                    ui.checkbox(&mut settings.show_rows, "Show splits");
+                    ui.checkbox(&mut settings.show_relative_times, "Show relative times");
+                    ui.checkbox(
+                        &mut settings.show_last_relative_time,
+                        "Show last relative time",
+                    );
+                    ui.checkbox(
+                        &mut settings.collapse_sections,
+                        "Collapse sections other than the current one",
+                    );
+                }
+                Component::Footer(settings) => {
+                    ui.checkbox(&mut settings.show_info, "Show info");
+                    ui.add_enabled_ui(settings.show_info, |ui| {
+                        ui.checkbox(&mut settings.show_attempts, "Attempts");
+                        ui.checkbox(&mut settings.show_total_playtime, "Total playtime");
+                        ui.checkbox(&mut settings.show_sum_of_best, "Sum of Best");
+                        ui.checkbox(&mut settings.show_best_possible, "Best possible time");
+                        ui.checkbox(&mut settings.show_current_pace, "Current pace");
+                        ui.checkbox(&mut settings.show_pb, "Personal Best");
+                        ui.checkbox(&mut settings.show_pb_chance, "PB chance");
+                        ui.checkbox(&mut settings.show_possible_time_save, "Possible time save");
+                        ui.checkbox(&mut settings.show_previous_segment, "Previous segment");
+                    });
+                }
+                Component::Graph(settings) => {
+                    ui.add(egui::Slider::new(&mut settings.height, 32.0..=240.0).text("Height"));
+                }
+            });
+        });
+    }
+
+    if let Some((a, b)) = swap {
+        components.swap(a, b);
+    }
+    if let Some(i) = remove {
+        components.remove(i);
+    }
+
+    ui.add_space(4.0);
+    ui.menu_button(
+        format!("{} Add component", egui_phosphor::regular::PLUS),
+        |ui| {
+            for component in Component::all() {
+                if ui.button(component.name()).clicked() {
+                    components.push(component);
+                    ui.close();
+                }
+            }
+        },
+    );
+}
+
 fn color_edit(ui: &mut egui::Ui, label: &str, hex_color: &mut String) {
     let mut color = egui::Color32::from_hex(hex_color).unwrap_or(egui::Color32::WHITE);
     let mut changed = false;
@@ -583,7 +641,31 @@ fn color_edit(ui: &mut egui::Ui, label: &str, hex_color: &mut String) {
     }
 }
 
+/// The hotkey for a key pressed while waiting. Windows hotkeys are a
+/// single key; on Linux the held modifiers are kept as a combo, with
+/// Super taken from the keyboards themselves as egui doesn't report it.
 #[cfg(windows)]
+fn recorded_key(key: egui::Key, _modifiers: egui::Modifiers) -> KeyWrapper {
+    KeyWrapper(format!("{:?}", key))
+}
+
+#[cfg(target_os = "linux")]
+fn recorded_key(key: egui::Key, modifiers: egui::Modifiers) -> KeyWrapper {
+    use openspeedrun::config::keys::{KeyCombo, Modifiers};
+
+    let combo = KeyCombo {
+        modifiers: Modifiers {
+            ctrl: modifiers.ctrl,
+            shift: modifiers.shift,
+            alt: modifiers.alt,
+            meta: openspeedrun::core::evdev::held_modifiers().meta,
+        },
+        key: format!("{:?}", key).to_uppercase(),
+    };
+    KeyWrapper(combo.to_string())
+}
+
+#[cfg(any(windows, target_os = "linux"))]
 fn hotkey_button(
     ui: &mut egui::Ui,
     label: &str,
@@ -1,54 +1,59 @@
 use std::env;
-use std::io::Write;
-#[cfg(unix)]
-use std::os::unix::net::UnixStream;
 use std::process;
 
 #[cfg(unix)]
 fn main() {
-    let socket_path = openspeedrun::core::socket_path();
+    use openspeedrun::core::client::ControlClient;
+    use openspeedrun::core::protocol::{COMMANDS, COMMANDS_WITH_ARGUMENT, Reply};
+
     let args: Vec<String> = env::args().collect();
 
     if args.len() < 2 {
-        eprintln!("Usage: {} <command>", args[0]);
-        eprintln!(
-            "Commands: split, start, pause, reset, savepb, undolastsplit, loadbackup, nextpage, prevpage, togglehelp, toggleloading, cyclecomparison"
-        );
+        eprintln!("Usage: {} <command> [<argument>] [<command>...]", args[0]);
+        eprintln!("Commands: {}", COMMANDS.join(", "));
+        eprintln!("Taking an argument: {}", COMMANDS_WITH_ARGUMENT.join(", "));
         process::exit(1);
     }
 
-    let cmd = args[1].trim();
+    let mut cmds: Vec<String> = Vec::new();
+    let mut rest = args[1..].iter().map(|a| a.trim());
+    while let Some(cmd) = rest.next() {
+        if !COMMANDS.contains(&cmd) {
+            eprintln!("Invalid command '{}'", cmd);
+            process::exit(1);
+        }
+        if COMMANDS_WITH_ARGUMENT.contains(&cmd) {
+            let Some(arg) = rest.next() else {
+                eprintln!("'{}' needs an argument", cmd);
+                process::exit(1);
+            };
+            cmds.push(format!("{} {}", cmd, arg));
+        } else {
+            cmds.push(cmd.to_string());
+        }
+    }
 
-    let valid_cmds = [
-        "split",
-        "start",
-        "pause",
-        "reset",
-        "savepb",
-        "undolastsplit",
-        "loadbackup",
-        "nextpage",
-        "prevpage",
-        "togglehelp",
-        "toggleloading",
-        "cyclecomparison",
-    ];
-    if !valid_cmds.contains(&cmd) {
-        eprintln!("Invalid command '{}'", cmd);
+    let mut client = ControlClient::connect().unwrap_or_else(|e| {
+        eprintln!("Could not connect to the OpenSpeedRun socket: {}", e);
         process::exit(1);
+    });
+
+    // Several commands go down the same connection, in order, stopping at
+    // the first one that fails.
+    for cmd in cmds {
+        match client.send(&cmd) {
+            Ok(Reply::Ok(Some(value))) => println!("{}", value),
+            Ok(Reply::Ok(None)) => {}
+            Ok(Reply::Err(reason)) => {
+                eprintln!("{}: {}", cmd, reason);
+                process::exit(1);
+            }
+            Err(e) => {
+                eprintln!("Failed to send '{}': {}", cmd, e);
+                process::exit(1);
+            }
+        }
     }
-
-    let mut stream =
-        UnixStream::connect(socket_path).expect("Could not connect to the OpenSpeedRun socket");
-
-    stream
-        .write_all(cmd.as_bytes())
-        .expect("Failed to write command");
-    stream.write_all(b"\n").expect("Failed to write newline");
-
-    stream
-        .shutdown(std::net::Shutdown::Write)
-        .expect("Failed to shutdown write half");
 }
 
 #[cfg(windows)]
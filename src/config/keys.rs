@@ -1,16 +1,85 @@
+#[cfg(windows)]
 use rdev::Key;
 use serde::{Deserialize, Serialize};
+
+/// A hotkey as written in the theme: a key name (`"F1"`, `"SPACE"`), on
+/// Linux optionally preceded by modifiers (`"CTRL+SHIFT+S"`, see
+/// `KeyCombo`).
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct KeyWrapper(pub String);
 
 impl KeyWrapper {
+    #[cfg(windows)]
     pub fn as_key(&self) -> Option<Key> {
         string_to_key(&self.0)
     }
 
+    #[cfg(windows)]
     pub fn from_key(key: Key) -> Self {
         KeyWrapper(key_to_string(&key))
     }
+
+    /// `None` when unbound or malformed.
+    pub fn as_combo(&self) -> Option<KeyCombo> {
+        KeyCombo::parse(&self.0)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Modifiers {
+    pub ctrl: bool,
+    pub shift: bool,
+    pub alt: bool,
+    pub meta: bool,
+}
+
+/// A key plus the exact set of modifiers that must be held with it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyCombo {
+    pub modifiers: Modifiers,
+    /// Uppercased key name, e.g. `"S"` or `"F1"`.
+    pub key: String,
+}
+
+impl KeyCombo {
+    /// Parses `"CTRL+SHIFT+S"`-style strings, case-insensitively. The key
+    /// comes last; `CONTROL`, `SUPER` and `WIN` are accepted as aliases.
+    pub fn parse(s: &str) -> Option<Self> {
+        let mut parts: Vec<String> = s.split('+').map(|p| p.trim().to_uppercase()).collect();
+        let key = parts.pop()?;
+        if key.is_empty() {
+            return None;
+        }
+
+        let mut modifiers = Modifiers::default();
+        for part in parts {
+            match part.as_str() {
+                "CTRL" | "CONTROL" => modifiers.ctrl = true,
+                "SHIFT" => modifiers.shift = true,
+                "ALT" => modifiers.alt = true,
+                "META" | "SUPER" | "WIN" => modifiers.meta = true,
+                _ => return None,
+            }
+        }
+        Some(KeyCombo { modifiers, key })
+    }
+}
+
+impl std::fmt::Display for KeyCombo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let m = self.modifiers;
+        for (held, name) in [
+            (m.ctrl, "CTRL"),
+            (m.shift, "SHIFT"),
+            (m.alt, "ALT"),
+            (m.meta, "META"),
+        ] {
+            if held {
+                write!(f, "{name}+")?;
+            }
+        }
+        write!(f, "{}", self.key)
+    }
 }
 
 impl Default for KeyWrapper {
@@ -19,6 +88,7 @@ impl Default for KeyWrapper {
     }
 }
 
+#[cfg(windows)]
 pub fn string_to_key(s: &str) -> Option<Key> {
     use Key::*;
     Some(match s.to_uppercase().as_str() {
@@ -101,6 +171,7 @@ pub fn string_to_key(s: &str) -> Option<Key> {
     })
 }
 
+#[cfg(windows)]
 pub fn key_to_string(key: &Key) -> String {
     format!("{:?}", key).to_uppercase()
 }
@@ -1,17 +1,18 @@
-#[cfg(windows)]
+#[cfg(any(windows, target_os = "linux"))]
 use crate::config::keys::KeyWrapper;
 use serde::{Deserialize, Serialize};
 use std::fs;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
-#[derive(Default)]
 pub struct LayoutConfig {
     pub font_sizes: FontSizes,
     pub colors: Colors,
     pub spacings: Spacings,
     pub options: Options,
-    #[cfg(windows)]
+    /// What the main window shows, in order — see `Component`.
+    pub components: Vec<Component>,
+    #[cfg(any(windows, target_os = "linux"))]
     pub hotkeys: Hotkeys,
 }
 
@@ -55,8 +56,159 @@ pub struct Spacings {
     pub split_bottom: f32,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-pub enum SectionKind {
+/// One block of the main window, with its own settings. Serialized
+/// internally tagged, so each entry of the theme's `components` array
+/// reads `{ "type": "Title", "show_category": false, ... }`; settings left
+/// out take their defaults.
+///
+/// `Splits` always occupies the remaining central space (see
+/// `AppState::draw_ui` in `app::update`): components before it stack from
+/// the top down, those after it from the bottom up. Only the first
+/// `Splits` is drawn; any other kind can appear any number of times.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum Component {
+    Title(TitleSettings),
+    Timer(TimerSettings),
+    Splits(SplitsSettings),
+    Footer(FooterSettings),
+    Graph(GraphSettings),
+}
+
+impl Component {
+    /// One of each kind with default settings, for "add component" menus.
+    pub fn all() -> Vec<Component> {
+        vec![
+            Component::Title(TitleSettings::default()),
+            Component::Timer(TimerSettings::default()),
+            Component::Splits(SplitsSettings::default()),
+            Component::Footer(FooterSettings::default()),
+            Component::Graph(GraphSettings::default()),
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Component::Title(_) => "Title",
+            Component::Timer(_) => "Timer",
+            Component::Splits(_) => "Splits",
+            Component::Footer(_) => "Footer",
+            Component::Graph(_) => "Graph",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TitleSettings {
+    pub show_title: bool,
+    pub show_category: bool,
+}
+
+impl Default for TitleSettings {
+    fn default() -> Self {
+        Self {
+            show_title: true,
+            show_category: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TimerSettings {
+    /// The smaller line under the main timer with the other clock (IGT
+    /// under RTA or the other way round), once that clock has started.
+    pub show_secondary: bool,
+}
+
+impl Default for TimerSettings {
+    fn default() -> Self {
+        Self {
+            show_secondary: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SplitsSettings {
+    /// Off leaves the central space empty, so the other components keep
+    /// their places.
+    pub show_rows: bool,
+    pub show_relative_times: bool,
+    pub show_last_relative_time: bool,
+    /// Folds every split section (see `Run::sections`) except the current
+    /// one down to its header row.
+    pub collapse_sections: bool,
+}
+
+impl Default for SplitsSettings {
+    fn default() -> Self {
+        Self {
+            show_rows: true,
+            show_relative_times: false,
+            show_last_relative_time: false,
+            collapse_sections: true,
+        }
+    }
+}
+
+/// The info rows (see `app::info`). `show_info` turns them all off at
+/// once; the rows added after the first few start off, so an existing
+/// footer doesn't grow by itself.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FooterSettings {
+    pub show_info: bool,
+    pub show_attempts: bool,
+    pub show_sum_of_best: bool,
+    pub show_best_possible: bool,
+    pub show_pb: bool,
+    /// The last segment's delta against the selected comparison, marked
+    /// when it was a gold.
+    pub show_previous_segment: bool,
+    pub show_possible_time_save: bool,
+    pub show_current_pace: bool,
+    pub show_total_playtime: bool,
+    pub show_pb_chance: bool,
+}
+
+impl Default for FooterSettings {
+    fn default() -> Self {
+        Self {
+            show_info: true,
+            show_attempts: true,
+            show_sum_of_best: true,
+            show_best_possible: true,
+            show_pb: true,
+            show_previous_segment: true,
+            show_possible_time_save: false,
+            show_current_pace: false,
+            show_total_playtime: false,
+            show_pb_chance: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GraphSettings {
+    pub height: f32,
+}
+
+impl Default for GraphSettings {
+    fn default() -> Self {
+        Self { height: 76.0 }
+    }
+}
+
+/// Themes from before `LayoutConfig::components` described the window as
+/// a fixed set of sections, ordered by `options.section_order` and toggled
+/// by `options.show_*`. Only read, to migrate them (see
+/// `LayoutConfig::load_or_default`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+enum SectionKind {
     Title,
     Timer,
     Splits,
@@ -64,21 +216,89 @@ pub enum SectionKind {
     Graph,
 }
 
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+struct LegacyOptions {
+    show_title: bool,
+    show_category: bool,
+    show_splits: bool,
+    show_info: bool,
+    show_body: bool,
+    show_footer: bool,
+    show_graph: bool,
+    show_relative_times: bool,
+    show_last_relative_time: bool,
+    section_order: Vec<SectionKind>,
+}
+
+impl Default for LegacyOptions {
+    fn default() -> Self {
+        Self {
+            show_title: true,
+            show_category: true,
+            show_splits: true,
+            show_info: true,
+            show_body: true,
+            show_footer: true,
+            show_graph: false,
+            show_relative_times: false,
+            show_last_relative_time: false,
+            section_order: vec![
+                SectionKind::Title,
+                SectionKind::Timer,
+                SectionKind::Splits,
+                SectionKind::Footer,
+                SectionKind::Graph,
+            ],
+        }
+    }
+}
+
+impl LegacyOptions {
+    /// The components that draw what these options did. Hidden sections
+    /// are left out, except `Splits`, which keeps its place (it decides
+    /// what stacks from the top and what from the bottom) with its rows
+    /// turned off.
+    fn to_components(&self) -> Vec<Component> {
+        let mut order = self.section_order.clone();
+        // Configs saved before the graph existed don't list it.
+        if !order.contains(&SectionKind::Graph) {
+            order.push(SectionKind::Graph);
+        }
+
+        order
+            .into_iter()
+            .filter_map(|section| match section {
+                SectionKind::Title => (self.show_title || self.show_category).then_some({
+                    Component::Title(TitleSettings {
+                        show_title: self.show_title,
+                        show_category: self.show_category,
+                    })
+                }),
+                SectionKind::Timer => Some(Component::Timer(TimerSettings::default())),
+                SectionKind::Splits => Some(Component::Splits(SplitsSettings {
+                    show_rows: self.show_body && self.show_splits,
+                    show_relative_times: self.show_relative_times,
+                    show_last_relative_time: self.show_last_relative_time,
+                    ..SplitsSettings::default()
+                })),
+                SectionKind::Footer => self.show_footer.then_some({
+                    Component::Footer(FooterSettings {
+                        show_info: self.show_info,
+                        ..FooterSettings::default()
+                    })
+                }),
+                SectionKind::Graph => self
+                    .show_graph
+                    .then(|| Component::Graph(GraphSettings::default())),
+            })
+            .collect()
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
 pub struct Options {
-    pub show_title: bool,
-    pub show_category: bool,
-    pub show_splits: bool,
-    pub show_info: bool,
-    pub show_body: bool,
-    pub show_footer: bool,
-    /// Whether the delta-vs-comparison graph section renders. Off by
-    /// default so upgrading users don't get a new panel silently eating
-    /// vertical space in an already-sized window (see `app::graph`).
-    pub show_graph: bool,
-    pub show_relative_times: bool,
-    pub show_last_relative_time: bool,
     pub titlebar: bool,
     pub enable_shader: bool,
     pub enable_background_image: bool,
@@ -95,18 +315,14 @@ pub struct Options {
     /// every row's name text lines up at the same x regardless of icon
     /// size/absence (see `app::splits_panel`).
     pub split_icon_size: f32,
-    /// Vertical stacking order of the main window's sections. `Splits`
-    /// always occupies the remaining central space (see `AppState::draw_ui`
-    /// in `app::update`); everything before it in this list stacks from the
-    /// top down, everything after it stacks from the bottom up.
-    pub section_order: Vec<SectionKind>,
 }
 
-#[cfg(windows)]
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg(any(windows, target_os = "linux"))]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(default)]
 pub struct Hotkeys {
     pub split: KeyWrapper,
+    pub skip_split: KeyWrapper,
     pub start: KeyWrapper,
     pub pause: KeyWrapper,
     pub reset: KeyWrapper,
@@ -123,25 +339,53 @@ pub struct Hotkeys {
     pub reload_theme: KeyWrapper,
 }
 
-#[cfg(windows)]
-impl Default for Hotkeys {
+#[cfg(any(windows, target_os = "linux"))]
+impl Hotkeys {
+    /// Every hotkey paired with the control command (see `core::protocol`)
+    /// it triggers.
+    pub fn bindings(&self) -> [(&KeyWrapper, &'static str); 16] {
+        [
+            (&self.split, "split"),
+            (&self.skip_split, "skipsplit"),
+            (&self.start, "start"),
+            (&self.pause, "pause"),
+            (&self.reset, "reset"),
+            (&self.save_pb, "savepb"),
+            (&self.undo_split, "undolastsplit"),
+            (&self.undo_pb, "loadbackup"),
+            (&self.next_page, "nextpage"),
+            (&self.prev_page, "prevpage"),
+            (&self.toggle_help, "togglehelp"),
+            (&self.toggle_loading, "toggleloading"),
+            (&self.cycle_comparison, "cyclecomparison"),
+            (&self.reload_all, "reloadall"),
+            (&self.reload_run, "reloadrun"),
+            (&self.reload_theme, "reloadtheme"),
+        ]
+    }
+
+    pub fn any_bound(&self) -> bool {
+        self.bindings()
+            .iter()
+            .any(|(key, _)| !key.0.trim().is_empty())
+    }
+}
+
+impl Default for LayoutConfig {
     fn default() -> Self {
         Self {
-            split: KeyWrapper::default(),
-            start: KeyWrapper::default(),
-            pause: KeyWrapper::default(),
-            reset: KeyWrapper::default(),
-            save_pb: KeyWrapper::default(),
-            undo_split: KeyWrapper::default(),
-            undo_pb: KeyWrapper::default(),
-            next_page: KeyWrapper::default(),
-            prev_page: KeyWrapper::default(),
-            toggle_help: KeyWrapper::default(),
-            toggle_loading: KeyWrapper::default(),
-            cycle_comparison: KeyWrapper::default(),
-            reload_all: KeyWrapper::default(),
-            reload_run: KeyWrapper::default(),
-            reload_theme: KeyWrapper::default(),
+            font_sizes: FontSizes::default(),
+            colors: Colors::default(),
+            spacings: Spacings::default(),
+            options: Options::default(),
+            components: vec![
+                Component::Title(TitleSettings::default()),
+                Component::Timer(TimerSettings::default()),
+                Component::Splits(SplitsSettings::default()),
+                Component::Footer(FooterSettings::default()),
+            ],
+            #[cfg(any(windows, target_os = "linux"))]
+            hotkeys: Hotkeys::default(),
         }
     }
 }
@@ -195,15 +439,6 @@ impl Default for Spacings {
 impl Default for Options {
     fn default() -> Self {
         Self {
-            show_title: true,
-            show_category: true,
-            show_splits: true,
-            show_info: true,
-            show_body: true,
-            show_footer: true,
-            show_graph: false,
-            show_relative_times: false,
-            show_last_relative_time: false,
             titlebar: true,
             enable_shader: false,
             enable_background_image: false,
@@ -211,31 +446,30 @@ impl Default for Options {
             enable_overlay_server: false,
             overlay_server_port: 7331,
             split_icon_size: 20.0,
-            section_order: vec![
-                SectionKind::Title,
-                SectionKind::Timer,
-                SectionKind::Splits,
-                SectionKind::Footer,
-                SectionKind::Graph,
-            ],
         }
     }
 }
 
 impl LayoutConfig {
     pub fn load_or_default(path: &str) -> Self {
-        let mut config: Self = fs::read_to_string(path)
+        let Some(value) = fs::read_to_string(path)
             .ok()
-            .and_then(|data| serde_json::from_str(&data).ok())
-            .unwrap_or_default();
-
-        // `section_order` is a concrete array in already-saved configs, so
-        // serde's `#[serde(default)]` (which only fills entirely-missing
-        // fields) won't retrofit newly added `SectionKind` variants into it.
-        // Patch it here so upgrading users can find/reorder the new section
-        // instead of it being permanently absent.
-        if !config.options.section_order.contains(&SectionKind::Graph) {
-            config.options.section_order.push(SectionKind::Graph);
+            .and_then(|data| serde_json::from_str::<serde_json::Value>(&data).ok())
+        else {
+            return Self::default();
+        };
+
+        let mut config: Self = serde_json::from_value(value.clone()).unwrap_or_default();
+
+        // A theme without `components` predates them: rebuild the same
+        // window from its section options. It's written in the new form
+        // the next time the theme is saved.
+        if value.get("components").is_none() {
+            let legacy: LegacyOptions = value
+                .get("options")
+                .and_then(|options| serde_json::from_value(options.clone()).ok())
+                .unwrap_or_default();
+            config.components = legacy.to_components();
         }
 
         config
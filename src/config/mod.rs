@@ -1,4 +1,4 @@
-#[cfg(windows)]
+#[cfg(any(windows, target_os = "linux"))]
 pub mod keys;
 pub mod layout;
 pub mod load;
@@ -0,0 +1,202 @@
+//! Looking at one logged attempt on its own: its split-by-split breakdown,
+//! put back together from every split's `segment_history` by `run_index`
+//! (and kept as a ghost comparison, if wanted), and the filters the
+//! history window narrows the attempt list with.
+
+use chrono::{Duration, NaiveDate};
+
+use crate::core::split::{AttemptHistoryEntry, ComparisonTime, Run, TimingMethod};
+
+/// One split of a past attempt.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AttemptSplit {
+    /// `None` if the split was skipped or never reached.
+    pub segment: Option<Duration>,
+    /// Time from the start of the attempt, when `segment` is known.
+    pub time: Option<Duration>,
+    /// The segment also covers skipped splits before it.
+    pub combined: bool,
+    /// The split's Best Segment as it stood before this attempt.
+    pub gold_then: Option<Duration>,
+    /// The split time of the Personal Best as it stood before this attempt.
+    pub pb_then: Option<Duration>,
+}
+
+impl AttemptSplit {
+    pub fn gold_delta(&self) -> Option<Duration> {
+        Some(self.segment? - self.gold_then?)
+    }
+
+    pub fn pb_delta(&self) -> Option<Duration> {
+        Some(self.time? - self.pb_then?)
+    }
+}
+
+impl Run {
+    /// Attempt `run_index` split by split on `method`, with the golds and
+    /// PB it was up against (from earlier attempts only).
+    pub fn attempt_splits(&self, run_index: u32, method: TimingMethod) -> Vec<AttemptSplit> {
+        let pb_then = self.personal_best_before(run_index, method);
+
+        let mut elapsed = Duration::zero();
+        let mut pb_elapsed = Some(Duration::zero());
+        self.splits
+            .iter()
+            .enumerate()
+            .map(|(i, split)| {
+                let entry = split
+                    .segment_history
+                    .iter()
+                    .find(|e| e.run_index == run_index);
+                let segment = entry.and_then(|e| e.get(method));
+                if let Some(segment) = segment {
+                    elapsed += segment;
+                }
+                pb_elapsed = pb_elapsed
+                    .zip(pb_then.as_ref().and_then(|pb| pb[i]))
+                    .map(|(total, segment)| total + segment);
+
+                AttemptSplit {
+                    segment,
+                    time: segment.map(|_| elapsed),
+                    combined: entry.is_some_and(|e| e.combined),
+                    gold_then: split
+                        .segment_history
+                        .iter()
+                        .filter(|e| e.run_index < run_index && !e.combined)
+                        .filter_map(|e| e.get(method))
+                        .min(),
+                    pb_then: pb_elapsed,
+                }
+            })
+            .collect()
+    }
+
+    /// Attempt `run_index`'s segments on both clocks, ready to use as a
+    /// comparison. Skipped and unreached splits are left empty.
+    pub fn attempt_comparison(&self, run_index: u32) -> Vec<ComparisonTime> {
+        self.splits
+            .iter()
+            .map(|split| {
+                split
+                    .segment_history
+                    .iter()
+                    .find(|e| e.run_index == run_index)
+                    .map(|e| ComparisonTime {
+                        real_time: e.real_time,
+                        game_time: e.game_time,
+                    })
+                    .unwrap_or_default()
+            })
+            .collect()
+    }
+
+    /// Stores attempt `run_index` as a custom comparison named
+    /// `attempt_comparison_name(run_index)` — a ghost to race, selectable
+    /// like any other — replacing one made earlier. Returns the name.
+    pub fn add_attempt_comparison(&mut self, run_index: u32) -> Result<String, String> {
+        if !self
+            .attempt_history
+            .iter()
+            .any(|a| a.run_index == run_index)
+        {
+            return Err(format!("no attempt #{run_index}"));
+        }
+        let segments = self.attempt_comparison(run_index);
+        if segments
+            .iter()
+            .all(|c| c.real_time.is_none() && c.game_time.is_none())
+        {
+            return Err(format!("attempt #{run_index} has no segment times"));
+        }
+
+        let name = attempt_comparison_name(run_index);
+        for (split, segment) in self.splits.iter_mut().zip(segments) {
+            split.comparisons.insert(name.clone(), segment);
+        }
+        self.generate_comparisons();
+        Ok(name)
+    }
+
+    /// The segments of the fastest finished attempt before `run_index`
+    /// with a time on every split — the PB at the time, picked the way
+    /// `recompute_personal_best` does.
+    fn personal_best_before(
+        &self,
+        run_index: u32,
+        method: TimingMethod,
+    ) -> Option<Vec<Option<Duration>>> {
+        self.attempt_history
+            .iter()
+            .filter(|a| a.ended && a.run_index < run_index)
+            .filter_map(|a| {
+                let segments: Vec<Option<Duration>> = self
+                    .splits
+                    .iter()
+                    .map(|s| {
+                        s.segment_history
+                            .iter()
+                            .find(|e| e.run_index == a.run_index)
+                            .and_then(|e| e.get(method))
+                    })
+                    .collect();
+                let total = segments.iter().copied().sum::<Option<Duration>>()?;
+                Some((total, segments))
+            })
+            .min_by_key(|(total, _)| *total)
+            .map(|(_, segments)| segments)
+    }
+}
+
+/// `"Attempt #312"`: what `Run::add_attempt_comparison` names a ghost.
+pub fn attempt_comparison_name(run_index: u32) -> String {
+    format!("Attempt #{run_index}")
+}
+
+/// Which attempts to show, by whether they finished.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Completion {
+    #[default]
+    Any,
+    Finished,
+    Reset,
+}
+
+/// What the history window's attempt list is narrowed to. Every bound is
+/// optional and inclusive; dates are UTC.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AttemptFilter {
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    pub completion: Completion,
+    pub min_time: Option<Duration>,
+    pub max_time: Option<Duration>,
+    pub tag: Option<String>,
+}
+
+impl AttemptFilter {
+    /// Whether `attempt` passes, with times on `method`. An attempt
+    /// without a date (or time) fails any date (or time) bound.
+    pub fn matches(&self, attempt: &AttemptHistoryEntry, method: TimingMethod) -> bool {
+        let completion = match self.completion {
+            Completion::Any => true,
+            Completion::Finished => attempt.ended,
+            Completion::Reset => !attempt.ended,
+        };
+        let date = attempt.date.map(|d| d.date_naive());
+        let in_dates = self.from.is_none_or(|from| date.is_some_and(|d| d >= from))
+            && self.to.is_none_or(|to| date.is_some_and(|d| d <= to));
+        let time = match method {
+            TimingMethod::RealTime => attempt.real_time,
+            TimingMethod::GameTime => attempt.game_time,
+        };
+        let in_times = self
+            .min_time
+            .is_none_or(|min| time.is_some_and(|t| t >= min))
+            && self
+                .max_time
+                .is_none_or(|max| time.is_some_and(|t| t <= max));
+        let tagged = self.tag.as_deref().is_none_or(|tag| attempt.has_tag(tag));
+        completion && in_dates && in_times && tagged
+    }
+}
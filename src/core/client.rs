@@ -0,0 +1,46 @@
+//! Blocking client side of `core::protocol`, for `openspeedrun-cli`,
+//! `openspeedrun-autosplitter`, or any other tool driving the timer.
+
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::net::UnixStream;
+use std::path::Path;
+
+use crate::core::protocol::Reply;
+use crate::core::socket_path;
+
+/// One connection to the control socket, kept open across commands.
+pub struct ControlClient {
+    reader: BufReader<UnixStream>,
+    writer: UnixStream,
+}
+
+impl ControlClient {
+    pub fn connect() -> std::io::Result<Self> {
+        Self::connect_to(&socket_path())
+    }
+
+    pub fn connect_to(path: &Path) -> std::io::Result<Self> {
+        let writer = UnixStream::connect(path)?;
+        let reader = BufReader::new(writer.try_clone()?);
+        Ok(Self { reader, writer })
+    }
+
+    /// Sends one command and waits for its reply.
+    pub fn send(&mut self, cmd: &str) -> std::io::Result<Reply> {
+        writeln!(self.writer, "{cmd}")?;
+
+        let mut line = String::new();
+        if self.reader.read_line(&mut line)? == 0 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::UnexpectedEof,
+                "openspeedrun closed the connection",
+            ));
+        }
+        Reply::parse(&line).ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("unexpected reply '{}'", line.trim_end()),
+            )
+        })
+    }
+}
@@ -0,0 +1,390 @@
+//! Global hotkeys on Linux, read straight from the kernel's evdev devices
+//! (`/dev/input/event*`) so they work under X11 and Wayland alike, with
+//! whatever window focused. Reading those needs root or membership of the
+//! `input` group.
+//!
+//! Raw bytes become `InputEvent`s through `EventReader`, which works on
+//! any `Read`; `HotkeyMatcher` turns those into `Chord`s, tracking held
+//! modifiers; `command_for` looks a chord up in the theme's `Hotkeys`.
+//! None of that touches a device, so it all runs against synthetic event
+//! streams in tests.
+
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::Read;
+use std::sync::{Arc, Mutex, OnceLock, mpsc};
+
+use crate::app::AppState;
+use crate::config::keys::Modifiers;
+use crate::config::layout::Hotkeys;
+use crate::core::protocol::{Reply, handle_command};
+
+/// `type` of key press/release/repeat events.
+pub const EV_KEY: u16 = 0x01;
+
+pub const KEY_RELEASED: i32 = 0;
+pub const KEY_PRESSED: i32 = 1;
+pub const KEY_REPEATED: i32 = 2;
+
+pub const KEY_LEFTCTRL: u16 = 29;
+pub const KEY_LEFTSHIFT: u16 = 42;
+pub const KEY_RIGHTSHIFT: u16 = 54;
+pub const KEY_LEFTALT: u16 = 56;
+pub const KEY_RIGHTCTRL: u16 = 97;
+pub const KEY_RIGHTALT: u16 = 100;
+pub const KEY_LEFTMETA: u16 = 125;
+pub const KEY_RIGHTMETA: u16 = 126;
+
+/// `struct input_event`: a `struct timeval` (two `long`s) followed by
+/// `__u16 type`, `__u16 code` and `__s32 value`, in native byte order.
+pub const INPUT_EVENT_SIZE: usize = 2 * std::mem::size_of::<std::ffi::c_long>() + 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InputEvent {
+    pub kind: u16,
+    pub code: u16,
+    pub value: i32,
+}
+
+impl InputEvent {
+    pub fn key(code: u16, value: i32) -> Self {
+        InputEvent {
+            kind: EV_KEY,
+            code,
+            value,
+        }
+    }
+
+    /// Decodes one `struct input_event`. The timestamp is dropped.
+    pub fn from_bytes(bytes: &[u8; INPUT_EVENT_SIZE]) -> Self {
+        let at = INPUT_EVENT_SIZE - 8;
+        InputEvent {
+            kind: u16::from_ne_bytes([bytes[at], bytes[at + 1]]),
+            code: u16::from_ne_bytes([bytes[at + 2], bytes[at + 3]]),
+            value: i32::from_ne_bytes([bytes[at + 4], bytes[at + 5], bytes[at + 6], bytes[at + 7]]),
+        }
+    }
+
+    /// The inverse of `from_bytes`, with a zero timestamp.
+    pub fn to_bytes(self) -> [u8; INPUT_EVENT_SIZE] {
+        let mut bytes = [0; INPUT_EVENT_SIZE];
+        let at = INPUT_EVENT_SIZE - 8;
+        bytes[at..at + 2].copy_from_slice(&self.kind.to_ne_bytes());
+        bytes[at + 2..at + 4].copy_from_slice(&self.code.to_ne_bytes());
+        bytes[at + 4..].copy_from_slice(&self.value.to_ne_bytes());
+        bytes
+    }
+}
+
+/// The events in a stream of raw `struct input_event`s — an evdev device,
+/// or any byte source in tests. Ends at EOF or the first read error.
+pub struct EventReader<R: Read> {
+    source: R,
+}
+
+impl<R: Read> EventReader<R> {
+    pub fn new(source: R) -> Self {
+        EventReader { source }
+    }
+}
+
+impl<R: Read> Iterator for EventReader<R> {
+    type Item = InputEvent;
+
+    fn next(&mut self) -> Option<InputEvent> {
+        let mut bytes = [0; INPUT_EVENT_SIZE];
+        self.source.read_exact(&mut bytes).ok()?;
+        Some(InputEvent::from_bytes(&bytes))
+    }
+}
+
+/// A non-modifier key going down, with the modifiers held at the time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Chord {
+    pub modifiers: Modifiers,
+    pub code: u16,
+}
+
+/// Follows which modifier keys are held across an event stream, and
+/// reports each fresh press of any other key as a `Chord`. Autorepeats
+/// are ignored, so holding a hotkey down fires it once.
+#[derive(Debug, Default)]
+pub struct HotkeyMatcher {
+    held: HashSet<u16>,
+}
+
+impl HotkeyMatcher {
+    pub fn modifiers(&self) -> Modifiers {
+        let held = |codes: [u16; 2]| codes.iter().any(|c| self.held.contains(c));
+        Modifiers {
+            ctrl: held([KEY_LEFTCTRL, KEY_RIGHTCTRL]),
+            shift: held([KEY_LEFTSHIFT, KEY_RIGHTSHIFT]),
+            alt: held([KEY_LEFTALT, KEY_RIGHTALT]),
+            meta: held([KEY_LEFTMETA, KEY_RIGHTMETA]),
+        }
+    }
+
+    pub fn feed(&mut self, event: InputEvent) -> Option<Chord> {
+        if event.kind != EV_KEY {
+            return None;
+        }
+        if is_modifier(event.code) {
+            match event.value {
+                KEY_PRESSED => {
+                    self.held.insert(event.code);
+                }
+                KEY_RELEASED => {
+                    self.held.remove(&event.code);
+                }
+                _ => {}
+            }
+            return None;
+        }
+        (event.value == KEY_PRESSED).then(|| Chord {
+            modifiers: self.modifiers(),
+            code: event.code,
+        })
+    }
+}
+
+fn is_modifier(code: u16) -> bool {
+    matches!(
+        code,
+        KEY_LEFTCTRL
+            | KEY_RIGHTCTRL
+            | KEY_LEFTSHIFT
+            | KEY_RIGHTSHIFT
+            | KEY_LEFTALT
+            | KEY_RIGHTALT
+            | KEY_LEFTMETA
+            | KEY_RIGHTMETA
+    )
+}
+
+/// The control command bound to `chord`, if any. Modifiers must match
+/// exactly, so a plain `S` binding doesn't fire on Ctrl+S.
+pub fn command_for(hotkeys: &Hotkeys, chord: Chord) -> Option<&'static str> {
+    hotkeys.bindings().into_iter().find_map(|(key, command)| {
+        let combo = key.as_combo()?;
+        (key_code(&combo.key)? == chord.code && combo.modifiers == chord.modifiers)
+            .then_some(command)
+    })
+}
+
+/// The evdev key code for a key name. Takes the names
+/// `config::keys::string_to_key` does, plus egui's (`ARROWLEFT`, `NUM1`,
+/// ...) as the theme editor records those, and F13–F24.
+pub fn key_code(name: &str) -> Option<u16> {
+    Some(match name.to_uppercase().as_str() {
+        "ESC" | "ESCAPE" => 1,
+        "1" | "NUM1" => 2,
+        "2" | "NUM2" => 3,
+        "3" | "NUM3" => 4,
+        "4" | "NUM4" => 5,
+        "5" | "NUM5" => 6,
+        "6" | "NUM6" => 7,
+        "7" | "NUM7" => 8,
+        "8" | "NUM8" => 9,
+        "9" | "NUM9" => 10,
+        "0" | "NUM0" => 11,
+        "MINUS" => 12,
+        "EQUAL" | "EQUALS" => 13,
+        "BACKSPACE" => 14,
+        "TAB" => 15,
+        "Q" => 16,
+        "W" => 17,
+        "E" => 18,
+        "R" => 19,
+        "T" => 20,
+        "Y" => 21,
+        "U" => 22,
+        "I" => 23,
+        "O" => 24,
+        "P" => 25,
+        "LEFTBRACKET" | "OPENBRACKET" => 26,
+        "RIGHTBRACKET" | "CLOSEBRACKET" => 27,
+        "ENTER" => 28,
+        "A" => 30,
+        "S" => 31,
+        "D" => 32,
+        "F" => 33,
+        "G" => 34,
+        "H" => 35,
+        "J" => 36,
+        "K" => 37,
+        "L" => 38,
+        "SEMICOLON" => 39,
+        "APOSTROPHE" | "QUOTE" => 40,
+        "GRAVE" | "BACKTICK" => 41,
+        "BACKSLASH" => 43,
+        "Z" => 44,
+        "X" => 45,
+        "C" => 46,
+        "V" => 47,
+        "B" => 48,
+        "N" => 49,
+        "M" => 50,
+        "COMMA" => 51,
+        "DOT" | "PERIOD" => 52,
+        "SLASH" => 53,
+        "SPACE" => 57,
+        "CAPSLOCK" => 58,
+        "F1" => 59,
+        "F2" => 60,
+        "F3" => 61,
+        "F4" => 62,
+        "F5" => 63,
+        "F6" => 64,
+        "F7" => 65,
+        "F8" => 66,
+        "F9" => 67,
+        "F10" => 68,
+        "F11" => 87,
+        "F12" => 88,
+        "HOME" => 102,
+        "UP" | "ARROWUP" => 103,
+        "PAGEUP" => 104,
+        "LEFT" | "ARROWLEFT" => 105,
+        "RIGHT" | "ARROWRIGHT" => 106,
+        "END" => 107,
+        "DOWN" | "ARROWDOWN" => 108,
+        "PAGEDOWN" => 109,
+        "INSERT" => 110,
+        "DELETE" => 111,
+        "F13" => 183,
+        "F14" => 184,
+        "F15" => 185,
+        "F16" => 186,
+        "F17" => 187,
+        "F18" => 188,
+        "F19" => 189,
+        "F20" => 190,
+        "F21" => 191,
+        "F22" => 192,
+        "F23" => 193,
+        "F24" => 194,
+        _ => return None,
+    })
+}
+
+/// The `/dev/input/event*` nodes of the keyboards listed in
+/// `/proc/bus/input/devices`: devices with the `kbd` handler that report
+/// key events and autorepeat, which leaves out mice, power buttons and
+/// the like.
+pub fn keyboard_devices(proc_devices: &str) -> Vec<String> {
+    const EV_REP_BIT: u64 = 1 << 0x14;
+    const EV_KEY_BIT: u64 = 1 << EV_KEY;
+
+    proc_devices
+        .split("\n\n")
+        .filter_map(|block| {
+            let handlers = block.lines().find_map(|l| l.strip_prefix("H: Handlers="))?;
+            let ev = block
+                .lines()
+                .find_map(|l| l.strip_prefix("B: EV="))
+                .and_then(|bits| u64::from_str_radix(bits.trim(), 16).ok())?;
+            let mut handlers = handlers.split_whitespace();
+            let is_keyboard = handlers.clone().any(|h| h == "kbd")
+                && ev & EV_KEY_BIT != 0
+                && ev & EV_REP_BIT != 0;
+            let event = handlers.find(|h| h.starts_with("event"))?;
+            is_keyboard.then(|| format!("/dev/input/{event}"))
+        })
+        .collect()
+}
+
+/// Reads every keyboard on its own thread and runs the command bound to
+/// each hotkey pressed on any of them. Returns straight away if none can
+/// be opened, which is only reported when a hotkey is bound. The bindings
+/// are looked up per key press, so a theme reload takes effect
+/// immediately, including binding the first one.
+pub fn listen_for_hotkeys(app: Arc<Mutex<AppState>>) {
+    let report = app.lock().unwrap().layout.hotkeys.any_bound();
+    let Some(events) = open_keyboards(report) else {
+        return;
+    };
+
+    std::thread::spawn(move || {
+        // One matcher for all keyboards, so Ctrl on one and S on another
+        // still make Ctrl+S.
+        let mut matcher = HotkeyMatcher::default();
+        for event in events {
+            let Some(chord) = matcher.feed(event) else {
+                continue;
+            };
+            let mut app = app.lock().unwrap();
+            let Some(command) = command_for(&app.layout.hotkeys, chord) else {
+                continue;
+            };
+            if let Reply::Err(reason) = handle_command(&mut app, command) {
+                eprintln!("Hotkey '{}' failed: {}", command, reason);
+            }
+        }
+    });
+}
+
+/// The modifiers held on any keyboard, followed from the first call on.
+/// egui doesn't report Super on Linux, so the theme editor asks here when
+/// recording a hotkey. Without access to the keyboards nothing is ever
+/// held.
+pub fn held_modifiers() -> Modifiers {
+    static MATCHER: OnceLock<Arc<Mutex<HotkeyMatcher>>> = OnceLock::new();
+    let matcher = MATCHER.get_or_init(|| {
+        let matcher = Arc::new(Mutex::new(HotkeyMatcher::default()));
+        if let Some(events) = open_keyboards(false) {
+            let matcher = matcher.clone();
+            std::thread::spawn(move || {
+                for event in events {
+                    matcher.lock().unwrap().feed(event);
+                }
+            });
+        }
+        matcher
+    });
+    matcher.lock().unwrap().modifiers()
+}
+
+/// Reads every keyboard on its own thread, merging their events into one
+/// channel. `None` if none can be opened; why is printed if `report`.
+fn open_keyboards(report: bool) -> Option<mpsc::Receiver<InputEvent>> {
+    let devices = match std::fs::read_to_string("/proc/bus/input/devices") {
+        Ok(text) => keyboard_devices(&text),
+        Err(e) => {
+            if report {
+                eprintln!("Can't list input devices for global hotkeys: {}", e);
+            }
+            return None;
+        }
+    };
+
+    let (tx, rx) = mpsc::channel();
+    let mut opened = 0;
+    for path in devices {
+        match File::open(&path) {
+            Ok(file) => {
+                opened += 1;
+                let tx = tx.clone();
+                std::thread::spawn(move || {
+                    for event in EventReader::new(file) {
+                        if tx.send(event).is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+            Err(e) if report => eprintln!("Can't read {} for global hotkeys: {}", path, e),
+            Err(_) => {}
+        }
+    }
+
+    if opened == 0 {
+        if report {
+            eprintln!(
+                "Global hotkeys disabled: no readable keyboard in /dev/input \
+                 (add your user to the 'input' group)"
+            );
+        }
+        return None;
+    }
+    Some(rx)
+}
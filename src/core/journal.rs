@@ -0,0 +1,129 @@
+//! Crash-safe record of the attempt in progress. Finished segments are
+//! merged into `split.json` as they happen, but the live state — both
+//! clocks, the split times so far, the IGT pauses — only exists in
+//! `AppState`. `AppState` rewrites this journal next to `split.json` on
+//! every split, pause and IGT toggle, and deletes it once the attempt is
+//! finished or reset; one still on disk at startup means the last attempt
+//! was interrupted, and can be resumed or recorded as a reset.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::config::atomic_write;
+
+pub const JOURNAL_FILE_NAME: &str = "split.journal.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum JournalTimerState {
+    #[default]
+    Running,
+    Paused,
+}
+
+/// A time the attempt reached split `i` at. Both `None` for a skipped split.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct JournalSplit {
+    #[serde(with = "crate::core::split::duration_millis")]
+    pub real_time: Option<Duration>,
+    #[serde(with = "crate::core::split::duration_millis")]
+    pub game_time: Option<Duration>,
+}
+
+/// One stretch with the IGT clock stopped for a load, in real time from
+/// the start of the attempt. `end_ms` is `None` while it's still going.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IgtPause {
+    pub start_ms: i64,
+    pub end_ms: Option<i64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AttemptJournal {
+    /// The attempt's `run_index` (see `Run::attempts`).
+    pub run_index: u32,
+    pub timer_state: JournalTimerState,
+    /// When the attempt started (after any start-offset countdown).
+    pub started_at: Option<DateTime<Utc>>,
+    /// When this journal was written — the elapsed times below are as of
+    /// then.
+    pub written_at: Option<DateTime<Utc>>,
+    pub real_elapsed_ms: i64,
+    pub game_elapsed_ms: i64,
+    pub current_split: usize,
+    /// One entry per split in the run; only the first `current_split` are
+    /// meaningful.
+    pub splits: Vec<JournalSplit>,
+    pub igt_pauses: Vec<IgtPause>,
+    /// See `AppState::game_time_external`.
+    pub game_time_external: bool,
+}
+
+impl AttemptJournal {
+    pub fn path(split_base_path: &Path) -> PathBuf {
+        split_base_path.join(JOURNAL_FILE_NAME)
+    }
+
+    /// The journal left in `split_base_path`, if any. An unreadable one is
+    /// reported and ignored rather than blocking startup.
+    pub fn load(split_base_path: &Path) -> Option<Self> {
+        let file = std::fs::read_to_string(Self::path(split_base_path)).ok()?;
+        match serde_json::from_str(&file) {
+            Ok(journal) => Some(journal),
+            Err(e) => {
+                eprintln!("Ignoring unreadable attempt journal: {}", e);
+                None
+            }
+        }
+    }
+
+    pub fn save(&self, split_base_path: &Path) -> std::io::Result<()> {
+        let json = serde_json::to_string_pretty(self).unwrap();
+        atomic_write(&Self::path(split_base_path), &json)
+    }
+
+    pub fn remove(split_base_path: &Path) {
+        let path = Self::path(split_base_path);
+        if path.exists()
+            && let Err(e) = std::fs::remove_file(&path)
+        {
+            eprintln!("Error removing attempt journal: {}", e);
+        }
+    }
+
+    /// Whether a load was in progress (IGT stopped) when this was written.
+    pub fn igt_paused(&self) -> bool {
+        self.igt_pauses.last().is_some_and(|p| p.end_ms.is_none())
+    }
+
+    /// Time that passed between this journal being written and `now` while
+    /// the timer was running — nothing if it was paused. The app was down
+    /// for that stretch, but it's still real time the attempt took.
+    fn downtime(&self, now: DateTime<Utc>) -> Duration {
+        match (self.timer_state, self.written_at) {
+            (JournalTimerState::Running, Some(written_at)) => {
+                (now - written_at).max(Duration::zero())
+            }
+            _ => Duration::zero(),
+        }
+    }
+
+    /// Real time the attempt would be at by `now`, had it never stopped.
+    pub fn real_elapsed_at(&self, now: DateTime<Utc>) -> Duration {
+        Duration::milliseconds(self.real_elapsed_ms) + self.downtime(now)
+    }
+
+    /// Game time at `now`: like `real_elapsed_at`, unless a load was in
+    /// progress, in which case the IGT clock stays where it stopped.
+    pub fn game_elapsed_at(&self, now: DateTime<Utc>) -> Duration {
+        let game = Duration::milliseconds(self.game_elapsed_ms);
+        if self.igt_paused() {
+            game
+        } else {
+            game + self.downtime(now)
+        }
+    }
+}
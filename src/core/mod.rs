@@ -1,6 +1,18 @@
+pub mod attempts;
+#[cfg(unix)]
+pub mod client;
+#[cfg(target_os = "linux")]
+pub mod evdev;
+pub mod journal;
+pub mod outliers;
+pub mod protocol;
+pub mod run_edit;
 #[cfg(unix)]
 pub mod server;
 pub mod split;
+pub mod stats;
+pub mod tags;
+pub mod time_source;
 pub mod timer;
 #[cfg(windows)]
 pub mod winserver;
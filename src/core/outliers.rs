@@ -0,0 +1,130 @@
+//! Finding segment times that are almost certainly mistakes — a double
+//! tapped split, a forgotten one — before they become a permanent fake
+//! gold or skew Average/Median. Nothing here changes the run by itself:
+//! `Run::segment_outliers` only flags, and the history window lets the
+//! runner decide what to `remove_segment_entries`.
+
+use chrono::Duration;
+
+use crate::core::split::{COMPARISON_BEST_SEGMENTS, Run, TimingMethod};
+use crate::core::stats::{quantile, robust_z_score};
+
+/// A segment is flagged once its robust z-score is past this (either way).
+/// 3.5 is the usual cut-off for the modified z-score.
+pub const OUTLIER_Z_THRESHOLD: f64 = 3.5;
+
+/// Fewer timed segments than this on a split and it isn't judged by
+/// z-score at all: there's no telling what's normal yet.
+pub const OUTLIER_MIN_SAMPLES: usize = 5;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum OutlierReason {
+    /// Implausibly far from the split's median segment: `z` is the robust
+    /// z-score (negative = faster) and `median` what it was compared to.
+    FarFromMedian { z: f64, median: Duration },
+    /// A combined segment over splits `span_start..=split_index` that beat
+    /// the sum of those splits' Best Segments, which no real segment can.
+    FasterThanBestSegments {
+        span_start: usize,
+        sum_of_best: Duration,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SegmentOutlier {
+    pub split_index: usize,
+    pub run_index: u32,
+    pub time: Duration,
+    pub reason: OutlierReason,
+}
+
+impl Run {
+    /// Every segment history entry that looks like a mis-split on
+    /// `method`, in split order.
+    pub fn segment_outliers(&self, method: TimingMethod) -> Vec<SegmentOutlier> {
+        let mut outliers = Vec::new();
+        for (i, split) in self.splits.iter().enumerate() {
+            let mut times: Vec<Duration> = split
+                .segment_history
+                .iter()
+                .filter(|e| !e.combined)
+                .filter_map(|e| e.get(method))
+                .collect();
+            times.sort_unstable();
+            let median = quantile(&times, 0.5);
+
+            for entry in &split.segment_history {
+                let Some(time) = entry.get(method) else {
+                    continue;
+                };
+                let reason = if entry.combined {
+                    self.combined_outlier(i, entry.run_index, time, method)
+                } else if times.len() >= OUTLIER_MIN_SAMPLES {
+                    robust_z_score(time, &times)
+                        .filter(|z| z.abs() > OUTLIER_Z_THRESHOLD)
+                        .zip(median)
+                        .map(|(z, median)| OutlierReason::FarFromMedian { z, median })
+                } else {
+                    None
+                };
+                if let Some(reason) = reason {
+                    outliers.push(SegmentOutlier {
+                        split_index: i,
+                        run_index: entry.run_index,
+                        time,
+                        reason,
+                    });
+                }
+            }
+        }
+        outliers
+    }
+
+    /// Deletes the given `(split_index, run_index)` segment entries, then
+    /// recomputes Best Segments for the splits touched and the Personal
+    /// Best.
+    pub fn remove_segment_entries(&mut self, entries: &[(usize, u32)]) {
+        for (i, split) in self.splits.iter_mut().enumerate() {
+            let before = split.segment_history.len();
+            split
+                .segment_history
+                .retain(|e| !entries.contains(&(i, e.run_index)));
+            if split.segment_history.len() != before {
+                split.recompute_best_segment();
+            }
+        }
+        self.recompute_personal_best();
+        self.generate_comparisons();
+    }
+
+    /// `FasterThanBestSegments` if the combined segment attempt
+    /// `run_index` recorded at split `index` took less than the Best
+    /// Segments of the splits it spans (back to its last timed split).
+    fn combined_outlier(
+        &self,
+        index: usize,
+        run_index: u32,
+        time: Duration,
+        method: TimingMethod,
+    ) -> Option<OutlierReason> {
+        let skipped = self.splits[..index]
+            .iter()
+            .rev()
+            .take_while(|s| {
+                s.segment_history
+                    .iter()
+                    .any(|e| e.run_index == run_index && e.get(method).is_none())
+            })
+            .count();
+        let span_start = index - skipped;
+
+        let sum_of_best = self.splits[span_start..=index]
+            .iter()
+            .map(|s| s.comparison_time(COMPARISON_BEST_SEGMENTS, method))
+            .sum::<Option<Duration>>()?;
+        (time < sum_of_best).then_some(OutlierReason::FasterThanBestSegments {
+            span_start,
+            sum_of_best,
+        })
+    }
+}
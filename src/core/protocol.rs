@@ -0,0 +1,331 @@
+//! The control socket's line protocol, shared by `core::server` (which
+//! speaks it over the Unix socket), `openspeedrun-cli` and
+//! `openspeedrun-autosplitter`. Each request is one line holding one
+//! command; each gets exactly one reply line, in order:
+//!
+//! - `ok` — an action command was carried out;
+//! - `ok <value>` — the answer to a query (`get*`, `version`);
+//! - `err <reason>` — nothing happened, and why.
+//!
+//! A few commands take an argument after a space (`setgametime <ms>`,
+//! `practice <split index>`, `note <text>`, `tag <name>`,
+//! `ghost <attempt #>`).
+//! A connection stays open for as many commands as the client cares to
+//! send. Times are whole milliseconds, deltas signed (negative = ahead),
+//! and `-` stands for "no value". `version` reports `PROTOCOL_VERSION`,
+//! which is bumped whenever an existing command's meaning or reply
+//! changes, so a client can check it's talking to a timer it understands.
+
+use std::fmt;
+
+use crate::app::AppState;
+use crate::core::split::TimingMethod;
+use crate::core::timer::TimerState;
+
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// Every command the server accepts. `reloadshader` is answered by
+/// `core::server` itself, since it has to reach the UI thread.
+pub const COMMANDS: &[&str] = &[
+    "split",
+    "skipsplit",
+    "start",
+    "pause",
+    "reset",
+    "practice",
+    "note",
+    "tag",
+    "savepb",
+    "undolastsplit",
+    "loadbackup",
+    "nextpage",
+    "prevpage",
+    "togglehelp",
+    "toggleloading",
+    "cyclecomparison",
+    "ghost",
+    "initgametime",
+    "setgametime",
+    "pausegametime",
+    "resumegametime",
+    "reloadall",
+    "reloadrun",
+    "reloadtheme",
+    "reloadshader",
+    "version",
+    "getstate",
+    "getcurrenttime",
+    "getsplitindex",
+    "getdelta",
+    "getcomparison",
+];
+
+/// The subset of `COMMANDS` that takes an argument.
+pub const COMMANDS_WITH_ARGUMENT: &[&str] = &["setgametime", "practice", "note", "tag", "ghost"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Reply {
+    Ok(Option<String>),
+    Err(String),
+}
+
+impl Reply {
+    pub fn ok() -> Self {
+        Reply::Ok(None)
+    }
+
+    pub fn value(value: impl ToString) -> Self {
+        Reply::Ok(Some(value.to_string()))
+    }
+
+    pub fn err(reason: impl Into<String>) -> Self {
+        Reply::Err(reason.into())
+    }
+
+    /// Reads a reply line back, for clients. `None` if it's neither an
+    /// `ok` nor an `err` line.
+    pub fn parse(line: &str) -> Option<Self> {
+        let line = line.trim_end_matches(['\r', '\n']);
+        if line == "ok" {
+            Some(Reply::Ok(None))
+        } else if let Some(value) = line.strip_prefix("ok ") {
+            Some(Reply::value(value))
+        } else if line == "err" {
+            Some(Reply::err(""))
+        } else {
+            line.strip_prefix("err ").map(Reply::err)
+        }
+    }
+}
+
+impl fmt::Display for Reply {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Reply::Ok(None) => write!(f, "ok"),
+            Reply::Ok(Some(value)) => write!(f, "ok {value}"),
+            Reply::Err(reason) => write!(f, "err {reason}"),
+        }
+    }
+}
+
+/// Runs one command line against `app` and says how it went. Refuses,
+/// rather than silently ignoring, anything the timer's current state
+/// doesn't allow.
+pub fn handle_command(app: &mut AppState, cmd: &str) -> Reply {
+    let cmd = cmd.trim();
+    let (name, arg) = match cmd.split_once(char::is_whitespace) {
+        Some((name, arg)) => (name, arg.trim()),
+        None => (cmd, ""),
+    };
+    if !arg.is_empty() && COMMANDS.contains(&name) && !COMMANDS_WITH_ARGUMENT.contains(&name) {
+        return Reply::err(format!("'{name}' takes no argument"));
+    }
+
+    let state = &app.timer.state;
+    let in_attempt = matches!(state, TimerState::Running | TimerState::Paused);
+    match name {
+        "" => Reply::err("empty command"),
+        "version" => Reply::value(PROTOCOL_VERSION),
+
+        "split" => match state {
+            TimerState::Paused => Reply::err("timer is paused"),
+            TimerState::Ended => Reply::err("run already finished"),
+            TimerState::Running if app.timer.current_time() < chrono::Duration::zero() => {
+                Reply::err("start offset countdown still running")
+            }
+            _ => {
+                app.split();
+                Reply::ok()
+            }
+        },
+        "skipsplit" => {
+            if *state != TimerState::Running {
+                Reply::err("timer not running")
+            } else if app.current_split + 1 >= app.splits_display.len() {
+                Reply::err("the final split can't be skipped")
+            } else {
+                app.skip_split();
+                Reply::ok()
+            }
+        }
+        "start" => match state {
+            TimerState::Running => Reply::err("timer already running"),
+            TimerState::Ended => Reply::err("run already finished"),
+            _ => {
+                app.start_timers();
+                Reply::ok()
+            }
+        },
+        "pause" => {
+            if *state != TimerState::Running {
+                return Reply::err("timer not running");
+            }
+            app.pause_timers();
+            Reply::ok()
+        }
+        "reset" => {
+            app.reset_splits();
+            Reply::ok()
+        }
+        "practice" => {
+            let Ok(index) = arg.parse::<usize>() else {
+                return Reply::err("practice needs a split index");
+            };
+            if *state != TimerState::NotStarted {
+                return Reply::err("can't start practice during a run");
+            }
+            if index >= app.splits_display.len() {
+                return Reply::err(format!(
+                    "split index out of range (0-{})",
+                    app.splits_display.len().saturating_sub(1)
+                ));
+            }
+            app.start_practice(index);
+            Reply::ok()
+        }
+        "note" => {
+            if arg.is_empty() {
+                return Reply::err("note needs some text");
+            }
+            match app.note_attempt(arg) {
+                Ok(()) => Reply::ok(),
+                Err(e) => Reply::err(e),
+            }
+        }
+        "tag" => {
+            if arg.is_empty() {
+                return Reply::err("tag needs a name");
+            }
+            if arg.contains(',') {
+                return Reply::err("tags can't contain commas");
+            }
+            match app.tag_attempt(arg) {
+                Ok(()) => Reply::ok(),
+                Err(e) => Reply::err(e),
+            }
+        }
+        "savepb" => match app.save_comparisons() {
+            Ok(()) => Reply::ok(),
+            Err(e) => Reply::err(format!("saving comparisons failed: {e}")),
+        },
+        "undolastsplit" => {
+            if app.current_split == 0 {
+                return Reply::err("no split to undo");
+            }
+            app.undo_split();
+            Reply::ok()
+        }
+        "loadbackup" => {
+            app.undo_pb();
+            Reply::ok()
+        }
+        "toggleloading" => {
+            if *state != TimerState::Running {
+                return Reply::err("timer not running");
+            }
+            if app.game_time_external {
+                return Reply::err("game time is externally controlled");
+            }
+            app.toggle_igt_pause();
+            Reply::ok()
+        }
+        "initgametime" => {
+            app.init_game_time();
+            Reply::ok()
+        }
+        "setgametime" => {
+            let Ok(ms) = arg.parse::<i64>() else {
+                return Reply::err("setgametime needs a time in milliseconds");
+            };
+            if ms < 0 {
+                return Reply::err("game time can't be negative");
+            }
+            if !in_attempt {
+                return Reply::err("no attempt in progress");
+            }
+            app.set_game_time(chrono::Duration::milliseconds(ms));
+            Reply::ok()
+        }
+        "pausegametime" | "resumegametime" if !in_attempt => Reply::err("no attempt in progress"),
+        "pausegametime" => {
+            app.pause_game_time();
+            Reply::ok()
+        }
+        "resumegametime" => {
+            app.resume_game_time();
+            Reply::ok()
+        }
+        "cyclecomparison" => {
+            app.cycle_comparison();
+            Reply::ok()
+        }
+        "ghost" => {
+            let Ok(run_index) = arg.parse::<u32>() else {
+                return Reply::err("ghost needs an attempt number");
+            };
+            match app.add_attempt_comparison(run_index) {
+                Ok(_) => Reply::ok(),
+                Err(e) => Reply::err(e),
+            }
+        }
+        "nextpage" => {
+            let total_pages = app.run.splits.len().div_ceil(app.splits_per_page);
+            if app.current_page + 1 >= total_pages {
+                return Reply::err("already on the last page");
+            }
+            app.current_page += 1;
+            Reply::ok()
+        }
+        "prevpage" => {
+            if app.current_page == 0 {
+                return Reply::err("already on the first page");
+            }
+            app.current_page -= 1;
+            Reply::ok()
+        }
+        "togglehelp" => {
+            app.show_help = !app.show_help;
+            Reply::ok()
+        }
+        "reloadall" | "reloadrun" | "reloadtheme" if *state != TimerState::NotStarted => {
+            Reply::err("can't reload during a run")
+        }
+        "reloadall" => {
+            app.reload_all();
+            Reply::ok()
+        }
+        "reloadrun" => {
+            app.reload_run();
+            Reply::ok()
+        }
+        "reloadtheme" => {
+            app.reload_theme();
+            Reply::ok()
+        }
+
+        "getstate" => Reply::value(match state {
+            TimerState::NotStarted => "not_started",
+            TimerState::Running => "running",
+            TimerState::Paused => "paused",
+            TimerState::Ended => "ended",
+        }),
+        "getcurrenttime" => {
+            let timer = match app.run.timing_method {
+                TimingMethod::RealTime => &app.timer,
+                TimingMethod::GameTime => &app.igt_timer,
+            };
+            Reply::value(timer.current_time().num_milliseconds())
+        }
+        "getsplitindex" => match state {
+            TimerState::NotStarted => Reply::value(-1),
+            _ => Reply::value(app.current_split),
+        },
+        "getdelta" => match app.delta_series(app.elapsed_split_time()).last() {
+            Some((_, seconds)) => Reply::value((seconds * 1000.0).round() as i64),
+            None => Reply::value("-"),
+        },
+        "getcomparison" => Reply::value(&app.run.selected_comparison),
+
+        other => Reply::err(format!("unknown command '{other}'")),
+    }
+}
@@ -0,0 +1,335 @@
+//! Editing a run's list of splits without breaking its history. Past
+//! attempts keep every segment time they recorded, so their cumulative
+//! times (and `attempt_history` totals) stay right; where the new layout
+//! doesn't match what an attempt actually did, it's recorded the same way
+//! a skipped split is (an empty entry, then a `combined` segment).
+
+use chrono::Duration;
+
+use crate::core::split::{
+    COMPARISON_BEST_SEGMENTS, ComparisonTime, Run, SegmentHistoryEntry, Split, TimingMethod,
+};
+
+const METHODS: [TimingMethod; 2] = [TimingMethod::RealTime, TimingMethod::GameTime];
+
+fn has_time(entry: &SegmentHistoryEntry) -> bool {
+    entry.real_time.is_some() || entry.game_time.is_some()
+}
+
+fn set_time(entry: &mut SegmentHistoryEntry, method: TimingMethod, value: Option<Duration>) {
+    match method {
+        TimingMethod::RealTime => entry.real_time = value,
+        TimingMethod::GameTime => entry.game_time = value,
+    }
+}
+
+impl Run {
+    /// Inserts a new split named `name` at `index` (or at the end, if past
+    /// it), in the section of the split it's inserted before. Attempts
+    /// that got past that point get a skipped entry for it, which makes
+    /// their next segment a combined one. Existing comparisons are kept —
+    /// the next split's PB and gold now just cover both — and the new
+    /// split starts without any.
+    pub fn insert_split(&mut self, index: usize, name: &str) {
+        let index = index.min(self.splits.len());
+        let mut split = Split {
+            name: name.to_string(),
+            section: self.splits.get(index).and_then(|s| s.section.clone()),
+            ..Split::default()
+        };
+        if let Some(next) = self.splits.get_mut(index) {
+            for entry in &mut next.segment_history {
+                split.segment_history.push(SegmentHistoryEntry {
+                    run_index: entry.run_index,
+                    ..Default::default()
+                });
+                entry.combined = has_time(entry);
+            }
+        }
+        self.splits.insert(index, split);
+        self.remap_reset_split_indices(|r| if r > index { r + 1 } else { r });
+        self.generate_comparisons();
+    }
+
+    /// Splits split `index` in two: a new split named `first_name` is
+    /// inserted before it for the first part, and the original keeps the
+    /// rest. History is handled as in `insert_split`; the original's
+    /// comparisons other than Best Segments are divided between the two at
+    /// `fraction` (`0.0..=1.0`), so their totals don't change.
+    pub fn split_in_two(&mut self, index: usize, first_name: &str, fraction: f64) {
+        if index >= self.splits.len() {
+            return;
+        }
+        self.insert_split(index, first_name);
+
+        let fraction = fraction.clamp(0.0, 1.0);
+        let (before, after) = self.splits.split_at_mut(index + 1);
+        let (first, second) = (&mut before[index], &mut after[0]);
+        for (name, time) in &mut second.comparisons {
+            if name == COMPARISON_BEST_SEGMENTS {
+                continue;
+            }
+            for method in METHODS {
+                let Some(total) = time.get(method) else {
+                    continue;
+                };
+                let part = Duration::milliseconds(
+                    (total.num_milliseconds() as f64 * fraction).round() as i64,
+                );
+                first
+                    .comparisons
+                    .entry(name.clone())
+                    .or_default()
+                    .set(method, Some(part));
+                time.set(method, Some(total - part));
+            }
+        }
+        self.generate_comparisons();
+    }
+
+    /// Removes split `index`, merging its segments into the next split (or,
+    /// for the final split, into the previous one, which becomes the final
+    /// split). Comparisons are summed across the two, Best Segments comes
+    /// from the merged history where there is any, and the Personal Best is
+    /// recomputed. Does nothing to the last remaining split.
+    pub fn remove_split(&mut self, index: usize) {
+        let len = self.splits.len();
+        if index >= len || len < 2 {
+            return;
+        }
+        if index + 1 < len {
+            self.merge_with_next(index, false);
+        } else {
+            self.merge_with_next(index - 1, true);
+        }
+    }
+
+    /// Moves split `from` to position `to`, taking its history and
+    /// comparisons along. A reset attempt keeps the split it was reset on,
+    /// unless it has a time on a split that's now after that one, in which
+    /// case it counts as reset just past its furthest time. Either way it
+    /// keeps all of its times: any split before that it never reached is
+    /// recorded as skipped, and skips that no longer come before it are
+    /// dropped again. Other splits' golds are left alone; the moved
+    /// split's only improves if its history now beats it.
+    pub fn move_split(&mut self, from: usize, to: usize) {
+        let len = self.splits.len();
+        if from >= len || to >= len || from == to {
+            return;
+        }
+        let split = self.splits.remove(from);
+        self.splits.insert(to, split);
+
+        self.remap_reset_split_indices(|r| {
+            if r == from {
+                to
+            } else if from < to && r > from && r <= to {
+                r - 1
+            } else if to < from && r >= to && r < from {
+                r + 1
+            } else {
+                r
+            }
+        });
+
+        for attempt in self.attempt_history.iter_mut().filter(|a| !a.ended) {
+            let Some(reset_at) = attempt.reset_split_index.as_mut() else {
+                continue;
+            };
+            let run_index = attempt.run_index;
+            let furthest_time = self.splits.iter().rposition(|s| {
+                s.segment_history
+                    .iter()
+                    .any(|e| e.run_index == run_index && has_time(e))
+            });
+            if let Some(furthest) = furthest_time {
+                *reset_at = (*reset_at).max(furthest + 1).min(len - 1);
+            }
+            for (i, split) in self.splits.iter_mut().enumerate() {
+                let history = &mut split.segment_history;
+                if i < *reset_at {
+                    if !history.iter().any(|e| e.run_index == run_index) {
+                        history.push(SegmentHistoryEntry {
+                            run_index,
+                            ..Default::default()
+                        });
+                    }
+                } else {
+                    history.retain(|e| e.run_index != run_index || has_time(e));
+                }
+            }
+        }
+
+        self.mark_combined_segments();
+        let moved = &mut self.splits[to];
+        for method in METHODS {
+            let history_best = moved
+                .segment_history
+                .iter()
+                .filter(|e| !e.combined)
+                .filter_map(|e| e.get(method))
+                .min();
+            let best = moved
+                .comparisons
+                .entry(COMPARISON_BEST_SEGMENTS.to_string())
+                .or_default();
+            if history_best.is_some_and(|h| best.get(method).is_none_or(|b| h < b)) {
+                best.set(method, history_best);
+            }
+        }
+        self.refresh_personal_best();
+        self.generate_comparisons();
+    }
+
+    /// Merges split `first` and the one after it into one, at `first`,
+    /// keeping the name, icon and section of `first` if `keep_first`, or
+    /// of the other one otherwise.
+    fn merge_with_next(&mut self, first: usize, keep_first: bool) {
+        let b = self.splits.remove(first + 1);
+        let a = self.splits.remove(first);
+
+        // An attempt that reached `a` but skipped `b` has its `a` time
+        // carried to its next timed segment, which spanned `b`.
+        let mut carried: Vec<(u32, TimingMethod, Duration)> = Vec::new();
+        let segment_history: Vec<SegmentHistoryEntry> = b
+            .segment_history
+            .iter()
+            .map(|eb| {
+                let Some(ea) = a
+                    .segment_history
+                    .iter()
+                    .find(|e| e.run_index == eb.run_index)
+                else {
+                    return eb.clone();
+                };
+                let mut merged = SegmentHistoryEntry {
+                    run_index: eb.run_index,
+                    ..Default::default()
+                };
+                for method in METHODS {
+                    let time = match (ea.get(method), eb.get(method)) {
+                        (Some(x), Some(y)) => Some(x + y),
+                        (None, y) => y,
+                        (Some(x), None) => {
+                            carried.push((eb.run_index, method, x));
+                            None
+                        }
+                    };
+                    set_time(&mut merged, method, time);
+                }
+                merged
+            })
+            .collect();
+
+        let mut comparisons = a.comparisons.clone();
+        for (name, time) in &b.comparisons {
+            let merged = comparisons.entry(name.clone()).or_default();
+            for method in METHODS {
+                let sum = merged.get(method).zip(time.get(method)).map(|(x, y)| x + y);
+                merged.set(method, sum);
+            }
+        }
+        for name in a.comparisons.keys() {
+            if !b.comparisons.contains_key(name) {
+                comparisons.insert(name.clone(), ComparisonTime::default());
+            }
+        }
+
+        let kept = if keep_first { a } else { b };
+        let mut merged = Split {
+            segment_history,
+            comparisons,
+            ..kept
+        };
+
+        // The merged segment only spans skips if the split before it was
+        // skipped too.
+        let previous = first.checked_sub(1).map(|i| &self.splits[i]);
+        for entry in &mut merged.segment_history {
+            entry.combined = has_time(entry)
+                && previous.is_some_and(|p| {
+                    p.segment_history
+                        .iter()
+                        .any(|e| e.run_index == entry.run_index && !has_time(e))
+                });
+        }
+
+        let best = merged
+            .comparisons
+            .entry(COMPARISON_BEST_SEGMENTS.to_string())
+            .or_default();
+        for method in METHODS {
+            let history_best = merged
+                .segment_history
+                .iter()
+                .filter(|e| !e.combined)
+                .filter_map(|e| e.get(method))
+                .min();
+            if history_best.is_some() {
+                best.set(method, history_best);
+            }
+        }
+
+        self.splits.insert(first, merged);
+
+        for (run_index, method, time) in carried {
+            let next = self.splits[first + 1..]
+                .iter_mut()
+                .filter_map(|s| {
+                    s.segment_history
+                        .iter_mut()
+                        .find(|e| e.run_index == run_index)
+                })
+                .find(|e| e.get(method).is_some());
+            if let Some(entry) = next {
+                set_time(entry, method, entry.get(method).map(|t| t + time));
+            }
+        }
+
+        self.remap_reset_split_indices(|r| if r > first { r - 1 } else { r });
+        self.refresh_personal_best();
+        self.generate_comparisons();
+    }
+
+    /// Sets `combined` on every segment that follows a skipped one in the
+    /// same attempt, and clears it everywhere else.
+    fn mark_combined_segments(&mut self) {
+        for i in 0..self.splits.len() {
+            let (before, after) = self.splits.split_at_mut(i);
+            let previous = before.last();
+            for entry in &mut after[0].segment_history {
+                entry.combined = has_time(entry)
+                    && previous.is_some_and(|p| {
+                        p.segment_history
+                            .iter()
+                            .any(|e| e.run_index == entry.run_index && !has_time(e))
+                    });
+            }
+        }
+    }
+
+    fn remap_reset_split_indices(&mut self, remap: impl Fn(usize) -> usize) {
+        for attempt in &mut self.attempt_history {
+            if let Some(index) = attempt.reset_split_index.as_mut() {
+                *index = remap(*index);
+            }
+        }
+    }
+
+    /// `recompute_personal_best`, but only when some finished attempt has
+    /// a time on every split — otherwise it would wipe a PB that was
+    /// entered by hand or imported without history.
+    fn refresh_personal_best(&mut self) {
+        let method = self.timing_method;
+        let has_complete_attempt = self.attempt_history.iter().filter(|a| a.ended).any(|a| {
+            self.splits.iter().all(|s| {
+                s.segment_history
+                    .iter()
+                    .any(|e| e.run_index == a.run_index && e.get(method).is_some())
+            })
+        });
+        if has_complete_attempt {
+            self.recompute_personal_best();
+        }
+    }
+}
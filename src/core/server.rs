@@ -1,12 +1,13 @@
 use std::os::unix::fs::PermissionsExt;
 use std::sync::{Arc, Mutex};
-use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
 #[cfg(unix)]
-use tokio::net::UnixListener;
+use tokio::net::{UnixListener, UnixStream};
 
 use std::sync::mpsc::Sender;
 
 use crate::app::AppState;
+use crate::core::protocol::{Reply, handle_command};
 use crate::core::socket_path;
 
 #[derive(Debug)]
@@ -31,6 +32,12 @@ pub async fn listen_for_commands(app: Arc<Mutex<AppState>>, tx: Sender<UICommand
 
     println!("Listening for commands on {}", socket_path.display());
 
+    serve(listener, app, tx).await;
+}
+
+/// Accepts connections forever, each handled on its own task. Split out
+/// from `listen_for_commands` so tests can serve on a socket of their own.
+pub async fn serve(listener: UnixListener, app: Arc<Mutex<AppState>>, tx: Sender<UICommand>) {
     loop {
         match listener.accept().await {
             Ok((stream, _)) => {
@@ -38,55 +45,8 @@ pub async fn listen_for_commands(app: Arc<Mutex<AppState>>, tx: Sender<UICommand
                 let tx = tx.clone();
 
                 tokio::spawn(async move {
-                    let reader = BufReader::new(stream);
-                    let mut lines = reader.lines();
-
-                    if let Ok(Some(line)) = lines.next_line().await {
-                        let cmd = line.trim();
-                        println!("Received command: '{}'", cmd);
-                        if cmd.is_empty() {
-                            eprintln!("Empty command received.");
-                            return;
-                        }
-
-                        if cmd == "reloadshader" {
-                            let _ = tx.send(UICommand::ReloadShader);
-                            return;
-                        }
-
-                        let mut app = app.lock().unwrap();
-                        match cmd {
-                            "split" => app.split(),
-                            "start" => app.start_timers(),
-                            "pause" => app.pause_timers(),
-                            "reset" => app.reset_splits(),
-                            "savepb" => {
-                                if let Err(e) = app.save_comparisons() {
-                                    eprintln!("Error saving comparisons: {}", e);
-                                }
-                            }
-                            "undolastsplit" => app.undo_split(),
-                            "loadbackup" => app.undo_pb(),
-                            "toggleloading" => app.toggle_igt_pause(),
-                            "cyclecomparison" => app.cycle_comparison(),
-                            "nextpage" => {
-                                let total_splits = app.run.splits.len();
-                                let total_pages = total_splits.div_ceil(app.splits_per_page);
-                                if app.current_page + 1 < total_pages {
-                                    app.current_page += 1;
-                                }
-                            }
-                            "prevpage" => {
-                                if app.current_page > 0 {
-                                    app.current_page -= 1;
-                                }
-                            }
-                            "togglehelp" => app.show_help = !app.show_help,
-                            "reloadall" => app.reload_all(),
-                            "reloadrun" => app.reload_run(),
-                            "reloadtheme" => app.reload_theme(),
-                            other => eprintln!("Unknown command: '{}'", other),
-                        }
+                    if let Err(e) = handle_connection(stream, app, tx).await {
+                        eprintln!("Control connection ended: {}", e);
                     }
                 });
             }
@@ -94,3 +54,35 @@ pub async fn listen_for_commands(app: Arc<Mutex<AppState>>, tx: Sender<UICommand
         }
     }
 }
+
+/// Answers each command line on `stream` in turn (see `core::protocol`)
+/// until the client hangs up.
+async fn handle_connection(
+    stream: UnixStream,
+    app: Arc<Mutex<AppState>>,
+    tx: Sender<UICommand>,
+) -> std::io::Result<()> {
+    let (reader, mut writer) = stream.into_split();
+    let mut lines = BufReader::new(reader).lines();
+
+    while let Some(line) = lines.next_line().await? {
+        let cmd = line.trim();
+        println!("Received command: '{}'", cmd);
+
+        let reply = if cmd == "reloadshader" {
+            match tx.send(UICommand::ReloadShader) {
+                Ok(()) => Reply::ok(),
+                Err(_) => Reply::err("timer window is gone"),
+            }
+        } else {
+            handle_command(&mut app.lock().unwrap(), cmd)
+        };
+
+        if let Reply::Err(reason) = &reply {
+            eprintln!("Command '{}' failed: {}", cmd, reason);
+        }
+        writer.write_all(format!("{reply}\n").as_bytes()).await?;
+    }
+
+    Ok(())
+}
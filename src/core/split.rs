@@ -1,6 +1,10 @@
 use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
+use std::ops::Range;
+use std::sync::{Arc, RwLock};
+
+use crate::core::stats;
 
 /// Bumped whenever the on-disk shape of `Run`/`Split` changes in a way that
 /// needs a migration. Files written before this field existed deserialize
@@ -11,14 +15,24 @@ pub const COMPARISON_PERSONAL_BEST: &str = "Personal Best";
 pub const COMPARISON_BEST_SEGMENTS: &str = "Best Segments";
 pub const COMPARISON_AVERAGE_SEGMENTS: &str = "Average Segments";
 pub const COMPARISON_MEDIAN_SEGMENTS: &str = "Median Segments";
-
-/// Comparisons every split always has an entry for. `"Average"`/`"Median"`
-/// are computed on the fly from `segment_history` instead of being stored.
+pub const COMPARISON_WORST_SEGMENTS: &str = "Worst Segments";
+pub const COMPARISON_LATEST_RUN: &str = "Latest Run";
+pub const COMPARISON_BEST_SPLIT_TIMES: &str = "Best Split Times";
+pub const COMPARISON_BALANCED_PB: &str = "Balanced PB";
+
+/// Comparisons every run offers. Only Personal Best and Best Segments are
+/// stored; `"Average"`/`"Median"`/`"Worst Segments"` are computed on the
+/// fly from `segment_history`, and the rest come from the built-in
+/// `ComparisonGenerator`s.
 pub const BUILTIN_COMPARISONS: &[&str] = &[
     COMPARISON_PERSONAL_BEST,
     COMPARISON_BEST_SEGMENTS,
     COMPARISON_AVERAGE_SEGMENTS,
     COMPARISON_MEDIAN_SEGMENTS,
+    COMPARISON_WORST_SEGMENTS,
+    COMPARISON_LATEST_RUN,
+    COMPARISON_BEST_SPLIT_TIMES,
+    COMPARISON_BALANCED_PB,
 ];
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
@@ -82,8 +96,21 @@ pub struct Split {
     /// hold custom-named comparisons (e.g. imported from a `.lss` file).
     pub comparisons: BTreeMap<String, ComparisonTime>,
     /// Segment time for every attempt that reached this split (not just
-    /// record-breaking ones) — the source data for "Average"/"Median".
+    /// record-breaking ones) — the source data for "Average"/"Median". An
+    /// attempt that skipped this split has an entry with no times at all.
     pub segment_history: Vec<SegmentHistoryEntry>,
+    /// Name of the section (group of subsplits) this split belongs to.
+    /// Consecutive splits with the same section form one group, whose
+    /// last split ends it — see `Run::sections`.
+    pub section: Option<String>,
+    /// Segment times from practice sessions (see `AppState::start_practice`),
+    /// kept out of `segment_history` so practice never counts as an
+    /// attempt or moves the PB, golds or averages.
+    pub practice_history: Vec<PracticeEntry>,
+    /// Segment times of the comparisons `Run::generate_comparisons` builds
+    /// from the whole run's history. Never saved; regenerated on load.
+    #[serde(skip)]
+    pub generated: BTreeMap<String, ComparisonTime>,
 }
 
 impl Split {
@@ -96,29 +123,44 @@ impl Split {
         }
     }
 
-    /// Looks up a comparison by name. `"Average Segments"`/`"Median
-    /// Segments"` are computed from `segment_history`; anything else is a
-    /// direct lookup in `comparisons` (built-in or custom).
+    /// Looks up a comparison by name. Generated ones come from `generated`
+    /// (which also holds Average/Median/Worst Segments while
+    /// `Run::comparison_tag` is set); otherwise `"Average"`/`"Median"`/
+    /// `"Worst Segments"` are computed from `segment_history`, and anything
+    /// else is a direct lookup in `comparisons` (built-in or custom).
     pub fn comparison_time(&self, name: &str, method: TimingMethod) -> Option<Duration> {
+        if let Some(generated) = self.generated.get(name) {
+            return generated.get(method);
+        }
         match name {
-            COMPARISON_AVERAGE_SEGMENTS => Self::segment_stat(&self.segment_history, method, false),
-            COMPARISON_MEDIAN_SEGMENTS => Self::segment_stat(&self.segment_history, method, true),
+            COMPARISON_AVERAGE_SEGMENTS => {
+                Self::segment_stat(&self.segment_history, method, SegmentStat::Average)
+            }
+            COMPARISON_MEDIAN_SEGMENTS => {
+                Self::segment_stat(&self.segment_history, method, SegmentStat::Median)
+            }
+            COMPARISON_WORST_SEGMENTS => {
+                Self::segment_stat(&self.segment_history, method, SegmentStat::Worst)
+            }
             _ => self.comparisons.get(name).and_then(|c| c.get(method)),
         }
     }
 
     /// Recomputes the "Best Segments" comparison from whatever remains in
     /// `segment_history` — used after deleting an erroneous entry so a
-    /// removed record doesn't linger in `comparisons`.
+    /// removed record doesn't linger in `comparisons`. Combined segments
+    /// (see `SegmentHistoryEntry::combined`) never count as a gold.
     pub fn recompute_best_segment(&mut self) {
         let best_real = self
             .segment_history
             .iter()
+            .filter(|e| !e.combined)
             .filter_map(|e| e.real_time)
             .min();
         let best_game = self
             .segment_history
             .iter()
+            .filter(|e| !e.combined)
             .filter_map(|e| e.game_time)
             .min();
 
@@ -133,10 +175,11 @@ impl Split {
     fn segment_stat(
         history: &[SegmentHistoryEntry],
         method: TimingMethod,
-        median: bool,
+        stat: SegmentStat,
     ) -> Option<Duration> {
         let mut millis: Vec<i64> = history
             .iter()
+            .filter(|e| !e.combined)
             .filter_map(|e| e.get(method))
             .map(|d| d.num_milliseconds())
             .collect();
@@ -145,22 +188,31 @@ impl Split {
             return None;
         }
 
-        let result = if median {
-            millis.sort_unstable();
-            let mid = millis.len() / 2;
-            if millis.len().is_multiple_of(2) {
-                (millis[mid - 1] + millis[mid]) / 2
-            } else {
-                millis[mid]
+        let result = match stat {
+            SegmentStat::Median => {
+                millis.sort_unstable();
+                let mid = millis.len() / 2;
+                if millis.len().is_multiple_of(2) {
+                    (millis[mid - 1] + millis[mid]) / 2
+                } else {
+                    millis[mid]
+                }
             }
-        } else {
-            millis.iter().sum::<i64>() / millis.len() as i64
+            SegmentStat::Average => millis.iter().sum::<i64>() / millis.len() as i64,
+            SegmentStat::Worst => millis.iter().copied().max().unwrap_or_default(),
         };
 
         Some(Duration::milliseconds(result))
     }
 }
 
+#[derive(Clone, Copy)]
+enum SegmentStat {
+    Average,
+    Median,
+    Worst,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
 pub struct Run {
@@ -172,6 +224,8 @@ pub struct Run {
     pub format_version: u32,
     pub title: String,
     pub category: String,
+    /// Every attempt ever started — finished and reset alike, as in
+    /// LiveSplit. Also the `run_index` of the most recent one.
     pub attempts: u32,
     pub splits: Vec<Split>,
     #[serde(default)]
@@ -183,6 +237,15 @@ pub struct Run {
     pub attempt_history: Vec<AttemptHistoryEntry>,
     pub pb_history: Vec<AttemptHistoryEntry>,
     pub metadata: RunMetadata,
+    /// Goal-time comparisons declared for this run (see
+    /// `GoalTimeGenerator`).
+    pub goal_comparisons: Vec<GoalTimeGenerator>,
+    /// Whether a practice segment faster than the split's Best Segment
+    /// becomes the new Best Segment.
+    pub promote_practice_golds: bool,
+    /// If set, Average/Median/Worst Segments and the generated comparisons
+    /// only use attempts with this tag (see `Run::restricted_to_tag`).
+    pub comparison_tag: Option<String>,
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -193,6 +256,149 @@ pub struct SegmentHistoryEntry {
     pub real_time: Option<Duration>,
     #[serde(with = "crate::core::split::duration_millis")]
     pub game_time: Option<Duration>,
+    /// Set when this attempt skipped the split(s) right before this one, so
+    /// the time spans all of them rather than just this split — not
+    /// comparable to a normal segment, and never a gold.
+    pub combined: bool,
+}
+
+/// A group of consecutive splits, as returned by `Run::sections`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SplitSection {
+    /// `None` for a split that isn't part of any section.
+    pub name: Option<String>,
+    pub range: Range<usize>,
+}
+
+impl SplitSection {
+    /// Index of the split that ends the section.
+    pub fn last(&self) -> usize {
+        self.range.end - 1
+    }
+}
+
+/// Builds a comparison from a run, for `Run::generate_comparisons`. The
+/// result is stored per split (in `Split::generated`), so `Split::
+/// comparison_time` and everything built on it see generated comparisons
+/// just like stored ones.
+pub trait ComparisonGenerator: Send + Sync {
+    /// The comparison's name, as listed by `Run::comparison_names`.
+    fn name(&self) -> &str;
+
+    /// One *segment* time per split, in split order. Missing entries are
+    /// treated as no time.
+    fn generate(&self, run: &Run) -> Vec<ComparisonTime>;
+}
+
+static REGISTERED_GENERATORS: RwLock<Vec<Arc<dyn ComparisonGenerator>>> = RwLock::new(Vec::new());
+
+/// Makes `generator` available to every run from then on, replacing a
+/// previously registered one of the same name. Runs already loaded pick
+/// it up at their next `Run::generate_comparisons`.
+pub fn register_comparison_generator(generator: Arc<dyn ComparisonGenerator>) {
+    let Ok(mut registered) = REGISTERED_GENERATORS.write() else {
+        return;
+    };
+    registered.retain(|g| g.name() != generator.name());
+    registered.push(generator);
+}
+
+/// The most recent attempt's segments, as far as it got.
+struct LatestRun;
+
+impl ComparisonGenerator for LatestRun {
+    fn name(&self) -> &str {
+        COMPARISON_LATEST_RUN
+    }
+
+    fn generate(&self, run: &Run) -> Vec<ComparisonTime> {
+        run.for_both_methods(|method| run.latest_run_segments(method))
+    }
+}
+
+/// The best time ever reached at each split, across attempts, turned
+/// back into segments.
+struct BestSplitTimes;
+
+impl ComparisonGenerator for BestSplitTimes {
+    fn name(&self) -> &str {
+        COMPARISON_BEST_SPLIT_TIMES
+    }
+
+    fn generate(&self, run: &Run) -> Vec<ComparisonTime> {
+        run.for_both_methods(|method| run.best_split_time_segments(method))
+    }
+}
+
+/// The PB's final time spread over the splits the way a run of even skill
+/// would (see `core::stats::pb_chance`), so it leans toward the Sum of
+/// Best where the PB left time on the table.
+struct BalancedPb;
+
+impl ComparisonGenerator for BalancedPb {
+    fn name(&self) -> &str {
+        COMPARISON_BALANCED_PB
+    }
+
+    fn generate(&self, run: &Run) -> Vec<ComparisonTime> {
+        run.for_both_methods(|method| run.balanced_pb_segments(method))
+    }
+}
+
+/// A target final time spread across the splits in proportion to their
+/// Best Segments, declared per run in `Run::goal_comparisons`:
+///
+/// ```json
+/// "goal_comparisons": [{ "name": "Sub 1:30", "goal": { "real_time": 5400000 } }]
+/// ```
+///
+/// A timing method without a goal time, or with a split that has no Best
+/// Segment, gets no times.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GoalTimeGenerator {
+    pub name: String,
+    pub goal: ComparisonTime,
+}
+
+impl GoalTimeGenerator {
+    fn segments(&self, run: &Run, method: TimingMethod) -> Vec<Option<Duration>> {
+        let spread = || -> Option<Vec<Duration>> {
+            let goal = self.goal.get(method)?.num_milliseconds();
+            let best: Vec<i64> = run
+                .splits
+                .iter()
+                .map(|s| s.comparison_time(COMPARISON_BEST_SEGMENTS, method))
+                .map(|time| time.map(|t| t.num_milliseconds()))
+                .collect::<Option<_>>()?;
+            let total: i64 = best.iter().sum();
+            if total <= 0 {
+                return None;
+            }
+            let mut segments: Vec<i64> = best
+                .iter()
+                .map(|&ms| (ms as i128 * goal as i128 / total as i128) as i64)
+                .collect();
+            // The last split takes the rounding, so the total is exact.
+            *segments.last_mut()? += goal - segments.iter().sum::<i64>();
+            Some(segments.into_iter().map(Duration::milliseconds).collect())
+        };
+
+        match spread() {
+            Some(segments) => segments.into_iter().map(Some).collect(),
+            None => vec![None; run.splits.len()],
+        }
+    }
+}
+
+impl ComparisonGenerator for GoalTimeGenerator {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn generate(&self, run: &Run) -> Vec<ComparisonTime> {
+        run.for_both_methods(|method| self.segments(run, method))
+    }
 }
 
 impl SegmentHistoryEntry {
@@ -204,16 +410,48 @@ impl SegmentHistoryEntry {
     }
 }
 
+/// One practiced segment, as recorded in `Split::practice_history`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PracticeEntry {
+    #[serde(with = "crate::core::split::duration_millis")]
+    pub real_time: Option<Duration>,
+    #[serde(with = "crate::core::split::duration_millis")]
+    pub game_time: Option<Duration>,
+    pub date: Option<DateTime<Utc>>,
+}
+
+impl PracticeEntry {
+    pub fn get(&self, method: TimingMethod) -> Option<Duration> {
+        match method {
+            TimingMethod::RealTime => self.real_time,
+            TimingMethod::GameTime => self.game_time,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(default)]
 pub struct AttemptHistoryEntry {
     pub run_index: u32,
+    /// For a finished attempt, its final time; for a reset one, how long
+    /// it had been running when it was reset.
     #[serde(with = "crate::core::split::duration_millis")]
     pub real_time: Option<Duration>,
     #[serde(with = "crate::core::split::duration_millis")]
     pub game_time: Option<Duration>,
+    /// `false` for an attempt that was reset before the final split.
     pub ended: bool,
+    /// When the attempt finished or was reset.
     pub date: Option<DateTime<Utc>>,
+    /// Index of the split that was in progress when a reset attempt was
+    /// reset (`0` = reset during the first split). `None` for finished
+    /// attempts.
+    pub reset_split_index: Option<usize>,
+    /// Free text: why it died, what was tried.
+    pub note: Option<String>,
+    /// Labels to filter attempts by (a route, a patch, a controller).
+    pub tags: Vec<String>,
 }
 
 impl Run {
@@ -246,6 +484,9 @@ impl Run {
             attempt_history: Vec::new(),
             pb_history: Vec::new(),
             metadata: RunMetadata::default(),
+            goal_comparisons: Vec::new(),
+            promote_practice_golds: false,
+            comparison_tag: None,
         }
     }
 
@@ -259,30 +500,42 @@ impl Run {
             .map(|times| times.into_iter().fold(Duration::zero(), |a, b| a + b))
     }
 
+    /// What `attempt` adds up to on `method`, if it can be a Personal
+    /// Best at all: it finished, with a time on every split. Reset attempts
+    /// and ones with a skipped split give `None`.
+    pub fn personal_best_total(
+        &self,
+        attempt: &AttemptHistoryEntry,
+        method: TimingMethod,
+    ) -> Option<Duration> {
+        if !attempt.ended {
+            return None;
+        }
+        self.splits
+            .iter()
+            .map(|split| {
+                split
+                    .segment_history
+                    .iter()
+                    .find(|e| e.run_index == attempt.run_index)
+                    .and_then(|e| e.get(method))
+            })
+            .sum()
+    }
+
     /// Recomputes the "Personal Best" comparison on every split from
     /// `attempt_history` + each split's `segment_history` — used after
     /// deleting an erroneous attempt so a removed PB doesn't linger in
     /// `comparisons`. Only attempts marked `ended` are eligible, and only
-    /// if every split has a recorded segment for that attempt.
+    /// if every split has a recorded segment for that attempt — an attempt
+    /// that skipped a split has an empty entry there, so it never is.
     pub fn recompute_personal_best(&mut self) {
         let method = self.timing_method;
 
         let best_run_index = self
             .attempt_history
             .iter()
-            .filter(|a| a.ended)
-            .filter_map(|a| {
-                let mut total = Duration::zero();
-                for split in &self.splits {
-                    let segment = split
-                        .segment_history
-                        .iter()
-                        .find(|e| e.run_index == a.run_index)
-                        .and_then(|e| e.get(method))?;
-                    total += segment;
-                }
-                Some((a.run_index, total))
-            })
+            .filter_map(|a| Some((a.run_index, self.personal_best_total(a, method)?)))
             .min_by_key(|(_, total)| *total)
             .map(|(run_index, _)| run_index);
 
@@ -304,10 +557,224 @@ impl Run {
         }
     }
 
-    /// Every comparison name currently in use: the built-ins plus any
-    /// custom ones present on at least one split.
+    /// The run's splits grouped into sections: one per run of consecutive
+    /// splits sharing a `Split::section`, and one per split outside any
+    /// section (with no name). The ranges cover every split in order.
+    pub fn sections(&self) -> Vec<SplitSection> {
+        let mut sections: Vec<SplitSection> = Vec::new();
+        for (i, split) in self.splits.iter().enumerate() {
+            match sections.last_mut() {
+                Some(last) if split.section.is_some() && last.name == split.section => {
+                    last.range.end = i + 1;
+                }
+                _ => sections.push(SplitSection {
+                    name: split.section.clone(),
+                    range: i..i + 1,
+                }),
+            }
+        }
+        sections
+    }
+
+    /// The section split `index` belongs to, if it's in range.
+    pub fn section_of(&self, index: usize) -> Option<SplitSection> {
+        self.sections()
+            .into_iter()
+            .find(|section| section.range.contains(&index))
+    }
+
+    /// Every comparison generator that applies to this run: the built-in
+    /// ones, then those added with `register_comparison_generator`, then
+    /// the run's own `goal_comparisons`.
+    pub fn comparison_generators(&self) -> Vec<Arc<dyn ComparisonGenerator>> {
+        let mut generators: Vec<Arc<dyn ComparisonGenerator>> = vec![
+            Arc::new(LatestRun),
+            Arc::new(BestSplitTimes),
+            Arc::new(BalancedPb),
+        ];
+        generators.extend(
+            REGISTERED_GENERATORS
+                .read()
+                .map(|registered| registered.clone())
+                .unwrap_or_default(),
+        );
+        for goal in &self.goal_comparisons {
+            generators.push(Arc::new(goal.clone()));
+        }
+        generators
+    }
+
+    /// Rebuilds every split's `generated` comparisons by running each of
+    /// `comparison_generators` over the run. A generator whose name is
+    /// already taken — by a stored comparison, or an earlier generator —
+    /// is skipped. With a `comparison_tag`, the generators (and Average/
+    /// Median/Worst Segments, stored here too) only see that tag's
+    /// attempts.
+    pub fn generate_comparisons(&mut self) {
+        for split in &mut self.splits {
+            split.generated.clear();
+        }
+
+        let restricted = self
+            .comparison_tag
+            .as_deref()
+            .map(|tag| self.restricted_to_tag(tag));
+        let source = restricted.as_ref().unwrap_or(self);
+
+        let mut generated: Vec<(String, Vec<ComparisonTime>)> = Vec::new();
+        if restricted.is_some() {
+            for name in [
+                COMPARISON_AVERAGE_SEGMENTS,
+                COMPARISON_MEDIAN_SEGMENTS,
+                COMPARISON_WORST_SEGMENTS,
+            ] {
+                let times = source.for_both_methods(|method| {
+                    source
+                        .splits
+                        .iter()
+                        .map(|s| s.comparison_time(name, method))
+                        .collect()
+                });
+                generated.push((name.to_string(), times));
+            }
+        }
+        for generator in self.comparison_generators() {
+            let name = generator.name();
+            let taken = generated.iter().any(|(n, _)| n == name)
+                || self.splits.iter().any(|s| s.comparisons.contains_key(name));
+            if taken || name.is_empty() {
+                continue;
+            }
+            generated.push((name.to_string(), generator.generate(source)));
+        }
+
+        for (name, times) in generated {
+            for (i, split) in self.splits.iter_mut().enumerate() {
+                let time = times.get(i).copied().unwrap_or_default();
+                split.generated.insert(name.clone(), time);
+            }
+        }
+    }
+
+    /// Runs `segments` for both clocks and zips the results per split.
+    fn for_both_methods(
+        &self,
+        segments: impl Fn(TimingMethod) -> Vec<Option<Duration>>,
+    ) -> Vec<ComparisonTime> {
+        segments(TimingMethod::RealTime)
+            .into_iter()
+            .zip(segments(TimingMethod::GameTime))
+            .map(|(real_time, game_time)| ComparisonTime {
+                real_time,
+                game_time,
+            })
+            .collect()
+    }
+
+    fn latest_run_segments(&self, method: TimingMethod) -> Vec<Option<Duration>> {
+        let latest = self.attempt_history.iter().map(|a| a.run_index).max();
+        self.splits
+            .iter()
+            .map(|split| {
+                split
+                    .segment_history
+                    .iter()
+                    .find(|e| Some(e.run_index) == latest)
+                    .and_then(|e| e.get(method))
+            })
+            .collect()
+    }
+
+    fn best_split_time_segments(&self, method: TimingMethod) -> Vec<Option<Duration>> {
+        let mut best: Vec<Option<Duration>> = vec![None; self.splits.len()];
+        for attempt in &self.attempt_history {
+            let mut total = Duration::zero();
+            for (i, split) in self.splits.iter().enumerate() {
+                let Some(entry) = split
+                    .segment_history
+                    .iter()
+                    .find(|e| e.run_index == attempt.run_index)
+                else {
+                    break;
+                };
+                // A skipped split has no time of its own; the next one's
+                // (combined) segment carries it.
+                let Some(segment) = entry.get(method) else {
+                    continue;
+                };
+                total += segment;
+                best[i] = Some(best[i].map_or(total, |b| b.min(total)));
+            }
+        }
+
+        let mut previous = Duration::zero();
+        best.into_iter()
+            .map(|time| {
+                time.map(|time| {
+                    let segment = time - previous;
+                    previous = time;
+                    segment
+                })
+            })
+            .collect()
+    }
+
+    fn balanced_pb_segments(&self, method: TimingMethod) -> Vec<Option<Duration>> {
+        let balanced = || -> Option<Vec<Duration>> {
+            let pb = self.comparison_total(COMPARISON_PERSONAL_BEST, method)?;
+            let history: Vec<Vec<Duration>> = self
+                .splits
+                .iter()
+                .map(|split| {
+                    let mut times: Vec<Duration> = split
+                        .segment_history
+                        .iter()
+                        .filter(|e| !e.combined)
+                        .filter_map(|e| e.get(method))
+                        .collect();
+                    times.sort();
+                    times
+                })
+                .collect();
+            let percentile = stats::pb_chance(Duration::zero(), &history, pb)?;
+            let segments: Vec<i64> = history
+                .iter()
+                .filter_map(|times| stats::quantile(times, percentile))
+                .map(|d| d.num_milliseconds())
+                .collect();
+
+            // Scale onto the PB exactly; the last split takes the rounding.
+            let total: i64 = segments.iter().sum();
+            if total <= 0 {
+                return None;
+            }
+            let pb_ms = pb.num_milliseconds();
+            let mut scaled: Vec<i64> = segments
+                .iter()
+                .map(|&ms| (ms as i128 * pb_ms as i128 / total as i128) as i64)
+                .collect();
+            let rounding = pb_ms - scaled.iter().sum::<i64>();
+            *scaled.last_mut()? += rounding;
+            Some(scaled.into_iter().map(Duration::milliseconds).collect())
+        };
+
+        match balanced() {
+            Some(segments) => segments.into_iter().map(Some).collect(),
+            None => vec![None; self.splits.len()],
+        }
+    }
+
+    /// Every comparison name currently in use: the built-ins, then the
+    /// names of `comparison_generators`, then any custom ones present on
+    /// at least one split.
     pub fn comparison_names(&self) -> Vec<String> {
         let mut names: Vec<String> = BUILTIN_COMPARISONS.iter().map(|s| s.to_string()).collect();
+        for generator in self.comparison_generators() {
+            let name = generator.name();
+            if !name.is_empty() && !names.iter().any(|n| n == name) {
+                names.push(name.to_string());
+            }
+        }
         for split in &self.splits {
             for key in split.comparisons.keys() {
                 if !names.contains(key) {
@@ -338,6 +805,7 @@ impl Run {
             }
         }
 
+        run.generate_comparisons();
         Ok(run)
     }
 
@@ -380,6 +848,7 @@ impl Run {
                     run_index,
                     real_time: Some(real_time),
                     game_time: None,
+                    combined: false,
                 })
                 .collect();
         }
@@ -401,6 +870,8 @@ impl Run {
                     game_time: None,
                     ended: e.ended,
                     date: e.date,
+                    reset_split_index: None,
+                    ..Default::default()
                 })
                 .collect()
         };
@@ -441,6 +912,9 @@ impl Default for Split {
             icon_path: None,
             comparisons,
             segment_history: Vec::new(),
+            section: None,
+            practice_history: Vec::new(),
+            generated: BTreeMap::new(),
         }
     }
 }
@@ -0,0 +1,212 @@
+//! Statistics over segment history, kept free of `AppState` so they can be
+//! checked against hand-made inputs.
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::core::split::{Run, TimingMethod};
+
+/// The `p`-quantile (`0.0..=1.0`) of `times`, interpolating linearly
+/// between neighbours. `times` must be sorted; `None` if it's empty.
+pub fn quantile(times: &[Duration], p: f64) -> Option<Duration> {
+    let last = times.len().checked_sub(1)?;
+    let position = p.clamp(0.0, 1.0) * last as f64;
+    let below = position.floor() as usize;
+    let above = position.ceil() as usize;
+    let fraction = position - below as f64;
+
+    let low = times[below].num_milliseconds() as f64;
+    let high = times[above].num_milliseconds() as f64;
+    Some(Duration::milliseconds(
+        (low + (high - low) * fraction).round() as i64,
+    ))
+}
+
+/// Chance of finishing under `target`, having `elapsed` on the clock with
+/// the segments `remaining` to go (one sorted list of past times each).
+///
+/// Uses the same skill curve as LiveSplit's PB chance: a run at
+/// percentile `p` is assumed to play every remaining segment at that
+/// segment's own `p`-quantile, and the answer is the highest `p` that
+/// still finishes in time. `None` if a remaining segment has no history.
+pub fn pb_chance(elapsed: Duration, remaining: &[Vec<Duration>], target: Duration) -> Option<f64> {
+    if remaining.iter().any(|times| times.is_empty()) {
+        return None;
+    }
+
+    let finish_at = |p: f64| -> Duration {
+        remaining
+            .iter()
+            .filter_map(|times| quantile(times, p))
+            .fold(elapsed, |total, segment| total + segment)
+    };
+
+    if finish_at(0.0) > target {
+        return Some(0.0);
+    }
+    if finish_at(1.0) <= target {
+        return Some(1.0);
+    }
+
+    let (mut low, mut high) = (0.0, 1.0);
+    for _ in 0..32 {
+        let mid = (low + high) / 2.0;
+        if finish_at(mid) <= target {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    Some(low)
+}
+
+/// How far `value` lies from the median of `times`, in (scaled) median
+/// absolute deviations: the robust z-score of Iglewicz and Hoaglin. Unlike
+/// a mean and standard deviation, a few wild values can't drag it around.
+/// `times` must be sorted; `None` if it's empty or its MAD is zero.
+pub fn robust_z_score(value: Duration, times: &[Duration]) -> Option<f64> {
+    let median = quantile(times, 0.5)?;
+    let mut deviations: Vec<Duration> = times.iter().map(|t| (*t - median).abs()).collect();
+    deviations.sort_unstable();
+    let mad = quantile(&deviations, 0.5)?.num_milliseconds();
+    if mad == 0 {
+        return None;
+    }
+    Some(0.6745 * (value - median).num_milliseconds() as f64 / mad as f64)
+}
+
+/// How one split fares across every logged attempt: how many got there,
+/// how many died there, and how its (non-combined) segment times spread.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SplitStatistics {
+    /// Attempts that started this split — finished ones, and resets at or
+    /// after it.
+    pub reached: u32,
+    /// Attempts reset during this split.
+    pub resets: u32,
+    /// `resets / reached`; `None` if nobody got here.
+    pub reset_rate: Option<f64>,
+    pub mean: Option<Duration>,
+    pub median: Option<Duration>,
+    /// Population standard deviation; `None` without any segment times.
+    pub std_dev: Option<Duration>,
+    /// Share of segment times that beat every earlier one, in attempt
+    /// order (the first ever isn't counted); `None` with fewer than two.
+    pub gold_rate: Option<f64>,
+}
+
+/// Per-split statistics for `run` on `method`, one entry per split — the
+/// numbers behind the history window's survival funnel. Attempts logged
+/// without a reset split (older files) can't be placed and are left out
+/// of `reached`/`resets`.
+pub fn split_statistics(run: &Run, method: TimingMethod) -> Vec<SplitStatistics> {
+    // The last split each attempt started, if known.
+    let furthest: Vec<usize> = run
+        .attempt_history
+        .iter()
+        .filter_map(|a| {
+            if a.ended {
+                Some(usize::MAX)
+            } else {
+                a.reset_split_index
+            }
+        })
+        .collect();
+
+    run.splits
+        .iter()
+        .enumerate()
+        .map(|(i, split)| {
+            let reached = furthest.iter().filter(|&&f| f >= i).count() as u32;
+            let resets = furthest.iter().filter(|&&f| f == i).count() as u32;
+
+            let mut history: Vec<_> = split
+                .segment_history
+                .iter()
+                .filter(|e| !e.combined)
+                .filter_map(|e| Some((e.run_index, e.get(method)?)))
+                .collect();
+            history.sort_by_key(|(run_index, _)| *run_index);
+
+            let mut golds = 0;
+            let mut best: Option<Duration> = None;
+            for (_, time) in &history {
+                if best.is_some_and(|b| *time < b) {
+                    golds += 1;
+                }
+                best = Some(best.map_or(*time, |b| b.min(*time)));
+            }
+
+            let mut times: Vec<Duration> = history.into_iter().map(|(_, t)| t).collect();
+            times.sort_unstable();
+            let millis: Vec<f64> = times.iter().map(|t| t.num_milliseconds() as f64).collect();
+            let mean =
+                (!millis.is_empty()).then(|| millis.iter().sum::<f64>() / millis.len() as f64);
+            let std_dev = mean.map(|mean| {
+                (millis.iter().map(|m| (m - mean).powi(2)).sum::<f64>() / millis.len() as f64)
+                    .sqrt()
+            });
+
+            SplitStatistics {
+                reached,
+                resets,
+                reset_rate: (reached > 0).then(|| resets as f64 / reached as f64),
+                mean: mean.map(|m| Duration::milliseconds(m.round() as i64)),
+                median: quantile(&times, 0.5),
+                std_dev: std_dev.map(|s| Duration::milliseconds(s.round() as i64)),
+                gold_rate: (times.len() > 1).then(|| golds as f64 / (times.len() - 1) as f64),
+            }
+        })
+        .collect()
+}
+
+/// Where the run stood right after one attempt.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProgressionPoint {
+    pub run_index: u32,
+    pub date: Option<DateTime<Utc>>,
+    /// Attempts so far, this one included.
+    pub attempts: u32,
+    /// `None` until every split has a (non-combined) segment time.
+    pub sum_of_best: Option<Duration>,
+    /// `None` until an attempt has finished.
+    pub personal_best: Option<Duration>,
+}
+
+/// Replays `attempt_history` in order, yielding the Sum of Best and
+/// Personal Best on `method` after each attempt — how both came down
+/// over time. Only attempts `Run::personal_best_total` accepts count
+/// towards the Personal Best.
+pub fn progression(run: &Run, method: TimingMethod) -> Vec<ProgressionPoint> {
+    let mut attempts: Vec<_> = run.attempt_history.iter().collect();
+    attempts.sort_by_key(|a| a.run_index);
+
+    let mut best: Vec<Option<Duration>> = vec![None; run.splits.len()];
+    let mut personal_best: Option<Duration> = None;
+    attempts
+        .into_iter()
+        .enumerate()
+        .map(|(n, attempt)| {
+            for (split, best) in run.splits.iter().zip(&mut best) {
+                let segment = split
+                    .segment_history
+                    .iter()
+                    .filter(|e| e.run_index == attempt.run_index && !e.combined)
+                    .find_map(|e| e.get(method));
+                if let Some(segment) = segment {
+                    *best = Some(best.map_or(segment, |b| b.min(segment)));
+                }
+            }
+            if let Some(total) = run.personal_best_total(attempt, method) {
+                personal_best = Some(personal_best.map_or(total, |pb| pb.min(total)));
+            }
+
+            ProgressionPoint {
+                run_index: attempt.run_index,
+                date: attempt.date,
+                attempts: n as u32 + 1,
+                sum_of_best: best.iter().copied().sum(),
+                personal_best,
+            }
+        })
+        .collect()
+}
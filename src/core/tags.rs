@@ -0,0 +1,42 @@
+//! Narrowing a run down to the attempts carrying one tag, so statistics
+//! and comparisons can be built from e.g. "new route" attempts only.
+
+use crate::core::split::{AttemptHistoryEntry, Run};
+
+impl AttemptHistoryEntry {
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t == tag)
+    }
+}
+
+impl Run {
+    /// Every tag used on an attempt, sorted, each once.
+    pub fn tags(&self) -> Vec<String> {
+        let mut tags: Vec<String> = self
+            .attempt_history
+            .iter()
+            .flat_map(|a| a.tags.iter().cloned())
+            .collect();
+        tags.sort();
+        tags.dedup();
+        tags
+    }
+
+    /// A copy of the run holding only the attempts tagged `tag`: in
+    /// `attempt_history`, `pb_history` and every split's segment history.
+    /// Stored comparisons (PB, Best Segments, custom) are kept as they are;
+    /// generated ones are dropped, since they came from the full history.
+    pub fn restricted_to_tag(&self, tag: &str) -> Run {
+        let mut run = self.clone();
+        run.attempt_history.retain(|a| a.has_tag(tag));
+        let kept: Vec<u32> = run.attempt_history.iter().map(|a| a.run_index).collect();
+        run.pb_history.retain(|p| kept.contains(&p.run_index));
+        for split in &mut run.splits {
+            split
+                .segment_history
+                .retain(|e| kept.contains(&e.run_index));
+            split.generated.clear();
+        }
+        run
+    }
+}
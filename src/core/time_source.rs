@@ -0,0 +1,97 @@
+//! Where `Timer` gets "now" from. Production uses `MonotonicClock`, which is
+//! immune to NTP steps and manual clock changes mid-run (the wall clock is
+//! only ever consulted for calendar dates, never for elapsed time). Tests
+//! use `FakeClock` to drive whole attempts deterministically instead of
+//! sleeping.
+
+use chrono::{DateTime, Duration, Utc};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Instant;
+
+pub trait TimeSource: Send + Sync {
+    /// Time elapsed since an arbitrary fixed origin. Only differences
+    /// between two readings are meaningful, so this must never go
+    /// backwards.
+    fn now(&self) -> Duration;
+
+    /// Calendar date/time, for stamping attempt history — never used to
+    /// measure elapsed time.
+    fn wall_now(&self) -> DateTime<Utc>;
+}
+
+/// `std::time::Instant`-backed clock. Every instance shares one
+/// process-wide origin, so readings from two `MonotonicClock`s (e.g. the
+/// RTA and IGT timers) are directly comparable.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MonotonicClock;
+
+fn process_origin() -> Instant {
+    static ORIGIN: OnceLock<Instant> = OnceLock::new();
+    *ORIGIN.get_or_init(Instant::now)
+}
+
+impl TimeSource for MonotonicClock {
+    fn now(&self) -> Duration {
+        Duration::from_std(process_origin().elapsed()).unwrap_or_else(|_| Duration::zero())
+    }
+
+    fn wall_now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// The clock production code should use unless a test says otherwise.
+pub fn default_clock() -> Arc<dyn TimeSource> {
+    Arc::new(MonotonicClock)
+}
+
+/// Manually advanced clock for tests. Clones share the same underlying
+/// time, so a test can hand one clone to `AppState`/`Timer` and keep
+/// another to `advance` it.
+#[derive(Debug, Clone)]
+pub struct FakeClock {
+    inner: Arc<Mutex<FakeClockState>>,
+}
+
+#[derive(Debug)]
+struct FakeClockState {
+    now: Duration,
+    wall_origin: DateTime<Utc>,
+}
+
+impl FakeClock {
+    /// Starts at zero elapsed, with `wall_now` reading `wall_origin`.
+    pub fn new(wall_origin: DateTime<Utc>) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(FakeClockState {
+                now: Duration::zero(),
+                wall_origin,
+            })),
+        }
+    }
+
+    pub fn advance(&self, by: Duration) {
+        self.inner.lock().unwrap().now += by;
+    }
+
+    pub fn advance_ms(&self, millis: i64) {
+        self.advance(Duration::milliseconds(millis));
+    }
+}
+
+impl Default for FakeClock {
+    fn default() -> Self {
+        Self::new(DateTime::UNIX_EPOCH)
+    }
+}
+
+impl TimeSource for FakeClock {
+    fn now(&self) -> Duration {
+        self.inner.lock().unwrap().now
+    }
+
+    fn wall_now(&self) -> DateTime<Utc> {
+        let state = self.inner.lock().unwrap();
+        state.wall_origin + state.now
+    }
+}
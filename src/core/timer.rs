@@ -1,4 +1,7 @@
-use chrono::{DateTime, Duration, Utc};
+use chrono::Duration;
+use std::sync::Arc;
+
+use crate::core::time_source::{TimeSource, default_clock};
 
 #[derive(Debug, PartialEq)]
 pub enum TimerState {
@@ -10,8 +13,12 @@ pub enum TimerState {
 
 pub struct Timer {
     pub state: TimerState,
-    pub start_time: Option<DateTime<Utc>>,
+    /// Reading of `clock` when the timer was last (re)started — a
+    /// monotonic instant, not a wall-clock time, so changing the system
+    /// clock mid-run can't add or remove time from an attempt.
+    pub start_time: Option<Duration>,
     pub elapsed: Duration,
+    pub clock: Arc<dyn TimeSource>,
 }
 
 impl Default for Timer {
@@ -22,16 +29,21 @@ impl Default for Timer {
 
 impl Timer {
     pub fn new() -> Self {
+        Self::with_clock(default_clock())
+    }
+
+    pub fn with_clock(clock: Arc<dyn TimeSource>) -> Self {
         Self {
             state: TimerState::NotStarted,
             start_time: None,
             elapsed: Duration::zero(),
+            clock,
         }
     }
 
     pub fn start_with_offset(&mut self, offset_seconds: i64) {
         if self.state == TimerState::NotStarted {
-            self.start_time = Some(Utc::now() + Duration::seconds(offset_seconds));
+            self.start_time = Some(self.clock.now() + Duration::seconds(offset_seconds));
             self.elapsed = Duration::zero();
             self.state = TimerState::Running;
         } else if self.state == TimerState::Paused {
@@ -42,13 +54,13 @@ impl Timer {
     }
 
     pub fn start(&mut self) {
-        self.start_time = Some(Utc::now());
+        self.start_time = Some(self.clock.now());
         self.state = TimerState::Running;
     }
 
     pub fn pause(&mut self) {
         if let Some(start) = self.start_time {
-            self.elapsed = Utc::now() - start + self.elapsed;
+            self.elapsed = self.clock.now() - start + self.elapsed;
             self.start_time = None;
             self.state = TimerState::Paused;
         }
@@ -59,12 +71,24 @@ impl Timer {
     /// resume it — a finished run shouldn't start ticking again.
     pub fn end(&mut self) {
         if let Some(start) = self.start_time {
-            self.elapsed = Utc::now() - start + self.elapsed;
+            self.elapsed = self.clock.now() - start + self.elapsed;
             self.start_time = None;
         }
         self.state = TimerState::Ended;
     }
 
+    /// Puts the clock back at `elapsed` — e.g. resuming an attempt
+    /// recovered from `core::journal` — ticking on from there if `running`,
+    /// otherwise paused at it.
+    pub fn restore(&mut self, elapsed: Duration, running: bool) {
+        self.start_time = None;
+        self.elapsed = elapsed;
+        self.state = TimerState::Paused;
+        if running {
+            self.start();
+        }
+    }
+
     pub fn reset(&mut self) {
         self.start_time = None;
         self.elapsed = Duration::zero();
@@ -75,7 +99,7 @@ impl Timer {
         match self.state {
             TimerState::Running => {
                 if let Some(start) = self.start_time {
-                    Utc::now() - start + self.elapsed
+                    self.clock.now() - start + self.elapsed
                 } else {
                     self.elapsed
                 }
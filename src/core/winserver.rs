@@ -41,6 +41,10 @@ fn handle_event(event: Event, app: &Arc<Mutex<AppState>>) {
             app.split();
         });
 
+        check_and_run!(&hotkeys.skip_split, {
+            app.skip_split();
+        });
+
         check_and_run!(&hotkeys.start, {
             app.start_timers();
         });
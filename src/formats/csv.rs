@@ -3,9 +3,12 @@
 //! Grafana, ...). Deliberately minimal — this exposes the raw data rather
 //! than computing metrics itself, the same "expose data, let other tools do
 //! the specialized work" pattern as the control socket (for autosplitters)
-//! and the overlay WebSocket server (for OBS).
+//! and the overlay WebSocket server (for OBS). The exceptions,
+//! `split_statistics_csv` and `progression_csv`, just write out what
+//! `core::stats` already computes for the history window.
 
 use crate::core::split::Run;
+use crate::core::stats::{progression, split_statistics};
 
 /// One row per recorded attempt: when it happened, how long it took, and
 /// whether it became the new Personal Best.
@@ -46,6 +49,52 @@ pub fn segments_csv(run: &Run) -> String {
     out
 }
 
+/// One row per split: the `stats::split_statistics` survival and
+/// segment-time numbers, on the run's own timing method. Rates are
+/// fractions (`0.25`, not `25%`).
+pub fn split_statistics_csv(run: &Run) -> String {
+    let mut out = String::from(
+        "split_index,split_name,reached,resets,reset_rate,mean_ms,median_ms,std_dev_ms,gold_rate\n",
+    );
+    let rate_field = |rate: Option<f64>| rate.map(|r| format!("{r:.4}")).unwrap_or_default();
+    for (split_index, (split, stats)) in run
+        .splits
+        .iter()
+        .zip(split_statistics(run, run.timing_method))
+        .enumerate()
+    {
+        out.push_str(&format!(
+            "{split_index},{},{},{},{},{},{},{},{}\n",
+            csv_escape(&split.name),
+            stats.reached,
+            stats.resets,
+            rate_field(stats.reset_rate),
+            ms_field(stats.mean),
+            ms_field(stats.median),
+            ms_field(stats.std_dev),
+            rate_field(stats.gold_rate),
+        ));
+    }
+    out
+}
+
+/// One row per attempt, in order: the Sum of Best and Personal Best (on
+/// the run's timing method) right after it.
+pub fn progression_csv(run: &Run) -> String {
+    let mut out = String::from("run_index,date,attempts,sum_of_best_ms,personal_best_ms\n");
+    for point in progression(run, run.timing_method) {
+        let date = point.date.map(|d| d.to_rfc3339()).unwrap_or_default();
+        out.push_str(&format!(
+            "{},{date},{},{},{}\n",
+            point.run_index,
+            point.attempts,
+            ms_field(point.sum_of_best),
+            ms_field(point.personal_best),
+        ));
+    }
+    out
+}
+
 fn ms_field(duration: Option<chrono::Duration>) -> String {
     duration
         .map(|d| d.num_milliseconds().to_string())
@@ -21,9 +21,16 @@
 //! reimport into this app. Import extracts the embedded PNG with a
 //! signature scan, which works for both the wrapped form and a plain
 //! unwrapped PNG (a form no real tool writes, but harmless to also accept).
-//! `AutoSplitterSettings` is ignored both ways. `AttemptCount`/
-//! `AttemptHistory` fidelity is limited by the fact that `Run::attempts`
-//! only counts completed runs, not resets.
+//! `AutoSplitterSettings` is ignored both ways.
+//!
+//! `AttemptCount` and `Run::attempts` both count every started attempt,
+//! resets included. LiveSplit only stores a final time for finished
+//! attempts, so a reset attempt's `RealTime`/`GameTime` are written with
+//! an `isReset="True"` attribute to tell it apart. Files from LiveSplit
+//! itself have neither, so there a reset attempt's elapsed time comes from
+//! the gap between its `started` and `ended` stamps (whole seconds, real
+//! time only). The split it was reset on is recovered from
+//! `SegmentHistory` on import.
 
 use chrono::{DateTime, Duration, Utc};
 use quick_xml::events::Event;
@@ -257,9 +264,13 @@ pub fn import(path: &Path, icons_dir: &Path) -> Result<ImportResult, String> {
     if segment_names.is_empty() {
         return Err("The .lss file has no segments".to_string());
     }
+    let (segment_names, sections) = parse_subsplit_names(&segment_names);
     let segment_name_refs: Vec<&str> = segment_names.iter().map(String::as_str).collect();
 
     let mut run = Run::new(&game_name, &category_name, &segment_name_refs);
+    for (split, section) in run.splits.iter_mut().zip(sections) {
+        split.section = section;
+    }
 
     if let Some(offset) = root.child_time("Offset") {
         let secs = -offset.num_seconds();
@@ -313,13 +324,22 @@ pub fn import(path: &Path, icons_dir: &Path) -> Result<ImportResult, String> {
                 continue;
             };
 
-            let real_time = attempt.child_time("RealTime");
+            let mut real_time = attempt.child_time("RealTime");
             let game_time = attempt.child_time("GameTime");
-            let ended = real_time.is_some() || game_time.is_some();
+            let ended = (real_time.is_some() || game_time.is_some())
+                && attempt.attr("isReset") != Some("True");
+            let started = attempt.attr("started").and_then(parse_dotnet_datetime);
             let date = attempt
                 .attr("ended")
-                .or_else(|| attempt.attr("started"))
-                .and_then(parse_dotnet_datetime);
+                .and_then(parse_dotnet_datetime)
+                .or(started);
+
+            if real_time.is_none()
+                && game_time.is_none()
+                && let (Some(started), Some(date)) = (started, date)
+            {
+                real_time = Some(date - started);
+            }
 
             run.attempt_history.push(AttemptHistoryEntry {
                 run_index,
@@ -327,6 +347,9 @@ pub fn import(path: &Path, icons_dir: &Path) -> Result<ImportResult, String> {
                 game_time,
                 ended,
                 date,
+                // Filled in from `SegmentHistory` once segments are read.
+                reset_split_index: None,
+                ..Default::default()
             });
         }
     }
@@ -392,27 +415,131 @@ pub fn import(path: &Path, icons_dir: &Path) -> Result<ImportResult, String> {
                     else {
                         continue;
                     };
-                    let real_time = time_node.child_time("RealTime");
-                    let game_time = time_node.child_time("GameTime");
-                    if real_time.is_none() && game_time.is_none() {
-                        continue; // self-closing <Time id="N" />: no data for that attempt
-                    }
+                    // A self-closing `<Time id="N" />` is how LiveSplit
+                    // records a skipped split — kept as an empty entry so
+                    // the next split's segment is recognized as combined
+                    // below.
                     split.segment_history.push(SegmentHistoryEntry {
                         run_index,
-                        real_time,
-                        game_time,
+                        real_time: time_node.child_time("RealTime"),
+                        game_time: time_node.child_time("GameTime"),
+                        combined: false,
                     });
                 }
             }
         }
     }
 
+    mark_combined_segments(&mut run);
+    fill_reset_split_indices(&mut run);
+
     Ok(ImportResult {
         run,
         source_version,
     })
 }
 
+/// Splits LiveSplit's subsplit naming back into names and sections: a
+/// `-` prefix marks a subsplit, and the split closing the group names the
+/// section — either `{Section} Name`, or just its own name. A trailing
+/// group that's never closed takes its last subsplit's name.
+fn parse_subsplit_names(names: &[String]) -> (Vec<String>, Vec<Option<String>>) {
+    let mut plain = Vec::with_capacity(names.len());
+    let mut sections = vec![None; names.len()];
+    let mut group_start = None;
+
+    for (i, name) in names.iter().enumerate() {
+        if let Some(rest) = name.strip_prefix('-') {
+            plain.push(rest.trim().to_string());
+            group_start.get_or_insert(i);
+            continue;
+        }
+
+        let braced = name
+            .strip_prefix('{')
+            .and_then(|rest| rest.split_once('}'))
+            .map(|(section, rest)| (section.trim().to_string(), rest.trim().to_string()));
+        let section = match braced {
+            Some((section, rest)) => {
+                plain.push(rest);
+                Some(section)
+            }
+            None => {
+                plain.push(name.clone());
+                group_start.map(|_| name.clone())
+            }
+        };
+        for slot in &mut sections[group_start.take().unwrap_or(i)..=i] {
+            slot.clone_from(&section);
+        }
+    }
+
+    if let Some(start) = group_start {
+        let section = plain.last().cloned();
+        for slot in &mut sections[start..] {
+            slot.clone_from(&section);
+        }
+    }
+    (plain, sections)
+}
+
+/// Inverse of `parse_subsplit_names`: every split of a section but the
+/// last gets a `-` prefix, and the last is written as `{Section} Name`
+/// unless its own name already says it.
+fn subsplit_names(run: &Run) -> Vec<String> {
+    let mut names: Vec<String> = run.splits.iter().map(|s| s.name.clone()).collect();
+    for section in run.sections() {
+        let Some(section_name) = &section.name else {
+            continue;
+        };
+        let last = section.last();
+        for name in &mut names[section.range.start..last] {
+            name.insert(0, '-');
+        }
+        if section.range.len() == 1 || names[last] != *section_name {
+            names[last] = format!("{{{section_name}}} {}", names[last]);
+        }
+    }
+    names
+}
+
+/// LiveSplit has no explicit "combined" marker: a segment is combined when
+/// the same attempt has an empty entry (a skip) on the split right before
+/// it. Recovers `SegmentHistoryEntry::combined` from that, chaining across
+/// several consecutive skips.
+fn mark_combined_segments(run: &mut Run) {
+    for i in 1..run.splits.len() {
+        let (before, after) = run.splits.split_at_mut(i);
+        let previous = &before[i - 1];
+        for entry in &mut after[0].segment_history {
+            let has_time = entry.real_time.is_some() || entry.game_time.is_some();
+            entry.combined = has_time
+                && previous.segment_history.iter().any(|e| {
+                    e.run_index == entry.run_index && e.real_time.is_none() && e.game_time.is_none()
+                });
+        }
+    }
+}
+
+/// A reset attempt was reset on the split after the last one it has a
+/// time for, or on the final split if it has a time for that too.
+fn fill_reset_split_indices(run: &mut Run) {
+    let last = run.splits.len().saturating_sub(1);
+    for attempt in run.attempt_history.iter_mut().filter(|a| !a.ended) {
+        let reached = run
+            .splits
+            .iter()
+            .rposition(|s| {
+                s.segment_history.iter().any(|e| {
+                    e.run_index == attempt.run_index
+                        && (e.real_time.is_some() || e.game_time.is_some())
+                })
+            })
+            .map_or(0, |i| i + 1);
+        attempt.reset_split_index = Some(reached.min(last));
+    }
+}
+
 /// LiveSplit embeds icons as a `.NET BinaryFormatter`-serialized
 /// `System.Drawing.Bitmap`, not a plain base64 PNG. Fully implementing that
 /// binary format is out of scope for icon support, so instead this scans
@@ -634,14 +761,31 @@ fn build_xml(run: &Run, icons_base_dir: &Path) -> String {
 
     out.push_str("  <AttemptHistory>\n");
     for attempt in &run.attempt_history {
+        let format_date = |d: DateTime<Utc>| d.format("%m/%d/%Y %H:%M:%S").to_string();
         let date = attempt
             .date
-            .map(|d| d.format("%m/%d/%Y %H:%M:%S").to_string())
+            .map(format_date)
             .unwrap_or_else(|| "01/01/2000 00:00:00".to_string());
-
-        if attempt.ended && (attempt.real_time.is_some() || attempt.game_time.is_some()) {
+        // `date` is when the attempt ended (finished or reset), so it
+        // started `real_time` earlier — for a reset attempt, that gap is
+        // the only place LiveSplit itself looks for its elapsed time.
+        // Rounded to the nearest second, since that's all the stamps can
+        // hold.
+        let started = match (attempt.date, attempt.real_time) {
+            (Some(d), Some(rt)) => {
+                format_date(d - Duration::seconds((rt.num_milliseconds() + 500) / 1000))
+            }
+            _ => date.clone(),
+        };
+
+        if attempt.real_time.is_some() || attempt.game_time.is_some() {
+            let reset = if attempt.ended {
+                ""
+            } else {
+                " isReset=\"True\""
+            };
             out.push_str(&format!(
-                "    <Attempt id=\"{}\" started=\"{date}\" isStartedSynced=\"True\" ended=\"{date}\" isEndedSynced=\"True\">\n",
+                "    <Attempt id=\"{}\" started=\"{started}\" isStartedSynced=\"True\" ended=\"{date}\" isEndedSynced=\"True\"{reset}>\n",
                 attempt.run_index
             ));
             if let Some(rt) = attempt.real_time {
@@ -659,7 +803,7 @@ fn build_xml(run: &Run, icons_base_dir: &Path) -> String {
             out.push_str("    </Attempt>\n");
         } else {
             out.push_str(&format!(
-                "    <Attempt id=\"{}\" started=\"{date}\" isStartedSynced=\"True\" ended=\"{date}\" isEndedSynced=\"True\" />\n",
+                "    <Attempt id=\"{}\" started=\"{started}\" isStartedSynced=\"True\" ended=\"{date}\" isEndedSynced=\"True\" />\n",
                 attempt.run_index
             ));
         }
@@ -669,9 +813,13 @@ fn build_xml(run: &Run, icons_base_dir: &Path) -> String {
     out.push_str("  <Segments>\n");
     let mut cumulative: BTreeMap<String, (Duration, Duration)> = BTreeMap::new();
 
-    for split in &run.splits {
+    let subsplit_names = subsplit_names(run);
+    for (index, split) in run.splits.iter().enumerate() {
         out.push_str("    <Segment>\n");
-        out.push_str(&format!("      <Name>{}</Name>\n", xml_escape(&split.name)));
+        out.push_str(&format!(
+            "      <Name>{}</Name>\n",
+            xml_escape(&subsplit_names[index])
+        ));
 
         let icon_bytes = split
             .icon_path
@@ -748,6 +896,8 @@ fn build_xml(run: &Run, icons_base_dir: &Path) -> String {
         out.push_str("      <SegmentHistory>\n");
         for entry in &split.segment_history {
             if entry.real_time.is_none() && entry.game_time.is_none() {
+                // A skipped split, written the way LiveSplit does.
+                out.push_str(&format!("        <Time id=\"{}\" />\n", entry.run_index));
                 continue;
             }
             out.push_str(&format!("        <Time id=\"{}\">\n", entry.run_index));
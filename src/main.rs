@@ -44,6 +44,9 @@ fn main() -> eframe::Result<()> {
         });
     }
 
+    #[cfg(target_os = "linux")]
+    crate::core::evdev::listen_for_hotkeys(app_state.clone());
+
     let layout = app_state.lock().unwrap().layout.clone();
     let titlebar = layout.options.titlebar;
     let window_size = layout.options.window_size;
@@ -409,6 +409,7 @@ fn try_fetch_from_user(username: &str, original_run_prefix: &str) -> Result<Vec<
                         run_index: i as u32,
                         real_time: Some(real_time),
                         game_time: None,
+                        combined: false,
                     })
                 })
                 .collect();
@@ -0,0 +1,133 @@
+mod common;
+
+use chrono::{DateTime, NaiveDate};
+use openspeedrun::Run;
+use openspeedrun::core::attempts::{AttemptFilter, AttemptSplit, Completion};
+use openspeedrun::core::split::TimingMethod;
+
+use common::secs;
+
+/// `common::run_with_history`, with the even attempts tagged "even".
+fn run_with_even_tags() -> Run {
+    let mut run = common::run_with_history();
+    for attempt in &mut run.attempt_history {
+        if attempt.run_index.is_multiple_of(2) {
+            attempt.tags = vec!["even".to_string()];
+        }
+    }
+    run
+}
+
+#[test]
+fn first_attempt_has_nothing_to_compare_against() {
+    let run = run_with_even_tags();
+    let splits = run.attempt_splits(1, TimingMethod::RealTime);
+    assert_eq!(
+        splits[2],
+        AttemptSplit {
+            segment: Some(secs(30)),
+            time: Some(secs(60)),
+            combined: false,
+            gold_then: None,
+            pb_then: None,
+        }
+    );
+    assert_eq!(splits[2].pb_delta(), None);
+}
+
+#[test]
+fn deltas_are_against_the_golds_and_pb_of_the_time() {
+    let run = run_with_even_tags();
+    let splits = run.attempt_splits(3, TimingMethod::RealTime);
+
+    // PB then was attempt 2 (12/27/52); golds 10/15/25.
+    let times: Vec<_> = splits.iter().map(|s| s.time).collect();
+    assert_eq!(times, [Some(secs(8)), Some(secs(26)), None]);
+    let pb: Vec<_> = splits.iter().map(|s| s.pb_delta()).collect();
+    assert_eq!(pb, [Some(secs(-4)), Some(secs(-1)), None]);
+    let gold: Vec<_> = splits.iter().map(|s| s.gold_delta()).collect();
+    assert_eq!(gold, [Some(secs(-2)), Some(secs(3)), None]);
+}
+
+#[test]
+fn a_skipped_split_leaves_a_gap_and_a_combined_segment() {
+    let run = run_with_even_tags();
+    let splits = run.attempt_splits(4, TimingMethod::RealTime);
+    assert_eq!(splits[1].segment, None);
+    assert_eq!(splits[1].time, None);
+    assert!(splits[2].combined);
+    assert_eq!(splits[2].time, Some(secs(51)));
+    assert_eq!(splits[2].pb_delta(), Some(secs(-1)));
+    // Golds then include attempt 3's 8 on A.
+    assert_eq!(splits[0].gold_then, Some(secs(8)));
+}
+
+#[test]
+fn an_attempt_becomes_a_comparison() {
+    let run = run_with_even_tags();
+    let segments: Vec<_> = run
+        .attempt_comparison(3)
+        .iter()
+        .map(|c| c.get(TimingMethod::RealTime))
+        .collect();
+    assert_eq!(segments, [Some(secs(8)), Some(secs(18)), None]);
+    assert!(
+        run.attempt_comparison(99)
+            .iter()
+            .all(|c| c.real_time.is_none())
+    );
+}
+
+#[test]
+fn filters_narrow_the_attempt_list() {
+    let run = run_with_even_tags();
+    let shown = |filter: &AttemptFilter| -> Vec<u32> {
+        run.attempt_history
+            .iter()
+            .filter(|a| filter.matches(a, TimingMethod::RealTime))
+            .map(|a| a.run_index)
+            .collect()
+    };
+
+    assert_eq!(shown(&AttemptFilter::default()), [1, 2, 3, 4]);
+    let reset = AttemptFilter {
+        completion: Completion::Reset,
+        ..Default::default()
+    };
+    assert_eq!(shown(&reset), [3]);
+
+    let day = |run_index: i64| {
+        DateTime::from_timestamp(1_700_000_000 + 86_400 * run_index, 0)
+            .unwrap()
+            .date_naive()
+    };
+    let dates = AttemptFilter {
+        from: Some(day(2)),
+        to: Some(day(3)),
+        ..Default::default()
+    };
+    assert_eq!(shown(&dates), [2, 3]);
+
+    let times = AttemptFilter {
+        min_time: Some(secs(51)),
+        max_time: Some(secs(52)),
+        ..Default::default()
+    };
+    assert_eq!(shown(&times), [2, 4]);
+
+    let tagged = AttemptFilter {
+        tag: Some("even".to_string()),
+        completion: Completion::Finished,
+        ..Default::default()
+    };
+    assert_eq!(shown(&tagged), [2, 4]);
+
+    // No date: excluded by any date bound.
+    let mut undated = run.attempt_history[0].clone();
+    undated.date = None;
+    let since = AttemptFilter {
+        from: NaiveDate::from_ymd_opt(2000, 1, 1),
+        ..Default::default()
+    };
+    assert!(!since.matches(&undated, TimingMethod::RealTime));
+}
@@ -1,85 +1,102 @@
+use std::collections::HashMap;
+
 use openspeedrun::autosplitter::config::{
-    Action, AutosplitterConfig, Condition, Endian, ValueType, Watch,
+    Action, AutosplitterConfig, Compound, Condition, Endian, SplitGuard, StringEncoding, ValueType,
+    Watch,
 };
+use openspeedrun::autosplitter::engine::{Engine, Fired};
+use openspeedrun::autosplitter::expr::Value;
 #[cfg(target_os = "linux")]
 use openspeedrun::autosplitter::process_memory::ProcessMemoryReader;
-use openspeedrun::autosplitter::process_memory::{find_module_base, resolve_pointer_chain};
+use openspeedrun::autosplitter::process_memory::{
+    find_module_base, find_module_size, resolve_pointer_chain,
+};
 use openspeedrun::autosplitter::retroarch::parse_read_memory_response;
+use openspeedrun::core::protocol::COMMANDS;
 
 #[test]
 fn condition_never_fires_on_the_first_sample() {
     // No `previous` yet, so there's nothing to transition from.
-    assert!(!Condition::Equals { value: 5 }.triggered(None, 5));
-    assert!(!Condition::Changed.triggered(None, 5));
-    assert!(!Condition::Increased.triggered(None, 5));
+    assert!(!Condition::Equals { value: 5 }.triggered(None, &Value::Int(5)));
+    assert!(!Condition::Changed.triggered(None, &Value::Int(5)));
+    assert!(!Condition::Increased.triggered(None, &Value::Int(5)));
 }
 
 #[test]
 fn equals_fires_only_on_the_transition_into_the_value() {
     let cond = Condition::Equals { value: 2 };
-    assert!(cond.triggered(Some(1), 2));
+    assert!(cond.triggered(Some(&Value::Int(1)), &Value::Int(2)));
     // Already at 2 on the previous sample: must not re-fire every tick.
-    assert!(!cond.triggered(Some(2), 2));
-    assert!(!cond.triggered(Some(1), 3));
+    assert!(!cond.triggered(Some(&Value::Int(2)), &Value::Int(2)));
+    assert!(!cond.triggered(Some(&Value::Int(1)), &Value::Int(3)));
 }
 
 #[test]
 fn not_equals_fires_only_on_leaving_the_value() {
     let cond = Condition::NotEquals { value: 2 };
-    assert!(cond.triggered(Some(2), 3));
-    assert!(!cond.triggered(Some(3), 4));
-    assert!(!cond.triggered(Some(2), 2));
+    assert!(cond.triggered(Some(&Value::Int(2)), &Value::Int(3)));
+    assert!(!cond.triggered(Some(&Value::Int(3)), &Value::Int(4)));
+    assert!(!cond.triggered(Some(&Value::Int(2)), &Value::Int(2)));
 }
 
 #[test]
 fn greater_than_fires_only_on_crossing_the_threshold_upward() {
     let cond = Condition::GreaterThan { value: 10 };
-    assert!(cond.triggered(Some(10), 11));
+    assert!(cond.triggered(Some(&Value::Int(10)), &Value::Int(11)));
     // Still above threshold next tick: no re-fire.
-    assert!(!cond.triggered(Some(11), 12));
-    assert!(!cond.triggered(Some(5), 5));
+    assert!(!cond.triggered(Some(&Value::Int(11)), &Value::Int(12)));
+    assert!(!cond.triggered(Some(&Value::Int(5)), &Value::Int(5)));
 }
 
 #[test]
 fn less_than_fires_only_on_crossing_the_threshold_downward() {
     let cond = Condition::LessThan { value: 10 };
-    assert!(cond.triggered(Some(10), 9));
-    assert!(!cond.triggered(Some(9), 8));
+    assert!(cond.triggered(Some(&Value::Int(10)), &Value::Int(9)));
+    assert!(!cond.triggered(Some(&Value::Int(9)), &Value::Int(8)));
 }
 
 #[test]
 fn increased_decreased_and_changed() {
-    assert!(Condition::Increased.triggered(Some(1), 2));
-    assert!(!Condition::Increased.triggered(Some(2), 2));
-    assert!(Condition::Decreased.triggered(Some(2), 1));
-    assert!(!Condition::Decreased.triggered(Some(1), 1));
-    assert!(Condition::Changed.triggered(Some(1), 2));
-    assert!(!Condition::Changed.triggered(Some(2), 2));
+    assert!(Condition::Increased.triggered(Some(&Value::Int(1)), &Value::Int(2)));
+    assert!(!Condition::Increased.triggered(Some(&Value::Int(2)), &Value::Int(2)));
+    assert!(Condition::Decreased.triggered(Some(&Value::Int(2)), &Value::Int(1)));
+    assert!(!Condition::Decreased.triggered(Some(&Value::Int(1)), &Value::Int(1)));
+    assert!(Condition::Changed.triggered(Some(&Value::Int(1)), &Value::Int(2)));
+    assert!(!Condition::Changed.triggered(Some(&Value::Int(2)), &Value::Int(2)));
 }
 
 #[test]
 fn value_type_decodes_signed_and_unsigned_widths_in_both_endians() {
-    assert_eq!(ValueType::U8.decode(&[0xFF], Endian::Little), Some(255));
-    assert_eq!(ValueType::I8.decode(&[0xFF], Endian::Little), Some(-1));
+    assert_eq!(
+        ValueType::U8.decode(&[0xFF], Endian::Little),
+        Some(Value::Int(255))
+    );
+    assert_eq!(
+        ValueType::I8.decode(&[0xFF], Endian::Little),
+        Some(Value::Int(-1))
+    );
 
     assert_eq!(
         ValueType::U16.decode(&[0x01, 0x00], Endian::Little),
-        Some(1)
+        Some(Value::Int(1))
+    );
+    assert_eq!(
+        ValueType::U16.decode(&[0x00, 0x01], Endian::Big),
+        Some(Value::Int(1))
     );
-    assert_eq!(ValueType::U16.decode(&[0x00, 0x01], Endian::Big), Some(1));
 
     assert_eq!(
         ValueType::I32.decode(&[0xFF, 0xFF, 0xFF, 0xFF], Endian::Little),
-        Some(-1)
+        Some(Value::Int(-1))
     );
     assert_eq!(
         ValueType::U32.decode(&[0xFF, 0xFF, 0xFF, 0xFF], Endian::Little),
-        Some(u32::MAX as i128)
+        Some(Value::Int(u32::MAX as i128))
     );
 
     assert_eq!(
         ValueType::U64.decode(&[1, 0, 0, 0, 0, 0, 0, 0], Endian::Little),
-        Some(1)
+        Some(Value::Int(1))
     );
 }
 
@@ -97,8 +114,10 @@ fn watch_address_parses_with_and_without_0x_prefix() {
         pointer_path: Vec::new(),
         value_type: ValueType::U8,
         endian: Endian::Little,
-        condition: Condition::Changed,
-        action: Action::Split,
+        condition: Some(Condition::Changed),
+        action: Some(Action::Split),
+        ms_per_unit: 1.0,
+        guard: SplitGuard::default(),
     };
 
     assert_eq!(mk("0x1A").address(), Ok(0x1A));
@@ -112,6 +131,49 @@ fn action_maps_to_the_same_command_vocabulary_the_control_socket_expects() {
     assert_eq!(Action::Split.as_command(), "split");
     assert_eq!(Action::Reset.as_command(), "reset");
     assert_eq!(Action::Pause.as_command(), "pause");
+    assert_eq!(Action::InitGameTime.as_command(), "initgametime");
+    assert_eq!(Action::PauseGameTime.as_command(), "pausegametime");
+    assert_eq!(Action::ResumeGameTime.as_command(), "resumegametime");
+    for action in [
+        Action::Start,
+        Action::InitGameTime,
+        Action::SetGameTime,
+        Action::ResumeGameTime,
+    ] {
+        assert!(COMMANDS.contains(&action.as_command()));
+    }
+}
+
+#[test]
+fn set_game_time_watches_send_the_value_scaled_to_milliseconds() {
+    let watch: Watch = serde_json::from_str(
+        r#"{
+            "name": "igt_frames",
+            "address": "0x10",
+            "value_type": "u32",
+            "condition": { "kind": "changed" },
+            "action": "set_game_time",
+            "ms_per_unit": 16.6667
+        }"#,
+    )
+    .unwrap();
+    assert_eq!(
+        watch.command(&Value::Int(60)).as_deref(),
+        Some("setgametime 1000")
+    );
+
+    let split: Watch = serde_json::from_str(
+        r#"{
+            "name": "room",
+            "address": "0x10",
+            "value_type": "u8",
+            "condition": { "kind": "changed" },
+            "action": "split"
+        }"#,
+    )
+    .unwrap();
+    assert_eq!(split.ms_per_unit, 1.0);
+    assert_eq!(split.command(&Value::Int(7)).as_deref(), Some("split"));
 }
 
 #[test]
@@ -233,6 +295,21 @@ fn find_module_base_matches_the_exact_file_name_not_a_substring() {
     assert_eq!(find_module_base(maps, "nonexistent.so"), None);
 }
 
+#[test]
+fn find_module_size_spans_all_of_a_modules_mappings() {
+    let maps = "\
+55a1b2c00000-55a1b2c04000 r--p 00000000 08:01 123456 /usr/bin/game.bin
+55a1b2c04000-55a1b2c09000 r-xp 00004000 08:01 123456 /usr/bin/game.bin
+55a1b2c09000-55a1b2c0a000 rw-p 00009000 08:01 123456 /usr/bin/game.bin
+55a1b2e00000-55a1b2e10000 rw-p 00000000 00:00 0
+7f9a00100000-7f9a00110000 r--p 00000000 08:01 654322 /usr/lib/libGL.so.1
+";
+
+    assert_eq!(find_module_size(maps, "game.bin"), Some(0xA000));
+    assert_eq!(find_module_size(maps, "libGL.so.1"), Some(0x10000));
+    assert_eq!(find_module_size(maps, "libGL.so"), None);
+}
+
 #[test]
 fn resolve_pointer_chain_with_no_offsets_returns_the_base_untouched() {
     let read = |_addr: u64| -> Option<u64> { panic!("shouldn't read anything") };
@@ -261,3 +338,515 @@ fn resolve_pointer_chain_fails_if_a_dereference_misses() {
     let read = |_addr: u64| -> Option<u64> { None };
     assert_eq!(resolve_pointer_chain(read, 0x1000, &[0x18]), None);
 }
+
+#[test]
+fn condition_values_parse_as_numbers_or_hex_strings() {
+    let equals: Condition = serde_json::from_str(r#"{"kind": "equals", "value": 18}"#).unwrap();
+    assert!(equals.holds(None, &Value::Int(18)));
+    let hex: Condition = serde_json::from_str(r#"{"kind": "less_than", "value": "0x12"}"#).unwrap();
+    assert!(hex.holds(None, &Value::Int(17)));
+    assert!(!hex.holds(None, &Value::Int(18)));
+    assert!(serde_json::from_str::<Condition>(r#"{"kind": "equals", "value": "12"}"#).is_err());
+}
+
+/// Watches `room` and `boss_hp` (inputs only) and one trigger splitting on
+/// "room == 0x12 and boss_hp went to 0", guarded by `guard`.
+fn boss_config(guard: &str) -> AutosplitterConfig {
+    AutosplitterConfig::from_json(&format!(
+        r#"{{
+            "target": {{ "kind": "retroarch" }},
+            "watches": [
+                {{ "name": "room", "address": "0x10", "value_type": "u8" }},
+                {{ "name": "boss_hp", "address": "0x11", "value_type": "u8" }}
+            ],
+            "triggers": [
+                {{
+                    "name": "boss",
+                    "when": {{ "all": [
+                        {{ "watch": "room", "kind": "equals", "value": "0x12" }},
+                        {{ "watch": "boss_hp", "kind": "equals", "value": 0 }}
+                    ] }},
+                    "action": "split"{guard}
+                }}
+            ]
+        }}"#
+    ))
+    .unwrap()
+}
+
+fn sample(room: i128, boss_hp: i128) -> HashMap<String, Value> {
+    HashMap::from([
+        ("room".to_string(), Value::Int(room)),
+        ("boss_hp".to_string(), Value::Int(boss_hp)),
+    ])
+}
+
+#[test]
+fn a_compound_trigger_fires_once_when_all_of_it_becomes_true() {
+    let mut engine = Engine::new(boss_config(""));
+    let never = || -> Option<usize> { panic!("no guard, no need to ask") };
+
+    // Already true on the first sample: nothing to transition from.
+    assert!(engine.step(&sample(0x12, 0), never).is_empty());
+    assert!(engine.step(&sample(0x12, 5), never).is_empty());
+    // A dead boss in another room doesn't count.
+    assert!(engine.step(&sample(0x13, 0), never).is_empty());
+    let fired = engine.step(&sample(0x12, 0), never);
+    assert_eq!(
+        fired,
+        [Fired {
+            source: "trigger 'boss'".to_string(),
+            command: "split".to_string(),
+        }]
+    );
+    assert!(engine.step(&sample(0x12, 0), never).is_empty());
+
+    // An unreadable watch leaves the trigger where it was.
+    engine.step(&sample(0x12, 5), never);
+    engine.step(&room(0x12), never);
+    assert_eq!(engine.step(&sample(0x12, 0), never).len(), 1);
+}
+
+#[test]
+fn guards_only_let_actions_through_on_their_splits() {
+    let mut engine = Engine::new(boss_config(r#", "only_on_split": 5"#));
+    engine.step(&sample(0x12, 5), || None);
+    let mut asked = 0;
+    assert!(
+        engine
+            .step(&sample(0x12, 0), || {
+                asked += 1;
+                Some(4)
+            })
+            .is_empty()
+    );
+    assert_eq!(asked, 1);
+
+    engine.step(&sample(0x12, 5), || Some(5));
+    assert_eq!(engine.step(&sample(0x12, 0), || Some(5)).len(), 1);
+
+    let guard = SplitGuard {
+        only_on_split: None,
+        split_range: Some((2, 4)),
+    };
+    assert!(!guard.allows(None));
+    assert!(!guard.allows(Some(1)));
+    assert!(guard.allows(Some(2)));
+    assert!(guard.allows(Some(4)));
+    assert!(!guard.allows(Some(5)));
+    assert!(SplitGuard::default().allows(None));
+}
+
+#[test]
+fn compound_conditions_combine_with_any_and_not() {
+    let when: Compound = serde_json::from_str(
+        r#"{ "any": [
+            { "watch": "room", "kind": "equals", "value": 1 },
+            { "not": { "watch": "boss_hp", "kind": "greater_than", "value": 0 } }
+        ] }"#,
+    )
+    .unwrap();
+    let none = HashMap::new();
+    assert_eq!(when.holds(&none, &sample(1, 9)), Some(true));
+    assert_eq!(when.holds(&none, &sample(2, 0)), Some(true));
+    assert_eq!(when.holds(&none, &sample(2, 9)), Some(false));
+    let room_only = room(1);
+    assert_eq!(when.holds(&none, &room_only), Some(true));
+    let hp_only = HashMap::from([("boss_hp".to_string(), Value::Int(9))]);
+    assert_eq!(when.holds(&none, &hp_only), None);
+}
+
+#[test]
+fn invalid_triggers_and_guards_are_rejected_at_load() {
+    let config = |watches: &str, triggers: &str| {
+        AutosplitterConfig::from_json(&format!(
+            r#"{{ "target": {{ "kind": "retroarch" }},
+                  "watches": [{watches}], "triggers": [{triggers}] }}"#
+        ))
+    };
+    let room = r#"{ "name": "room", "address": "0x10", "value_type": "u8" }"#;
+
+    assert_eq!(
+        config(
+            room,
+            r#"{ "name": "t", "action": "split",
+                 "when": { "watch": "rom", "kind": "changed" } }"#
+        )
+        .unwrap_err(),
+        "trigger 't' uses watch 'rom', which doesn't exist"
+    );
+    assert_eq!(
+        config(
+            room,
+            r#"{ "name": "t", "action": "split", "split_range": [4, 2],
+                 "when": { "watch": "room", "kind": "changed" } }"#
+        )
+        .unwrap_err(),
+        "trigger 't': split_range [4, 2] is backwards"
+    );
+    assert_eq!(
+        config(
+            r#"{ "name": "room", "address": "0x10", "value_type": "u8",
+                 "condition": { "kind": "changed" } }"#,
+            ""
+        )
+        .unwrap_err(),
+        "watch 'room' has a condition but no action"
+    );
+}
+
+/// `room` drives the whole run: start on entering room 1, split on rooms 2
+/// then 3 (split 1 is done by hand in between), reset on room 0.
+fn sequence_config() -> AutosplitterConfig {
+    AutosplitterConfig::from_json(
+        r#"{
+            "target": { "kind": "retroarch" },
+            "watches": [{ "name": "room", "address": "0x10", "value_type": "u8" }],
+            "start": { "watch": "room", "kind": "equals", "value": 1 },
+            "reset": { "watch": "room", "kind": "equals", "value": 0 },
+            "splits": [
+                { "name": "Two", "when": { "watch": "room", "kind": "equals", "value": 2 } },
+                { "name": "By hand" },
+                { "name": "Three", "when": { "watch": "room", "kind": "equals", "value": 3 } }
+            ]
+        }"#,
+    )
+    .unwrap()
+}
+
+fn room(value: i128) -> HashMap<String, Value> {
+    HashMap::from([("room".to_string(), Value::Int(value))])
+}
+
+fn commands_of(fired: Vec<Fired>) -> Vec<String> {
+    fired.into_iter().map(|f| f.command).collect()
+}
+
+#[test]
+fn the_split_sequence_follows_the_timer() {
+    let mut engine = Engine::new(sequence_config());
+
+    assert!(engine.step(&room(5), || None).is_empty());
+    assert_eq!(commands_of(engine.step(&room(1), || None)), ["start"]);
+    assert_eq!(engine.pointer(), Some(0));
+
+    // Room 3 isn't split 0's condition.
+    assert!(engine.step(&room(3), || Some(0)).is_empty());
+    let fired = engine.step(&room(2), || Some(0));
+    assert_eq!(fired[0].source, "split 0 ('Two')");
+    assert_eq!(commands_of(fired), ["split"]);
+    assert_eq!(engine.pointer(), Some(1));
+
+    // Split 1 has no condition: the runner splits by hand.
+    assert!(engine.step(&room(3), || Some(1)).is_empty());
+    assert!(engine.step(&room(2), || Some(1)).is_empty());
+    // The timer moved on; room 3 has to be entered again now that it counts.
+    assert!(engine.step(&room(3), || Some(2)).is_empty());
+    engine.step(&room(2), || Some(2));
+    assert_eq!(commands_of(engine.step(&room(3), || Some(2))), ["split"]);
+
+    // Finished (index past the last split): only reset is left.
+    engine.step(&room(2), || Some(3));
+    assert!(engine.step(&room(3), || Some(3)).is_empty());
+    assert_eq!(commands_of(engine.step(&room(0), || Some(3))), ["reset"]);
+    assert_eq!(engine.pointer(), None);
+}
+
+#[test]
+fn an_undo_moves_the_sequence_back() {
+    let mut engine = Engine::new(sequence_config());
+    engine.step(&room(1), || Some(0));
+    assert_eq!(commands_of(engine.step(&room(2), || Some(0))), ["split"]);
+    // The timer undid that split.
+    assert!(engine.step(&room(2), || Some(0)).is_empty());
+    assert_eq!(engine.pointer(), Some(0));
+    engine.step(&room(5), || Some(0));
+    assert_eq!(commands_of(engine.step(&room(2), || Some(0))), ["split"]);
+}
+
+#[test]
+fn a_split_sequence_survives_a_save() {
+    let config = sequence_config();
+    let json = serde_json::to_string_pretty(&config).unwrap();
+    let reloaded = AutosplitterConfig::from_json(&json).unwrap();
+    assert_eq!(reloaded.splits.len(), 3);
+    assert_eq!(reloaded.splits[1].name, "By hand");
+    assert!(reloaded.splits[1].when.is_none());
+    assert_eq!(
+        serde_json::to_string(&reloaded.splits[2].when).unwrap(),
+        r#"{"watch":"room","kind":"equals","value":3}"#
+    );
+
+    let broken = json.replace(r#""watch": "room""#, r#""watch": "rooom""#);
+    assert_eq!(
+        AutosplitterConfig::from_json(&broken).unwrap_err(),
+        "start uses watch 'rooom', which doesn't exist"
+    );
+}
+
+#[test]
+fn value_type_decodes_floats_strings_and_bytes() {
+    let f32_bytes = 1.5f32.to_le_bytes();
+    assert_eq!(
+        ValueType::F32.decode(&f32_bytes, Endian::Little),
+        Some(Value::Float(1.5))
+    );
+    assert_eq!(
+        ValueType::F64.decode(&(-0.25f64).to_be_bytes(), Endian::Big),
+        Some(Value::Float(-0.25))
+    );
+
+    let ascii = ValueType::String {
+        len: 8,
+        encoding: StringEncoding::Ascii,
+    };
+    assert_eq!(ascii.size(), 8);
+    assert_eq!(
+        ascii.decode(b"W1-2\0\0xx", Endian::Little),
+        Some(Value::Str("W1-2".to_string()))
+    );
+    assert_eq!(
+        ascii.decode(b"caf\xe9\0\0\0\0", Endian::Little),
+        Some(Value::Str("caf\u{FFFD}".to_string()))
+    );
+
+    let utf8 = ValueType::String {
+        len: 6,
+        encoding: StringEncoding::Utf8,
+    };
+    assert_eq!(
+        utf8.decode("café\0".as_bytes(), Endian::Little),
+        Some(Value::Str("café".to_string()))
+    );
+
+    let utf16 = ValueType::String {
+        len: 4,
+        encoding: StringEncoding::Utf16,
+    };
+    assert_eq!(utf16.size(), 8);
+    assert_eq!(
+        utf16.decode(&[0, b'H', 0, b'i', 0, 0, 0, b'!'], Endian::Big),
+        Some(Value::Str("Hi".to_string()))
+    );
+    assert_eq!(
+        utf16.decode(&[b'H', 0, b'i', 0], Endian::Little),
+        None,
+        "short read"
+    );
+
+    let bytes = ValueType::Bytes { len: 3 };
+    assert_eq!(
+        bytes.decode(&[1, 2, 3, 4], Endian::Little),
+        Some(Value::Bytes(vec![1, 2, 3]))
+    );
+}
+
+#[test]
+fn value_types_parse_by_name_or_with_their_length() {
+    let parse = |json: &str| serde_json::from_str::<ValueType>(json).unwrap();
+    assert_eq!(parse(r#""f32""#), ValueType::F32);
+    assert_eq!(
+        parse(r#"{ "string": { "len": 16, "encoding": "utf16" } }"#),
+        ValueType::String {
+            len: 16,
+            encoding: StringEncoding::Utf16
+        }
+    );
+    assert_eq!(
+        parse(r#"{ "string": { "len": 12 } }"#),
+        ValueType::String {
+            len: 12,
+            encoding: StringEncoding::Utf8
+        }
+    );
+    assert_eq!(
+        parse(r#"{ "bytes": { "len": 4 } }"#),
+        ValueType::Bytes { len: 4 }
+    );
+}
+
+fn float(x: f64) -> Value {
+    Value::Float(x)
+}
+
+fn text(s: &str) -> Value {
+    Value::Str(s.to_string())
+}
+
+#[test]
+fn approx_equals_allows_for_float_rounding() {
+    let cond: Condition =
+        serde_json::from_str(r#"{ "kind": "approx_equals", "value": 0.3 }"#).unwrap();
+    assert!(cond.holds(None, &float(0.1 + 0.2)));
+    assert!(cond.holds(None, &float(0.3005)));
+    assert!(!cond.holds(None, &float(0.302)));
+    assert!(!cond.holds(None, &Value::Int(0)));
+    assert!(cond.triggered(Some(&float(0.2)), &float(0.30001)));
+    assert!(!cond.triggered(Some(&float(0.3)), &float(0.30001)));
+
+    let loose = Condition::ApproxEquals {
+        value: 100.0,
+        tolerance: 0.5,
+    };
+    assert!(loose.holds(None, &Value::Int(100)));
+    assert!(loose.holds(None, &float(99.6)));
+}
+
+#[test]
+fn numeric_conditions_work_on_floats() {
+    let cond = Condition::GreaterThan { value: 10 };
+    assert!(cond.triggered(Some(&float(9.99)), &float(10.01)));
+    assert!(!cond.triggered(Some(&float(10.0)), &float(10.0)));
+    assert!(Condition::Decreased.triggered(Some(&float(1.0)), &float(0.5)));
+    assert!(Condition::Changed.triggered(Some(&float(1.0)), &float(1.5)));
+}
+
+#[test]
+fn string_conditions() {
+    let cond = Condition::StringEquals {
+        value: "W1-2".to_string(),
+    };
+    assert!(cond.triggered(Some(&text("W1-1")), &text("W1-2")));
+    assert!(!cond.triggered(Some(&text("W1-2")), &text("W1-2")));
+    assert!(!cond.holds(None, &text("w1-2")));
+
+    // Blanked while loading doesn't count, and neither does coming back to
+    // the same level from it... only a different, non-empty name.
+    let changed = Condition::StringChanged;
+    assert!(!changed.triggered(Some(&text("W1-1")), &text("")));
+    assert!(changed.triggered(Some(&text("")), &text("W1-2")));
+    assert!(changed.triggered(Some(&text("W1-1")), &text("W1-2")));
+    assert!(!changed.triggered(Some(&text("W1-2")), &text("W1-2")));
+    assert!(!changed.triggered(None, &text("W1-2")));
+
+    assert!(Condition::Changed.triggered(Some(&text("W1-1")), &text("")));
+}
+
+#[test]
+fn bitmask_tests_all_of_the_masks_bits() {
+    let set: Condition = serde_json::from_str(r#"{ "kind": "bitmask", "mask": "0x41" }"#).unwrap();
+    assert!(set.holds(None, &Value::Int(0xC1)));
+    assert!(!set.holds(None, &Value::Int(0x40)));
+    assert!(set.triggered(Some(&Value::Int(0x40)), &Value::Int(0x41)));
+    assert!(!set.triggered(Some(&Value::Int(0x41)), &Value::Int(0x43)));
+
+    let clear: Condition =
+        serde_json::from_str(r#"{ "kind": "bitmask", "mask": 64, "set": false }"#).unwrap();
+    assert!(clear.holds(None, &Value::Int(0x3F)));
+    assert!(!clear.holds(None, &Value::Int(0x40)));
+}
+
+#[test]
+fn changed_works_on_bytes() {
+    let a = Value::Bytes(vec![1, 2, 3]);
+    let b = Value::Bytes(vec![1, 2, 4]);
+    assert!(Condition::Changed.triggered(Some(&a), &b));
+    assert!(!Condition::Changed.triggered(Some(&a), &a.clone()));
+}
+
+#[test]
+fn set_game_time_can_follow_a_float_timer() {
+    let watch: Watch = serde_json::from_str(
+        r#"{
+            "name": "igt_seconds",
+            "address": "0x10",
+            "value_type": "f32",
+            "condition": { "kind": "changed" },
+            "action": "set_game_time",
+            "ms_per_unit": 1000
+        }"#,
+    )
+    .unwrap();
+    assert_eq!(
+        watch.command(&float(12.3456)).as_deref(),
+        Some("setgametime 12346")
+    );
+}
+
+fn typed_config(watch: &str, rest: &str) -> Result<AutosplitterConfig, String> {
+    AutosplitterConfig::from_json(&format!(
+        r#"{{ "target": {{ "kind": "retroarch" }}, "watches": [{watch}] {rest} }}"#
+    ))
+}
+
+#[test]
+fn conditions_must_fit_the_watchs_type() {
+    let float = r#"{ "name": "x", "address": "0x10", "value_type": "f32",
+                     "condition": { "kind": "equals", "value": 3 }, "action": "split" }"#;
+    assert_eq!(
+        typed_config(float, "").unwrap_err(),
+        "watch 'x': 'equals' can't be used on a float, which is rarely exactly equal to anything; use approx_equals"
+    );
+
+    let level = r#"{ "name": "level", "address": "0x10",
+                     "value_type": { "string": { "len": 8 } },
+                     "condition": { "kind": "increased" }, "action": "split" }"#;
+    assert_eq!(
+        typed_config(level, "").unwrap_err(),
+        "watch 'level': 'increased' can't be used on a string; use string_equals or string_changed"
+    );
+
+    let flags = r#"{ "name": "flags", "address": "0x10", "value_type": { "bytes": { "len": 2 } },
+                     "condition": { "kind": "bitmask", "mask": 1 }, "action": "split" }"#;
+    assert_eq!(
+        typed_config(flags, "").unwrap_err(),
+        "watch 'flags': 'bitmask' can't be used on a byte array"
+    );
+
+    let name = r#"{ "name": "level", "address": "0x10", "value_type": { "string": { "len": 8 } },
+                    "condition": { "kind": "changed" }, "action": "set_game_time" }"#;
+    assert_eq!(
+        typed_config(name, "").unwrap_err(),
+        "watch 'level': set_game_time needs a number, but this is a string"
+    );
+
+    let empty = r#"{ "name": "b", "address": "0x10", "value_type": { "bytes": { "len": 0 } } }"#;
+    assert_eq!(
+        typed_config(empty, "").unwrap_err(),
+        "watch 'b': a string or bytes watch needs a len of at least 1"
+    );
+
+    let level =
+        r#"{ "name": "level", "address": "0x10", "value_type": { "string": { "len": 8 } } }"#;
+    assert_eq!(
+        typed_config(
+            level,
+            r#", "reset": { "watch": "level", "kind": "equals", "value": 0 }"#
+        )
+        .unwrap_err(),
+        "reset, on watch 'level': 'equals' can't be used on a string; use string_equals or string_changed"
+    );
+    assert!(
+        typed_config(
+            level,
+            r#", "reset": { "watch": "level", "kind": "string_equals", "value": "title" }"#
+        )
+        .is_ok()
+    );
+}
+
+#[test]
+fn a_string_watch_drives_the_split_sequence() {
+    let config = typed_config(
+        r#"{ "name": "level", "address": "0x10",
+             "value_type": { "string": { "len": 16, "encoding": "utf16" } } }"#,
+        r#", "start": { "watch": "level", "kind": "string_equals", "value": "1-1" },
+            "splits": [
+              { "name": "1-1", "when": { "watch": "level", "kind": "string_changed" } },
+              { "name": "1-2", "when": "level == \"2-1\"" }
+            ]"#,
+    )
+    .unwrap();
+    let mut engine = Engine::new(config);
+    let level = |name: &str| HashMap::from([("level".to_string(), text(name))]);
+    let mut index = None;
+
+    assert!(engine.step(&level("title"), || index).is_empty());
+    assert_eq!(commands_of(engine.step(&level("1-1"), || index)), ["start"]);
+    index = Some(0);
+    assert!(engine.step(&level(""), || index).is_empty());
+    assert_eq!(commands_of(engine.step(&level("1-2"), || index)), ["split"]);
+    index = Some(1);
+    assert!(engine.step(&level(""), || index).is_empty());
+    assert_eq!(commands_of(engine.step(&level("2-1"), || index)), ["split"]);
+}
@@ -0,0 +1,87 @@
+//! Fixtures shared by the integration tests.
+
+#![allow(dead_code)]
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration};
+use openspeedrun::core::split::{AttemptHistoryEntry, SegmentHistoryEntry};
+use openspeedrun::core::time_source::FakeClock;
+use openspeedrun::{AppState, Run};
+
+pub fn secs(n: i64) -> Duration {
+    Duration::seconds(n)
+}
+
+/// Splits named `names`, with one attempt per entry of `attempts`, a day
+/// apart: its segment times in seconds (`None` for a skipped split, which
+/// makes the next segment combined) and whether it finished. A reset
+/// attempt was reset on the split after its last segment. Best Segments
+/// and the Personal Best come from that history.
+pub fn run_from_attempts(names: &[&str], attempts: &[(&[Option<i64>], bool)]) -> Run {
+    let mut run = Run::new("Game", "Any%", names);
+    for (attempt, &(segments, ended)) in attempts.iter().enumerate() {
+        let run_index = attempt as u32 + 1;
+        for (i, (split, segment)) in run.splits.iter_mut().zip(segments).enumerate() {
+            split.segment_history.push(SegmentHistoryEntry {
+                run_index,
+                real_time: segment.map(secs),
+                combined: i > 0 && segments[i - 1].is_none(),
+                ..Default::default()
+            });
+        }
+        run.attempt_history.push(AttemptHistoryEntry {
+            run_index,
+            real_time: Some(secs(segments.iter().flatten().sum())),
+            ended,
+            date: DateTime::from_timestamp(1_700_000_000 + 86_400 * run_index as i64, 0),
+            reset_split_index: (!ended).then_some(segments.len()),
+            ..Default::default()
+        });
+    }
+    for split in &mut run.splits {
+        split.recompute_best_segment();
+    }
+    run.recompute_personal_best();
+    run.attempts = attempts.len() as u32;
+    run
+}
+
+/// Splits A, B, C and four attempts:
+/// 1. finished, 10/20/30
+/// 2. finished, 12/15/25 — the PB
+/// 3. reset during C after 8/18
+/// 4. finished, 11, B skipped, then 40 for B and C together
+pub fn run_with_history() -> Run {
+    run_from_attempts(
+        &["A", "B", "C"],
+        &[
+            (&[Some(10), Some(20), Some(30)], true),
+            (&[Some(12), Some(15), Some(25)], true),
+            (&[Some(8), Some(18)], false),
+            (&[Some(11), None, Some(40)], true),
+        ],
+    )
+}
+
+/// An `AppState` for `run`, backed by a fresh `split.json` in its own temp
+/// dir (so its saves have somewhere real to go) and a `FakeClock` the test
+/// advances by hand.
+pub fn app_with_run(name: &str, run: Run) -> (AppState, FakeClock, PathBuf) {
+    let dir = std::env::temp_dir().join(format!("osr_{name}_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    run.save_to_file(dir.join("split.json").to_str().unwrap())
+        .unwrap();
+
+    let clock = FakeClock::default();
+    let mut app = AppState {
+        splits_display: run.splits.clone(),
+        splits_backup: run.splits.clone(),
+        run,
+        split_base_path: dir.clone(),
+        ..AppState::empty_for_test()
+    };
+    app.set_clock(Arc::new(clock.clone()));
+    (app, clock, dir)
+}
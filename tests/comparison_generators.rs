@@ -0,0 +1,160 @@
+//! Kept apart from `comparisons.rs`: registering a generator is global to
+//! the test binary.
+
+mod common;
+
+use std::sync::Arc;
+
+use chrono::Duration;
+use openspeedrun::Run;
+use openspeedrun::core::split::{
+    BUILTIN_COMPARISONS, COMPARISON_BEST_SEGMENTS, COMPARISON_PERSONAL_BEST, ComparisonGenerator,
+    ComparisonTime, GoalTimeGenerator, SegmentHistoryEntry, TimingMethod,
+    register_comparison_generator,
+};
+
+use common::secs;
+
+/// Average of each split's last `n` segments.
+struct LastAttemptsAverage {
+    n: usize,
+}
+
+impl ComparisonGenerator for LastAttemptsAverage {
+    fn name(&self) -> &str {
+        "Last 2 Average"
+    }
+
+    fn generate(&self, run: &Run) -> Vec<ComparisonTime> {
+        run.splits
+            .iter()
+            .map(|split| {
+                let last: Vec<Duration> = split
+                    .segment_history
+                    .iter()
+                    .rev()
+                    .filter_map(|e| e.real_time)
+                    .take(self.n)
+                    .collect();
+                ComparisonTime {
+                    real_time: (!last.is_empty())
+                        .then(|| last.iter().copied().sum::<Duration>() / last.len() as i32),
+                    game_time: None,
+                }
+            })
+            .collect()
+    }
+}
+
+fn run_with_golds() -> Run {
+    let mut run = Run::new("Game", "Any%", &["A", "B", "C"]);
+    for (split, (gold, history)) in
+        run.splits
+            .iter_mut()
+            .zip([(10, [12, 14, 10]), (20, [30, 24, 20]), (30, [31, 35, 30])])
+    {
+        split
+            .comparisons
+            .get_mut(COMPARISON_BEST_SEGMENTS)
+            .unwrap()
+            .real_time = Some(secs(gold));
+        for (i, segment) in history.into_iter().enumerate() {
+            split.segment_history.push(SegmentHistoryEntry {
+                run_index: i as u32 + 1,
+                real_time: Some(secs(segment)),
+                ..Default::default()
+            });
+        }
+    }
+    run
+}
+
+#[test]
+fn goal_comparisons_load_from_split_json_and_follow_best_segments() {
+    let dir = std::env::temp_dir().join(format!("osr_goal_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("split.json");
+
+    let mut json = serde_json::to_value(run_with_golds()).unwrap();
+    json["goal_comparisons"] = serde_json::json!([
+        { "name": "Sub 1:30", "goal": { "real_time": 90_000 } },
+        // Can't shadow a stored comparison.
+        { "name": "Personal Best", "goal": { "real_time": 1_000 } }
+    ]);
+    std::fs::write(&path, json.to_string()).unwrap();
+    let run = Run::load_from_file(path.to_str().unwrap()).unwrap();
+
+    let goal: Vec<_> = run
+        .splits
+        .iter()
+        .map(|s| s.comparison_time("Sub 1:30", TimingMethod::RealTime))
+        .collect();
+    assert_eq!(goal, [Some(secs(15)), Some(secs(30)), Some(secs(45))]);
+    assert_eq!(
+        run.splits[0].comparison_time("Sub 1:30", TimingMethod::GameTime),
+        None
+    );
+    assert_eq!(
+        run.comparison_total(COMPARISON_PERSONAL_BEST, TimingMethod::RealTime),
+        None
+    );
+    assert!(run.comparison_names().contains(&"Sub 1:30".to_string()));
+
+    // Saved back as declared, without the generated times.
+    run.save_to_file(path.to_str().unwrap()).unwrap();
+    let saved: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(saved["goal_comparisons"][0]["goal"]["real_time"], 90_000);
+    assert!(saved["splits"][0]["comparisons"].get("Sub 1:30").is_none());
+
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn goal_and_registered_generators_are_listed_and_generated() {
+    register_comparison_generator(Arc::new(LastAttemptsAverage { n: 2 }));
+
+    let mut run = run_with_golds();
+    run.goal_comparisons.push(GoalTimeGenerator {
+        name: "Goal".to_string(),
+        goal: ComparisonTime {
+            real_time: Some(secs(61)),
+            game_time: None,
+        },
+    });
+    run.generate_comparisons();
+
+    let names = run.comparison_names();
+    assert_eq!(names[..BUILTIN_COMPARISONS.len()], *BUILTIN_COMPARISONS);
+    assert_eq!(
+        names[BUILTIN_COMPARISONS.len()..],
+        ["Last 2 Average", "Goal"]
+    );
+
+    let average: Vec<_> = run
+        .splits
+        .iter()
+        .map(|s| s.comparison_time("Last 2 Average", TimingMethod::RealTime))
+        .collect();
+    assert_eq!(
+        average,
+        [
+            Some(secs(12)),
+            Some(secs(22)),
+            Some(Duration::milliseconds(32_500))
+        ]
+    );
+    // Rounding lands on the last split, so the goal total is exact.
+    assert_eq!(
+        run.comparison_total("Goal", TimingMethod::RealTime),
+        Some(secs(61))
+    );
+
+    // Removing a goal removes its comparison on the next generation.
+    run.goal_comparisons.clear();
+    run.generate_comparisons();
+    assert_eq!(
+        run.splits[0].comparison_time("Goal", TimingMethod::RealTime),
+        None
+    );
+}
@@ -0,0 +1,135 @@
+mod common;
+
+use std::sync::Arc;
+
+use chrono::Duration;
+use openspeedrun::core::split::{
+    BUILTIN_COMPARISONS, COMPARISON_BALANCED_PB, COMPARISON_BEST_SPLIT_TIMES,
+    COMPARISON_LATEST_RUN, COMPARISON_PERSONAL_BEST, COMPARISON_WORST_SEGMENTS, TimingMethod,
+};
+use openspeedrun::core::time_source::FakeClock;
+use openspeedrun::{AppState, Run};
+
+use common::{run_from_attempts, secs};
+
+/// Splits A, B, C. Attempt 1 finishes 10/20/30, attempt 2 (the PB)
+/// 12/15/25, and attempt 3 is reset after an 8s A.
+fn run_with_a_pb_and_a_reset() -> Run {
+    run_from_attempts(
+        &["A", "B", "C"],
+        &[
+            (&[Some(10), Some(20), Some(30)], true),
+            (&[Some(12), Some(15), Some(25)], true),
+            (&[Some(8)], false),
+        ],
+    )
+}
+
+fn segments(run: &Run, name: &str) -> Vec<Option<Duration>> {
+    run.splits
+        .iter()
+        .map(|s| s.comparison_time(name, TimingMethod::RealTime))
+        .collect()
+}
+
+#[test]
+fn generated_comparisons_come_from_the_whole_history() {
+    let mut run = run_with_a_pb_and_a_reset();
+    run.generate_comparisons();
+
+    assert_eq!(
+        segments(&run, COMPARISON_WORST_SEGMENTS),
+        [Some(secs(12)), Some(secs(20)), Some(secs(30))]
+    );
+    // The reset attempt only got through A.
+    assert_eq!(
+        segments(&run, COMPARISON_LATEST_RUN),
+        [Some(secs(8)), None, None]
+    );
+    // Best times at each split are 8, 27 and 52, from different attempts.
+    assert_eq!(
+        segments(&run, COMPARISON_BEST_SPLIT_TIMES),
+        [Some(secs(8)), Some(secs(19)), Some(secs(25))]
+    );
+    assert_eq!(
+        run.comparison_total(COMPARISON_BEST_SPLIT_TIMES, TimingMethod::RealTime),
+        Some(secs(52))
+    );
+
+    // Even skill: A = 8 + 4p, B = 15 + 5p, C = 25 + 5p, summing to the
+    // 52s PB at p = 2/7.
+    let balanced: Vec<i64> = segments(&run, COMPARISON_BALANCED_PB)
+        .into_iter()
+        .map(|s| s.unwrap().num_milliseconds())
+        .collect();
+    assert_eq!(balanced.iter().sum::<i64>(), 52_000);
+    for (segment, expected) in balanced.iter().zip([9_143, 16_429, 26_429]) {
+        assert!((segment - expected).abs() <= 1, "{balanced:?}");
+    }
+
+    // Nothing generated is written to disk.
+    let json = serde_json::to_string(&run).unwrap();
+    assert!(!json.contains(COMPARISON_LATEST_RUN));
+}
+
+#[test]
+fn balanced_pb_needs_a_pb_and_history_everywhere() {
+    let mut run = run_with_a_pb_and_a_reset();
+    run.splits[2].segment_history.clear();
+    run.generate_comparisons();
+    assert_eq!(segments(&run, COMPARISON_BALANCED_PB), [None, None, None]);
+
+    let mut run = run_with_a_pb_and_a_reset();
+    run.splits[0]
+        .comparisons
+        .get_mut(COMPARISON_PERSONAL_BEST)
+        .unwrap()
+        .real_time = None;
+    run.generate_comparisons();
+    assert_eq!(segments(&run, COMPARISON_BALANCED_PB), [None, None, None]);
+}
+
+#[test]
+fn cycling_reaches_every_comparison_and_finishing_updates_the_latest_run() {
+    let dir = std::env::temp_dir().join(format!("osr_comparisons_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    run_with_a_pb_and_a_reset()
+        .save_to_file(dir.join("split.json").to_str().unwrap())
+        .unwrap();
+    let run = Run::load_from_file(dir.join("split.json").to_str().unwrap()).unwrap();
+
+    let clock = FakeClock::default();
+    let mut app = AppState {
+        splits_display: run.splits.clone(),
+        splits_backup: run.splits.clone(),
+        run,
+        split_base_path: dir.clone(),
+        ..AppState::empty_for_test()
+    };
+    app.set_clock(Arc::new(clock.clone()));
+    assert_eq!(
+        segments(&app.run, COMPARISON_LATEST_RUN),
+        [Some(secs(8)), None, None]
+    );
+
+    let mut seen = vec![app.run.selected_comparison.clone()];
+    for _ in 1..BUILTIN_COMPARISONS.len() {
+        app.cycle_comparison();
+        seen.push(app.run.selected_comparison.clone());
+    }
+    assert_eq!(seen, BUILTIN_COMPARISONS);
+    app.cycle_comparison();
+    assert_eq!(app.run.selected_comparison, COMPARISON_PERSONAL_BEST);
+
+    app.start_timers();
+    for segment in [11_000, 16_000, 27_000] {
+        clock.advance_ms(segment);
+        app.split();
+    }
+    assert_eq!(
+        segments(&app.run, COMPARISON_LATEST_RUN),
+        [Some(secs(11)), Some(secs(16)), Some(secs(27))]
+    );
+
+    std::fs::remove_dir_all(&dir).ok();
+}
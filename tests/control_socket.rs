@@ -0,0 +1,209 @@
+#![cfg(unix)]
+
+mod common;
+
+use std::sync::{Arc, Mutex, mpsc};
+
+use openspeedrun::core::client::ControlClient;
+use openspeedrun::core::protocol::{PROTOCOL_VERSION, Reply, handle_command};
+use openspeedrun::core::server::{UICommand, serve};
+use openspeedrun::{Run, TimerState};
+
+use common::app_with_run;
+
+fn ms(n: i64) -> chrono::Duration {
+    chrono::Duration::milliseconds(n)
+}
+
+#[test]
+fn replies_render_and_parse_back() {
+    for reply in [
+        Reply::ok(),
+        Reply::value(1234),
+        Reply::err("timer not running"),
+    ] {
+        assert_eq!(Reply::parse(&format!("{reply}\n")), Some(reply));
+    }
+    assert_eq!(Reply::value("-").to_string(), "ok -");
+    assert_eq!(Reply::parse("hello"), None);
+}
+
+#[test]
+fn commands_report_success_or_why_they_were_refused() {
+    let (mut app, clock, dir) = app_with_run("commands", Run::new("Game", "Any%", &["A", "B"]));
+
+    assert_eq!(
+        handle_command(&mut app, "version"),
+        Reply::value(PROTOCOL_VERSION)
+    );
+    assert_eq!(
+        handle_command(&mut app, "getstate"),
+        Reply::value("not_started")
+    );
+    assert_eq!(handle_command(&mut app, "getsplitindex"), Reply::value(-1));
+    assert_eq!(
+        handle_command(&mut app, "pause"),
+        Reply::err("timer not running")
+    );
+    assert_eq!(
+        handle_command(&mut app, "undolastsplit"),
+        Reply::err("no split to undo")
+    );
+    assert_eq!(
+        handle_command(&mut app, "frobnicate"),
+        Reply::err("unknown command 'frobnicate'")
+    );
+
+    assert_eq!(handle_command(&mut app, "start"), Reply::ok());
+    assert_eq!(
+        handle_command(&mut app, "start"),
+        Reply::err("timer already running")
+    );
+    clock.advance_ms(1_500);
+    assert_eq!(
+        handle_command(&mut app, "getcurrenttime"),
+        Reply::value(1500)
+    );
+    assert_eq!(handle_command(&mut app, "skipsplit"), Reply::ok());
+    assert_eq!(
+        handle_command(&mut app, "skipsplit"),
+        Reply::err("the final split can't be skipped")
+    );
+    assert_eq!(
+        handle_command(&mut app, "reloadrun"),
+        Reply::err("can't reload during a run")
+    );
+    assert_eq!(handle_command(&mut app, "pause"), Reply::ok());
+    assert_eq!(
+        handle_command(&mut app, "split"),
+        Reply::err("timer is paused")
+    );
+    assert_eq!(handle_command(&mut app, "getstate"), Reply::value("paused"));
+    assert_eq!(handle_command(&mut app, "getsplitindex"), Reply::value(1));
+    assert_eq!(
+        handle_command(&mut app, "getcomparison"),
+        Reply::value("Personal Best")
+    );
+
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn delta_is_signed_milliseconds_or_a_dash_without_a_comparison() {
+    let (mut app, clock, dir) = app_with_run("delta", Run::new("Game", "Any%", &["A", "B"]));
+
+    assert_eq!(handle_command(&mut app, "getdelta"), Reply::value("-"));
+
+    app.run.splits[0]
+        .comparisons
+        .get_mut("Personal Best")
+        .unwrap()
+        .real_time = Some(chrono::Duration::seconds(10));
+    app.split();
+    clock.advance_ms(8_000);
+    app.split();
+    assert_eq!(handle_command(&mut app, "getdelta"), Reply::value(-2000));
+
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+/// Many commands over one connection against the real socket server.
+#[test]
+fn one_connection_carries_many_commands() {
+    let (app, _clock, dir) = app_with_run("socket", Run::new("Game", "Any%", &["A", "B"]));
+    let socket = dir.join("control.sock");
+    let app = Arc::new(Mutex::new(app));
+    let (tx, rx) = mpsc::channel::<UICommand>();
+
+    // The server never returns; the thread just dies with the test binary.
+    std::thread::spawn({
+        let app = app.clone();
+        let socket = socket.clone();
+        move || {
+            let rt = tokio::runtime::Runtime::new().unwrap();
+            rt.block_on(async move {
+                let listener = tokio::net::UnixListener::bind(&socket).unwrap();
+                serve(listener, app, tx).await;
+            });
+        }
+    });
+
+    let mut client = (0..100)
+        .find_map(|_| {
+            ControlClient::connect_to(&socket).ok().or_else(|| {
+                std::thread::sleep(std::time::Duration::from_millis(10));
+                None
+            })
+        })
+        .expect("server never came up");
+
+    assert_eq!(
+        client.send("getstate").unwrap(),
+        Reply::value("not_started")
+    );
+    assert_eq!(client.send("start").unwrap(), Reply::ok());
+    assert_eq!(client.send("getstate").unwrap(), Reply::value("running"));
+    assert_eq!(client.send("getsplitindex").unwrap(), Reply::value(0));
+    assert_eq!(client.send("reloadshader").unwrap(), Reply::ok());
+    assert!(matches!(rx.try_recv(), Ok(UICommand::ReloadShader)));
+    assert!(matches!(client.send("bogus").unwrap(), Reply::Err(_)));
+    // Still usable after an error.
+    assert_eq!(client.send("reset").unwrap(), Reply::ok());
+    assert_eq!(app.lock().unwrap().timer.state, TimerState::NotStarted);
+
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn game_time_commands_drive_igt_absolutely() {
+    let (mut app, clock, dir) = app_with_run("game_time", Run::new("Game", "Any%", &["A", "B"]));
+
+    assert_eq!(
+        handle_command(&mut app, "setgametime 5000"),
+        Reply::err("no attempt in progress")
+    );
+    assert_eq!(handle_command(&mut app, "initgametime"), Reply::ok());
+    assert_eq!(handle_command(&mut app, "start"), Reply::ok());
+    clock.advance_ms(2_000);
+
+    assert_eq!(handle_command(&mut app, "setgametime 90000"), Reply::ok());
+    clock.advance_ms(1_000);
+    assert_eq!(app.igt_timer.current_time(), ms(91_000));
+
+    // Idempotent, unlike toggleloading.
+    assert_eq!(handle_command(&mut app, "pausegametime"), Reply::ok());
+    assert_eq!(handle_command(&mut app, "pausegametime"), Reply::ok());
+    clock.advance_ms(4_000);
+    assert_eq!(app.igt_timer.current_time(), ms(91_000));
+    assert_eq!(app.timer.current_time(), ms(7_000));
+    assert_eq!(
+        handle_command(&mut app, "toggleloading"),
+        Reply::err("game time is externally controlled")
+    );
+
+    // Pausing and resuming the whole run mid-load doesn't end the load.
+    app.pause_timers();
+    app.start_timers();
+    clock.advance_ms(1_000);
+    assert_eq!(app.igt_timer.current_time(), ms(91_000));
+
+    assert_eq!(handle_command(&mut app, "resumegametime"), Reply::ok());
+    assert_eq!(handle_command(&mut app, "resumegametime"), Reply::ok());
+    clock.advance_ms(500);
+    assert_eq!(app.igt_timer.current_time(), ms(91_500));
+
+    assert_eq!(
+        handle_command(&mut app, "setgametime soon"),
+        Reply::err("setgametime needs a time in milliseconds")
+    );
+    assert_eq!(
+        handle_command(&mut app, "split now"),
+        Reply::err("'split' takes no argument")
+    );
+
+    // A reset hands game time back to manual control.
+    app.reset_splits();
+    assert!(!app.game_time_external);
+
+    std::fs::remove_dir_all(&dir).ok();
+}
@@ -21,6 +21,8 @@ fn attempts_csv_has_a_header_and_one_row_per_attempt() {
             game_time: None,
             ended: true,
             date: Some(fixed_date()),
+            reset_split_index: None,
+            ..Default::default()
         },
         AttemptHistoryEntry {
             run_index: 1,
@@ -28,6 +30,8 @@ fn attempts_csv_has_a_header_and_one_row_per_attempt() {
             game_time: None,
             ended: false,
             date: None,
+            reset_split_index: None,
+            ..Default::default()
         },
     ];
     run.pb_history = vec![AttemptHistoryEntry {
@@ -36,6 +40,8 @@ fn attempts_csv_has_a_header_and_one_row_per_attempt() {
         game_time: None,
         ended: true,
         date: Some(fixed_date()),
+        reset_split_index: None,
+        ..Default::default()
     }];
 
     let csv = csv::attempts_csv(&run);
@@ -70,17 +76,20 @@ fn segments_csv_has_one_row_per_attempt_and_split_pair() {
             run_index: 0,
             real_time: Some(ms(30_120)),
             game_time: None,
+            combined: false,
         },
         SegmentHistoryEntry {
             run_index: 1,
             real_time: Some(ms(29_800)),
             game_time: None,
+            combined: false,
         },
     ];
     run.splits[1].segment_history = vec![SegmentHistoryEntry {
         run_index: 0,
         real_time: Some(ms(60_000)),
         game_time: Some(ms(58_500)),
+        combined: false,
     }];
 
     let csv = csv::segments_csv(&run);
@@ -103,6 +112,7 @@ fn segments_csv_quotes_a_split_name_containing_a_comma() {
         run_index: 0,
         real_time: Some(ms(1_000)),
         game_time: None,
+        combined: false,
     }];
 
     let csv = csv::segments_csv(&run);
@@ -117,3 +127,88 @@ fn segments_csv_of_a_run_with_no_history_is_just_the_header() {
         "run_index,split_index,split_name,real_time_ms,game_time_ms\n"
     );
 }
+
+#[test]
+fn split_statistics_csv_has_one_row_per_split() {
+    let mut run = Run::new("Test Game", "Any%", &["A", "B"]);
+    run.splits[0].segment_history = [(0, 10_000), (1, 8_000)]
+        .into_iter()
+        .map(|(run_index, time)| SegmentHistoryEntry {
+            run_index,
+            real_time: Some(ms(time)),
+            game_time: None,
+            combined: false,
+        })
+        .collect();
+    run.attempt_history = [(0, None), (1, Some(1)), (2, Some(0))]
+        .into_iter()
+        .map(|(run_index, reset_split_index)| AttemptHistoryEntry {
+            run_index,
+            real_time: None,
+            game_time: None,
+            ended: reset_split_index.is_none(),
+            date: None,
+            reset_split_index,
+            ..Default::default()
+        })
+        .collect();
+
+    let csv = csv::split_statistics_csv(&run);
+    let mut lines = csv.lines();
+    assert_eq!(
+        lines.next(),
+        Some(
+            "split_index,split_name,reached,resets,reset_rate,mean_ms,median_ms,std_dev_ms,gold_rate"
+        )
+    );
+    assert_eq!(lines.next(), Some("0,A,3,1,0.3333,9000,9000,1000,1.0000"));
+    assert_eq!(lines.next(), Some("1,B,2,1,0.5000,,,,"));
+    assert_eq!(lines.next(), None);
+}
+
+#[test]
+fn progression_csv_has_one_row_per_attempt() {
+    let mut run = Run::new("Test Game", "Any%", &["A"]);
+    run.splits[0].segment_history = [(0, 10_000), (1, 8_000)]
+        .into_iter()
+        .map(|(run_index, time)| SegmentHistoryEntry {
+            run_index,
+            real_time: Some(ms(time)),
+            game_time: None,
+            combined: false,
+        })
+        .collect();
+    run.attempt_history = vec![
+        AttemptHistoryEntry {
+            run_index: 0,
+            real_time: Some(ms(10_000)),
+            game_time: None,
+            ended: true,
+            date: Some(fixed_date()),
+            reset_split_index: None,
+            ..Default::default()
+        },
+        AttemptHistoryEntry {
+            run_index: 1,
+            real_time: Some(ms(8_000)),
+            game_time: None,
+            ended: false,
+            date: None,
+            reset_split_index: Some(0),
+            ..Default::default()
+        },
+    ];
+
+    let csv = csv::progression_csv(&run);
+    let mut lines = csv.lines();
+    assert_eq!(
+        lines.next(),
+        Some("run_index,date,attempts,sum_of_best_ms,personal_best_ms")
+    );
+    assert_eq!(
+        lines.next(),
+        Some("0,2023-11-14T22:13:20+00:00,1,10000,10000")
+    );
+    assert_eq!(lines.next(), Some("1,,2,8000,10000"));
+    assert_eq!(lines.next(), None);
+}
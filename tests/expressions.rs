@@ -0,0 +1,465 @@
+use std::collections::HashMap;
+
+use openspeedrun::autosplitter::config::AutosplitterConfig;
+use openspeedrun::autosplitter::engine::Engine;
+use openspeedrun::autosplitter::expr::{ExprError, Expression, Scope, Type, Value};
+
+/// Integer watches `room`, `hp`, `frames` and `flags`, with the previous
+/// poll's values in `old`.
+struct Values {
+    current: HashMap<&'static str, i128>,
+    old: HashMap<&'static str, i128>,
+}
+
+impl Scope for Values {
+    fn value(&self, name: &str, old: bool) -> Option<Value> {
+        let values = if old { &self.old } else { &self.current };
+        values.get(name).map(|&v| Value::Int(v))
+    }
+}
+
+fn values(current: &[(&'static str, i128)], old: &[(&'static str, i128)]) -> Values {
+    Values {
+        current: current.iter().copied().collect(),
+        old: old.iter().copied().collect(),
+    }
+}
+
+fn watches(name: &str, _old: bool) -> Result<Type, String> {
+    match name {
+        "room" | "hp" | "frames" | "flags" => Ok(Type::Int),
+        "loading" => Ok(Type::Bool),
+        _ => Err(format!("there's no watch or variable named '{name}'")),
+    }
+}
+
+fn eval(source: &str, scope: &Values) -> Option<Value> {
+    let expression = Expression::new(source);
+    expression
+        .check(&watches)
+        .unwrap_or_else(|e| panic!("{}", e.pointing_into(source)));
+    expression.eval(scope)
+}
+
+fn int(source: &str) -> Option<i128> {
+    match eval(source, &values(&[], &[]))? {
+        Value::Int(n) => Some(n),
+        other => panic!("{source} isn't an integer but {other:?}"),
+    }
+}
+
+fn error(source: &str) -> ExprError {
+    Expression::new(source)
+        .check(&watches)
+        .expect_err("should not check")
+}
+
+#[test]
+fn integer_literals_in_every_base() {
+    assert_eq!(int("42"), Some(42));
+    assert_eq!(int("0x7E_0020"), Some(0x7E0020));
+    assert_eq!(int("0b1010"), Some(10));
+    assert_eq!(int("1_000_000"), Some(1_000_000));
+}
+
+#[test]
+fn arithmetic_follows_the_usual_precedence() {
+    assert_eq!(int("1 + 2 * 3"), Some(7));
+    assert_eq!(int("(1 + 2) * 3"), Some(9));
+    assert_eq!(int("10 - 4 - 3"), Some(3));
+    assert_eq!(int("7 / 2"), Some(3));
+    assert_eq!(int("-7 / 2"), Some(-3));
+    assert_eq!(int("7 % 4"), Some(3));
+    assert_eq!(int("--5"), Some(5));
+    assert_eq!(int("1 << 4 + 1"), Some(32));
+    assert_eq!(int("0xF0 >> 4"), Some(0xF));
+}
+
+#[test]
+fn bit_operators() {
+    assert_eq!(int("0xF0 & 0x3C"), Some(0x30));
+    assert_eq!(int("0xF0 | 0x0F"), Some(0xFF));
+    assert_eq!(int("0xFF ^ 0x0F"), Some(0xF0));
+    assert_eq!(int("0xFF & ~0x40"), Some(0xBF));
+    // `&` binds tighter than `|`, and both tighter than comparisons.
+    assert_eq!(int("1 | 2 & 0"), Some(1));
+    let scope = values(&[("flags", 0x41)], &[]);
+    assert_eq!(
+        eval("flags & 0x40 != 0", &scope),
+        eval("(flags & 0x40) != 0", &scope)
+    );
+}
+
+#[test]
+fn what_cant_be_computed_is_unknown() {
+    assert_eq!(int("1 / 0"), None);
+    assert_eq!(int("1 % 0"), None);
+    assert_eq!(int("1 << 200"), None);
+    assert_eq!(int("1 << -1"), None);
+    assert_eq!(
+        int("0x7fffffffffffffffffffffffffffffff + 1"),
+        None,
+        "overflow"
+    );
+}
+
+#[test]
+fn watches_read_now_and_as_old() {
+    let scope = values(&[("room", 0x12), ("hp", 0)], &[("room", 0x12), ("hp", 40)]);
+    assert_eq!(
+        eval("room == 0x12 && old.hp > 0 && hp == 0", &scope),
+        Some(Value::Bool(true))
+    );
+    assert_eq!(eval("current.hp == hp", &scope), Some(Value::Bool(true)));
+    assert_eq!(eval("old.hp - hp", &scope), Some(Value::Int(40)));
+    assert_eq!(
+        eval("(frames / 60) > 10", &values(&[("frames", 660)], &[])),
+        Some(Value::Bool(true))
+    );
+
+    // No previous poll yet.
+    let first = values(&[("hp", 0)], &[]);
+    assert_eq!(eval("old.hp > 0 && hp == 0", &first), None);
+}
+
+#[test]
+fn and_or_are_decided_by_one_known_side() {
+    let first = values(&[("hp", 0)], &[]);
+    assert_eq!(
+        eval("old.hp > 0 && hp > 0", &first),
+        Some(Value::Bool(false))
+    );
+    assert_eq!(
+        eval("hp > 0 && old.hp > 0", &first),
+        Some(Value::Bool(false))
+    );
+    assert_eq!(
+        eval("old.hp > 0 || hp == 0", &first),
+        Some(Value::Bool(true))
+    );
+    assert_eq!(eval("old.hp > 0 || hp > 0", &first), None);
+    assert_eq!(eval("!(old.hp > 0)", &first), None);
+}
+
+#[test]
+fn comparisons_and_the_conditional() {
+    let scope = values(&[("room", 3)], &[]);
+    assert_eq!(eval("room >= 3", &scope), Some(Value::Bool(true)));
+    assert_eq!(eval("room < 3", &scope), Some(Value::Bool(false)));
+    assert_eq!(eval("room != 3", &scope), Some(Value::Bool(false)));
+    assert_eq!(eval("true == !false", &scope), Some(Value::Bool(true)));
+    assert_eq!(eval("room > 2 ? 10 : 20", &scope), Some(Value::Int(10)));
+    assert_eq!(
+        eval("room == 1 ? 1 : room == 2 ? 2 : 3", &scope),
+        Some(Value::Int(3))
+    );
+    assert_eq!(
+        eval("true || 1 == 1 && false", &scope),
+        Some(Value::Bool(true))
+    );
+}
+
+#[test]
+fn syntax_errors_point_at_the_problem() {
+    let e = error("room == 1 &&");
+    assert_eq!(
+        (e.column, e.message.as_str()),
+        (13, "expected a value, found the end")
+    );
+
+    let e = error("room = 1");
+    assert_eq!(
+        (e.column, e.message.as_str()),
+        (6, "unexpected character '='")
+    );
+
+    let e = error("(room == 1");
+    assert_eq!(
+        (e.column, e.message.as_str()),
+        (11, "expected ')' to close the '('")
+    );
+
+    let e = error("room == 1)");
+    assert_eq!((e.column, e.message.as_str()), (10, "unmatched ')'"));
+
+    let e = error("room 1");
+    assert_eq!((e.column, e.message.as_str()), (6, "expected an operator"));
+
+    let e = error("0 < room < 5");
+    assert_eq!(e.column, 10);
+    assert_eq!(
+        e.message,
+        "comparisons can't be chained, join them with '&&'"
+    );
+
+    let e = error("old.3");
+    assert_eq!(e.column, 5);
+    assert_eq!(e.message, "expected a watch or variable name after 'old.'");
+
+    let e = error("0x12G == room");
+    assert_eq!(
+        (e.column, e.message.as_str()),
+        (1, "'0x12G' isn't a valid number")
+    );
+
+    let e = error("room ? 1");
+    assert_eq!(e.column, 9);
+
+    // Columns count characters, not bytes.
+    let e = error("\"é\" == room");
+    assert_eq!(e.column, 5);
+    assert_eq!(e.message, "'==' can't compare a string with an integer");
+    let e = error("room == 1 && é");
+    assert_eq!(e.column, 14);
+}
+
+#[test]
+fn type_errors_point_at_the_problem() {
+    let e = error("room + true");
+    assert_eq!(e.column, 8);
+    assert_eq!(e.message, "'+' needs a number here, found a bool");
+
+    let e = error("room == loading");
+    assert_eq!(e.column, 6);
+    assert_eq!(e.message, "'==' can't compare an integer with a bool");
+
+    let e = error("!room");
+    assert_eq!(e.column, 2);
+    assert_eq!(e.message, "'!' needs a bool here, found an integer");
+
+    let e = error("loading ? 1 : false");
+    assert_eq!(e.column, 15);
+
+    let e = error("room > 1 && bos_hp == 0");
+    assert_eq!(e.column, 13);
+    assert_eq!(e.message, "there's no watch or variable named 'bos_hp'");
+
+    let e = Expression::new("flags & 0x40")
+        .check_condition(&watches)
+        .unwrap_err();
+    assert_eq!(e.column, 1);
+    assert!(e.message.starts_with("a condition must be true or false"));
+
+    assert_eq!(
+        Expression::new("loading || hp == 0").check(&watches),
+        Ok(Type::Bool)
+    );
+    assert_eq!(
+        Expression::new("frames / 60").check(&watches),
+        Ok(Type::Int)
+    );
+}
+
+#[test]
+fn errors_print_with_a_caret() {
+    let source = "room == 1 && bos_hp == 0";
+    assert_eq!(
+        error(source).pointing_into(source),
+        "column 14: there's no watch or variable named 'bos_hp'\n    \
+         room == 1 && bos_hp == 0\n                 ^"
+    );
+}
+
+fn config(extra: &str) -> Result<AutosplitterConfig, String> {
+    AutosplitterConfig::from_json(&format!(
+        r#"{{
+            "target": {{ "kind": "retroarch" }},
+            "watches": [
+                {{ "name": "room", "address": "0x10", "value_type": "u8" }},
+                {{ "name": "hp", "address": "0x11", "value_type": "u8" }}
+            ],
+            {extra}
+        }}"#
+    ))
+}
+
+#[test]
+fn expressions_in_the_config_are_checked_at_load() {
+    assert!(config(r#""start": "room == 1 && old.room != 1""#).is_ok());
+    assert_eq!(
+        config(r#""reset": "room == 1 &&""#).unwrap_err(),
+        "reset: column 13: expected a value, found the end\n    \
+         room == 1 &&\n                ^"
+    );
+    assert!(
+        config(r#""splits": [{ "name": "A", "when": "room + 1" }]"#)
+            .unwrap_err()
+            .starts_with("split 0 ('A'): column 1: a condition must be true or false")
+    );
+    assert!(
+        config(
+            r#""triggers": [{ "name": "t", "action": "split", "when": { "any": ["hpp == 0"] } }]"#
+        )
+        .unwrap_err()
+        .starts_with("trigger 't': column 1: there's no watch or variable named 'hpp'")
+    );
+}
+
+#[test]
+fn variables_are_checked_in_order() {
+    let variables = |list: &str| config(&format!(r#""variables": [{list}]"#));
+    assert!(
+        variables(
+            r#"{ "name": "deaths", "initial": 0, "update": "old.deaths + (old.hp > 0 && hp == 0 ? 1 : 0)" },
+               { "name": "dead_twice", "initial": false, "update": "deaths >= 2" }"#
+        )
+        .is_ok()
+    );
+    assert!(
+        variables(r#"{ "name": "deaths", "initial": 0, "update": "deaths + 1" }"#)
+            .unwrap_err()
+            .starts_with(
+                "variable 'deaths': column 1: 'deaths' is what's being worked out here, use old.deaths"
+            )
+    );
+    assert!(
+        variables(
+            r#"{ "name": "a", "initial": 0, "update": "b" },
+               { "name": "b", "initial": 0, "update": "1" }"#
+        )
+        .unwrap_err()
+        .starts_with("variable 'a': column 1: 'b' is updated after this")
+    );
+    assert_eq!(
+        variables(r#"{ "name": "a", "initial": 0, "update": "hp > 0" }"#).unwrap_err(),
+        "variable 'a' starts as an integer but its update gives a bool"
+    );
+    assert_eq!(
+        variables(r#"{ "name": "room", "initial": 0, "update": "1" }"#).unwrap_err(),
+        "there's more than one watch or variable named 'room'"
+    );
+    assert_eq!(
+        variables(r#"{ "name": "old", "initial": 0, "update": "1" }"#).unwrap_err(),
+        "'old' can't be used as a variable name"
+    );
+}
+
+#[test]
+fn a_death_counter_drives_a_trigger_and_restarts_with_the_run() {
+    let config = config(
+        r#""variables": [
+            { "name": "deaths", "initial": 0, "update": "old.deaths + (old.hp > 0 && hp == 0 ? 1 : 0)" }
+        ],
+        "start": "room == 1 && old.room == 0",
+        "triggers": [{ "name": "third death", "action": "pause", "when": "deaths == 3" }]"#,
+    )
+    .unwrap();
+    let mut engine = Engine::new(config);
+    let sample = |room: i128, hp: i128| {
+        HashMap::from([
+            ("room".to_string(), Value::Int(room)),
+            ("hp".to_string(), Value::Int(hp)),
+        ])
+    };
+    let mut step = |room, hp| -> Vec<String> {
+        engine
+            .step(&sample(room, hp), || None)
+            .into_iter()
+            .map(|f| f.command)
+            .collect()
+    };
+
+    step(0, 10);
+    assert_eq!(step(1, 10), ["start"]);
+    for _ in 0..2 {
+        assert!(step(1, 0).is_empty());
+        assert!(step(1, 10).is_empty());
+    }
+    assert_eq!(step(1, 0), ["pause"]);
+    assert_eq!(engine.variable("deaths"), Some(&Value::Int(3)));
+}
+
+/// A float `speed`, a string `level` and a byte array `key`, alongside the
+/// integer `room`.
+struct Typed(HashMap<&'static str, Value>);
+
+impl Scope for Typed {
+    fn value(&self, name: &str, _old: bool) -> Option<Value> {
+        self.0.get(name).cloned()
+    }
+}
+
+fn typed_watches(name: &str, old: bool) -> Result<Type, String> {
+    match name {
+        "speed" => Ok(Type::Float),
+        "level" => Ok(Type::Str),
+        "key" => Ok(Type::Bytes),
+        _ => watches(name, old),
+    }
+}
+
+fn eval_typed(source: &str) -> Option<Value> {
+    let scope = Typed(HashMap::from([
+        ("room", Value::Int(3)),
+        ("speed", Value::Float(2.5)),
+        ("level", Value::Str("W1-2".to_string())),
+        ("key", Value::Bytes(vec![0xDE, 0xAD])),
+    ]));
+    let expression = Expression::new(source);
+    expression
+        .check(&typed_watches)
+        .unwrap_or_else(|e| panic!("{}", e.pointing_into(source)));
+    expression.eval(&scope)
+}
+
+fn typed_error(source: &str) -> ExprError {
+    Expression::new(source)
+        .check(&typed_watches)
+        .expect_err("should not check")
+}
+
+#[test]
+fn floats_mix_with_integers() {
+    assert_eq!(eval_typed("1.5"), Some(Value::Float(1.5)));
+    assert_eq!(eval_typed("speed * 2"), Some(Value::Float(5.0)));
+    assert_eq!(eval_typed("room + 0.5"), Some(Value::Float(3.5)));
+    assert_eq!(eval_typed("-speed"), Some(Value::Float(-2.5)));
+    assert_eq!(eval_typed("7 / 2"), Some(Value::Int(3)));
+    assert_eq!(eval_typed("7 / 2.0"), Some(Value::Float(3.5)));
+    assert_eq!(eval_typed("speed / 0"), None);
+    assert_eq!(eval_typed("speed > room"), Some(Value::Bool(false)));
+    assert_eq!(eval_typed("room == 3.0"), Some(Value::Bool(true)));
+    assert_eq!(
+        Expression::new("speed + room").check(&typed_watches),
+        Ok(Type::Float)
+    );
+}
+
+#[test]
+fn strings_and_bytes_compare_for_equality() {
+    assert_eq!(eval_typed(r#"level == "W1-2""#), Some(Value::Bool(true)));
+    assert_eq!(eval_typed(r#"level != "W1-3""#), Some(Value::Bool(true)));
+    assert_eq!(
+        eval_typed(r#""say \"hi\" \\ bye""#),
+        Some(Value::Str(r#"say "hi" \ bye"#.to_string()))
+    );
+    assert_eq!(eval_typed("key == key"), Some(Value::Bool(true)));
+
+    let e = typed_error(r#"level == "W1"#);
+    assert_eq!(
+        (e.column, e.message.as_str()),
+        (10, "this string has no closing '\"'")
+    );
+    let e = typed_error(r#"level == "W\n""#);
+    assert_eq!(e.column, 12);
+    assert!(e.message.starts_with("unknown escape '\\n'"));
+}
+
+#[test]
+fn each_operator_takes_the_types_that_make_sense() {
+    let e = typed_error("speed & 1");
+    assert_eq!(e.column, 1);
+    assert_eq!(e.message, "'&' needs an integer here, found a float");
+
+    let e = typed_error(r#"level < "W2""#);
+    assert_eq!(e.column, 1);
+    assert_eq!(e.message, "'<' needs a number here, found a string");
+
+    let e = typed_error("key == level");
+    assert_eq!(e.column, 5);
+    assert_eq!(e.message, "'==' can't compare a byte array with a string");
+
+    let e = typed_error("-level");
+    assert_eq!(e.column, 2);
+    assert_eq!(e.message, "'-' needs a number here, found a string");
+}
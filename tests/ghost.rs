@@ -0,0 +1,99 @@
+mod common;
+
+use chrono::Duration;
+use openspeedrun::Run;
+use openspeedrun::core::protocol::{Reply, handle_command};
+use openspeedrun::core::split::TimingMethod;
+
+use common::{app_with_run, run_from_attempts, secs};
+
+/// Splits A, B, C: attempt 1 finished 10/20/30, attempt 2 reset in C after
+/// 8/18, attempt 3 reset in A.
+fn run_with_resets() -> Run {
+    run_from_attempts(
+        &["A", "B", "C"],
+        &[
+            (&[Some(10), Some(20), Some(30)], true),
+            (&[Some(8), Some(18)], false),
+            (&[], false),
+        ],
+    )
+}
+
+fn ghost(run: &Run, name: &str) -> Vec<Option<Duration>> {
+    run.splits
+        .iter()
+        .map(|s| s.comparison_time(name, TimingMethod::RealTime))
+        .collect()
+}
+
+#[test]
+fn an_attempt_becomes_a_named_comparison() {
+    let mut run = run_with_resets();
+    assert_eq!(run.add_attempt_comparison(1).unwrap(), "Attempt #1");
+    assert!(run.comparison_names().contains(&"Attempt #1".to_string()));
+    assert_eq!(
+        ghost(&run, "Attempt #1"),
+        [Some(secs(10)), Some(secs(20)), Some(secs(30))]
+    );
+
+    // A reset attempt is a ghost up to where it stopped.
+    run.add_attempt_comparison(2).unwrap();
+    assert_eq!(
+        ghost(&run, "Attempt #2"),
+        [Some(secs(8)), Some(secs(18)), None]
+    );
+}
+
+#[test]
+fn attempts_without_segments_cant_be_ghosts() {
+    let mut run = run_with_resets();
+    assert_eq!(
+        run.add_attempt_comparison(3),
+        Err("attempt #3 has no segment times".to_string())
+    );
+    assert_eq!(
+        run.add_attempt_comparison(9),
+        Err("no attempt #9".to_string())
+    );
+    assert!(
+        !run.comparison_names()
+            .iter()
+            .any(|n| n.starts_with("Attempt #"))
+    );
+}
+
+#[test]
+fn ghost_command_saves_the_comparison() {
+    let (mut app, clock, dir) = app_with_run("command", run_with_resets());
+
+    assert_eq!(
+        handle_command(&mut app, "ghost"),
+        Reply::err("ghost needs an attempt number")
+    );
+    assert_eq!(
+        handle_command(&mut app, "ghost 9"),
+        Reply::err("no attempt #9")
+    );
+    assert_eq!(handle_command(&mut app, "ghost 1"), Reply::ok());
+
+    let saved = Run::load_from_file(dir.join("split.json").to_str().unwrap()).unwrap();
+    assert_eq!(
+        ghost(&saved, "Attempt #1"),
+        [Some(secs(10)), Some(secs(20)), Some(secs(30))]
+    );
+    assert!(
+        app.splits_display
+            .iter()
+            .all(|s| s.comparisons.contains_key("Attempt #1"))
+    );
+
+    app.start_timers();
+    clock.advance_ms(1_000);
+    assert_eq!(
+        handle_command(&mut app, "ghost 2"),
+        Reply::err("can't add a comparison during a run")
+    );
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -81,3 +81,25 @@ fn delta_series_is_empty_and_live_delta_is_zero_before_any_comparison_exists() {
     assert_eq!(app.delta_series(0.0), Vec::new());
     assert_eq!(app.live_delta(0.0), 0.0);
 }
+
+#[test]
+fn delta_after_a_skip_measures_the_combined_segment_against_both_comparisons() {
+    let mut app = AppState {
+        run: Run::new("Game", "Any%", &["A", "B", "C"]),
+        ..AppState::empty_for_test()
+    };
+    for (i, pb) in [10_000, 20_000, 30_000].into_iter().enumerate() {
+        app.run.splits[i]
+            .comparisons
+            .get_mut(COMPARISON_PERSONAL_BEST)
+            .unwrap()
+            .real_time = Some(ms(pb));
+    }
+
+    app.splits_display = app.run.splits.clone();
+    // A was skipped; B was reached at 28s, i.e. 2s ahead of A+B's 30s.
+    app.splits_display[1].last_time = Some(ms(28_000));
+    app.current_split = 2;
+
+    assert_eq!(app.delta_series(0.0), vec![(1, -2.0), (2, -32.0)]);
+}
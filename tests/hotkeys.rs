@@ -0,0 +1,175 @@
+#![cfg(target_os = "linux")]
+
+use openspeedrun::config::keys::{KeyCombo, KeyWrapper, Modifiers};
+use openspeedrun::config::layout::Hotkeys;
+use openspeedrun::core::evdev::{
+    Chord, EventReader, HotkeyMatcher, InputEvent, KEY_LEFTCTRL, KEY_LEFTSHIFT, KEY_PRESSED,
+    KEY_RELEASED, KEY_REPEATED, KEY_RIGHTCTRL, command_for, key_code, keyboard_devices,
+};
+
+const KEY_S: u16 = 31;
+const KEY_F1: u16 = 59;
+const SYN_REPORT: InputEvent = InputEvent {
+    kind: 0,
+    code: 0,
+    value: 0,
+};
+
+fn press(code: u16) -> InputEvent {
+    InputEvent::key(code, KEY_PRESSED)
+}
+
+fn release(code: u16) -> InputEvent {
+    InputEvent::key(code, KEY_RELEASED)
+}
+
+/// Runs a synthetic device's events through the reader, matcher and
+/// bindings, like the listener does.
+fn commands_for_stream(hotkeys: &Hotkeys, events: &[InputEvent]) -> Vec<&'static str> {
+    let bytes: Vec<u8> = events.iter().flat_map(|e| e.to_bytes()).collect();
+    let mut matcher = HotkeyMatcher::default();
+    EventReader::new(bytes.as_slice())
+        .filter_map(|e| matcher.feed(e))
+        .filter_map(|chord| command_for(hotkeys, chord))
+        .collect()
+}
+
+fn hotkeys() -> Hotkeys {
+    Hotkeys {
+        split: KeyWrapper("F1".into()),
+        save_pb: KeyWrapper("Ctrl+S".into()),
+        reset: KeyWrapper("CTRL+SHIFT+S".into()),
+        ..Hotkeys::default()
+    }
+}
+
+#[test]
+fn reader_decodes_raw_input_events_and_stops_at_a_partial_one() {
+    let events = [press(KEY_F1), SYN_REPORT, InputEvent::key(KEY_F1, -7)];
+    let mut bytes: Vec<u8> = events.iter().flat_map(|e| e.to_bytes()).collect();
+    bytes.extend_from_slice(&[1, 2, 3]);
+
+    let read: Vec<InputEvent> = EventReader::new(bytes.as_slice()).collect();
+    assert_eq!(read, events);
+}
+
+#[test]
+fn combos_parse_and_print_back() {
+    let combo = KeyCombo::parse("shift + control+f1").unwrap();
+    assert_eq!(
+        combo,
+        KeyCombo {
+            modifiers: Modifiers {
+                ctrl: true,
+                shift: true,
+                ..Modifiers::default()
+            },
+            key: "F1".into(),
+        }
+    );
+    assert_eq!(combo.to_string(), "CTRL+SHIFT+F1");
+    assert_eq!(
+        KeyCombo::parse("Super+Space").unwrap().to_string(),
+        "META+SPACE"
+    );
+    assert_eq!(KeyCombo::parse(""), None);
+    assert_eq!(KeyCombo::parse("CTRL+"), None);
+    assert_eq!(KeyCombo::parse("HYPER+A"), None);
+    assert_eq!(key_code("arrowleft"), key_code("LEFT"));
+    assert_eq!(key_code("Num1"), Some(2));
+    assert_eq!(key_code("NOPE"), None);
+}
+
+#[test]
+fn plain_key_fires_once_per_press_ignoring_autorepeat() {
+    let stream = [
+        press(KEY_F1),
+        SYN_REPORT,
+        InputEvent::key(KEY_F1, KEY_REPEATED),
+        InputEvent::key(KEY_F1, KEY_REPEATED),
+        release(KEY_F1),
+        press(KEY_F1),
+        release(KEY_F1),
+    ];
+    assert_eq!(commands_for_stream(&hotkeys(), &stream), ["split", "split"]);
+}
+
+#[test]
+fn modifiers_must_match_exactly() {
+    let stream = [
+        // S alone: nothing is bound to it.
+        press(KEY_S),
+        release(KEY_S),
+        // Ctrl+S
+        press(KEY_LEFTCTRL),
+        press(KEY_S),
+        release(KEY_S),
+        // Ctrl+Shift+S, with the right Ctrl this time.
+        release(KEY_LEFTCTRL),
+        press(KEY_RIGHTCTRL),
+        press(KEY_LEFTSHIFT),
+        press(KEY_S),
+        release(KEY_S),
+        // F1 with Shift still down isn't the bare F1 binding.
+        release(KEY_RIGHTCTRL),
+        press(KEY_F1),
+        release(KEY_F1),
+        // Everything up: F1 again.
+        release(KEY_LEFTSHIFT),
+        press(KEY_F1),
+    ];
+    assert_eq!(
+        commands_for_stream(&hotkeys(), &stream),
+        ["savepb", "reset", "split"]
+    );
+}
+
+#[test]
+fn matcher_reports_held_modifiers_with_each_chord() {
+    let mut matcher = HotkeyMatcher::default();
+    assert_eq!(matcher.feed(press(KEY_LEFTSHIFT)), None);
+    assert_eq!(
+        matcher.feed(press(KEY_S)),
+        Some(Chord {
+            modifiers: Modifiers {
+                shift: true,
+                ..Modifiers::default()
+            },
+            code: KEY_S,
+        })
+    );
+    assert_eq!(matcher.feed(release(KEY_S)), None);
+    assert_eq!(matcher.feed(release(KEY_LEFTSHIFT)), None);
+    assert_eq!(matcher.modifiers(), Modifiers::default());
+}
+
+#[test]
+fn only_keyboards_are_picked_from_proc_devices() {
+    let proc_devices = "\
+I: Bus=0019 Vendor=0000 Product=0001 Version=0000
+N: Name=\"Power Button\"
+H: Handlers=kbd event0
+B: EV=3
+B: KEY=10000000000000 0
+
+I: Bus=0011 Vendor=0001 Product=0001 Version=ab41
+N: Name=\"AT Translated Set 2 keyboard\"
+H: Handlers=sysrq kbd event3 leds
+B: EV=120013
+B: KEY=402000000 3803078f800d001 feffffdfffefffff fffffffffffffffe
+
+I: Bus=0003 Vendor=046d Product=c52b Version=0111
+N: Name=\"Logitech USB Receiver Mouse\"
+H: Handlers=mouse0 event5
+B: EV=17
+
+I: Bus=0003 Vendor=046d Product=c52b Version=0111
+N: Name=\"Logitech USB Receiver Keyboard\"
+H: Handlers=sysrq kbd leds event6
+B: EV=12001f
+";
+    assert_eq!(
+        keyboard_devices(proc_devices),
+        ["/dev/input/event3", "/dev/input/event6"]
+    );
+}
@@ -0,0 +1,133 @@
+mod common;
+
+use chrono::Duration;
+use openspeedrun::Run;
+use openspeedrun::app::overlay::build_snapshot;
+use openspeedrun::core::split::{
+    COMPARISON_BEST_SEGMENTS, COMPARISON_PERSONAL_BEST, SegmentHistoryEntry,
+};
+use openspeedrun::core::stats::{pb_chance, quantile};
+
+use common::{app_with_run, run_from_attempts, secs};
+
+/// Splits A and B, PB 10s + 10s, golds 8s + 9s, three past attempts:
+/// 9/9, 10/10 and 11/12.
+fn two_split_run() -> Run {
+    let mut run = run_from_attempts(
+        &["A", "B"],
+        &[
+            (&[Some(9), Some(9)], true),
+            (&[Some(10), Some(10)], true),
+            (&[Some(11), Some(12)], true),
+        ],
+    );
+    for (split, gold) in run.splits.iter_mut().zip([8, 9]) {
+        split
+            .comparisons
+            .entry(COMPARISON_PERSONAL_BEST.to_string())
+            .or_default()
+            .real_time = Some(secs(10));
+        split
+            .comparisons
+            .entry(COMPARISON_BEST_SEGMENTS.to_string())
+            .or_default()
+            .real_time = Some(secs(gold));
+    }
+    run
+}
+
+#[test]
+fn quantile_interpolates_between_neighbours() {
+    let times = [secs(10), secs(20), secs(40)];
+    assert_eq!(quantile(&times, 0.0), Some(secs(10)));
+    assert_eq!(quantile(&times, 0.25), Some(secs(15)));
+    assert_eq!(quantile(&times, 0.75), Some(secs(30)));
+    assert_eq!(quantile(&times, 1.0), Some(secs(40)));
+    assert_eq!(quantile(&times[..1], 0.6), Some(secs(10)));
+    assert_eq!(quantile(&[], 0.5), None);
+}
+
+#[test]
+fn pb_chance_is_the_highest_percentile_still_finishing_in_time() {
+    let remaining = vec![vec![secs(10), secs(20)], vec![secs(10), secs(30)]];
+    // At percentile p the run takes 20s + 30s * p.
+    let chance = pb_chance(Duration::zero(), &remaining, secs(35)).unwrap();
+    assert!((chance - 0.5).abs() < 1e-3, "{chance}");
+    assert_eq!(pb_chance(secs(5), &remaining, secs(24)), Some(0.0));
+    assert_eq!(pb_chance(Duration::zero(), &remaining, secs(50)), Some(1.0));
+    assert_eq!(pb_chance(secs(5), &[], secs(6)), Some(1.0));
+    assert_eq!(
+        pb_chance(Duration::zero(), &[vec![secs(1)], vec![]], secs(60)),
+        None
+    );
+}
+
+#[test]
+fn info_rows_follow_the_attempt() {
+    let (mut app, clock, dir) = app_with_run("rows", two_split_run());
+
+    // Before starting: everything is the comparison.
+    assert_eq!(app.sum_of_best(), secs(17));
+    assert_eq!(app.possible_time_save(), Some(secs(2)));
+    assert_eq!(app.current_pace(), Some(secs(20)));
+    assert_eq!(app.total_playtime(), secs(61));
+    let chance = app.pb_chance().unwrap();
+    assert!((chance - 0.5).abs() < 1e-3, "{chance}");
+    assert_eq!(app.previous_segment(), None);
+
+    app.start_timers();
+    clock.advance_ms(12_000);
+    // Two seconds over A's comparison already: pace counts the live time.
+    assert_eq!(app.current_pace(), Some(secs(22)));
+    assert_eq!(app.total_playtime(), secs(73));
+
+    app.split();
+    let previous = app.previous_segment().unwrap();
+    assert_eq!(previous.delta, secs(2));
+    assert!(!previous.is_gold);
+    assert_eq!(app.possible_time_save(), Some(secs(1)));
+    assert_eq!(app.best_possible(), secs(21));
+    // B would need to beat its own best.
+    assert_eq!(app.pb_chance(), Some(0.0));
+
+    clock.advance_ms(8_000);
+    app.split();
+    let previous = app.previous_segment().unwrap();
+    assert_eq!(previous.delta, secs(-2));
+    assert!(previous.is_gold);
+    assert_eq!(app.possible_time_save(), None);
+    assert_eq!(app.current_pace(), Some(secs(20)));
+    assert_eq!(app.total_playtime(), secs(81));
+
+    let snapshot = build_snapshot(&app);
+    assert_eq!(snapshot.previous_segment_delta_ms, Some(-2_000));
+    assert!(snapshot.previous_segment_is_gold);
+    assert_eq!(snapshot.current_pace_ms, Some(20_000));
+    assert_eq!(snapshot.total_playtime_ms, 81_000);
+    assert_eq!(snapshot.possible_time_save_ms, None);
+
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn pb_chance_needs_a_pb_and_history_for_every_remaining_split() {
+    let (mut app, _clock, dir) = app_with_run("chance", two_split_run());
+
+    app.run.splits[1].segment_history.clear();
+    assert_eq!(app.pb_chance(), None);
+
+    app.run.splits[1].segment_history = vec![SegmentHistoryEntry {
+        run_index: 1,
+        real_time: Some(secs(10)),
+        ..Default::default()
+    }];
+    app.run.splits[0]
+        .comparisons
+        .get_mut(COMPARISON_PERSONAL_BEST)
+        .unwrap()
+        .real_time = None;
+    assert_eq!(app.pb_chance(), None);
+    assert_eq!(build_snapshot(&app).pb_chance, None);
+
+    std::fs::remove_dir_all(&dir).ok();
+}
@@ -0,0 +1,163 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use chrono::Duration;
+use openspeedrun::core::journal::{AttemptJournal, IgtPause, JournalTimerState};
+use openspeedrun::core::time_source::FakeClock;
+use openspeedrun::{AppState, Run};
+
+fn ms(n: i64) -> Duration {
+    Duration::milliseconds(n)
+}
+
+/// An `AppState` on `dir`'s `split.json` as it would come up at startup —
+/// the run and any leftover journal read back from disk.
+fn app_from_disk(dir: &Path, clock: &FakeClock) -> AppState {
+    let run = Run::load_from_file(dir.join("split.json").to_str().unwrap()).unwrap();
+    let mut app = AppState {
+        splits_display: run.splits.clone(),
+        splits_backup: run.splits.clone(),
+        run,
+        split_base_path: dir.to_path_buf(),
+        pending_journal: AttemptJournal::load(dir),
+        ..AppState::empty_for_test()
+    };
+    app.set_clock(Arc::new(clock.clone()));
+    app
+}
+
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("osr_journal_{name}_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    Run::new("Game", "Any%", &["A", "B", "C"])
+        .save_to_file(dir.join("split.json").to_str().unwrap())
+        .unwrap();
+    dir
+}
+
+/// Starts an attempt, splits A at 10s, skips B, and is mid-load (IGT
+/// stopped since 12s) at 15s — then "crashes".
+fn interrupted_attempt(dir: &Path, clock: &FakeClock) {
+    let mut app = app_from_disk(dir, clock);
+    app.split();
+    clock.advance_ms(10_000);
+    app.split();
+    app.skip_split();
+    clock.advance_ms(2_000);
+    app.toggle_igt_pause();
+    clock.advance_ms(3_000);
+    app.pause_timers();
+    app.start_timers();
+}
+
+#[test]
+fn the_journal_tracks_the_live_attempt_and_goes_away_on_reset() {
+    let dir = scratch_dir("tracks");
+    let clock = FakeClock::default();
+    interrupted_attempt(&dir, &clock);
+
+    let journal = AttemptJournal::load(&dir).expect("no journal written");
+    assert_eq!(journal.run_index, 1);
+    assert_eq!(journal.timer_state, JournalTimerState::Running);
+    assert_eq!(journal.current_split, 2);
+    assert_eq!(journal.real_elapsed_ms, 15_000);
+    assert_eq!(journal.game_elapsed_ms, 12_000);
+    assert_eq!(journal.splits[0].real_time, Some(ms(10_000)));
+    assert_eq!(journal.splits[1].real_time, None);
+    assert_eq!(
+        journal.igt_pauses,
+        vec![IgtPause {
+            start_ms: 12_000,
+            end_ms: None
+        }]
+    );
+    assert_eq!(journal.started_at, Some(chrono::DateTime::UNIX_EPOCH));
+
+    let mut app = app_from_disk(&dir, &clock);
+    app.resume_interrupted_attempt();
+    app.reset_splits();
+    assert!(AttemptJournal::load(&dir).is_none());
+
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn resuming_restores_splits_and_credits_real_time_spent_down() {
+    let dir = scratch_dir("resume");
+    let clock = FakeClock::default();
+    interrupted_attempt(&dir, &clock);
+
+    clock.advance_ms(30_000); // time to restart the app
+    let mut app = app_from_disk(&dir, &clock);
+    assert!(app.pending_journal.is_some());
+    app.resume_interrupted_attempt();
+
+    assert!(app.pending_journal.is_none());
+    assert_eq!(app.current_split, 2);
+    assert_eq!(app.run.attempts, 1);
+    assert_eq!(app.splits_display[0].last_time, Some(ms(10_000)));
+    assert!(app.attempt_has_skipped_splits());
+    assert!(app.timer.is_running());
+    assert_eq!(app.timer.current_time(), ms(45_000));
+    // Still loading: game time didn't move.
+    assert!(app.igt_timer.is_paused());
+    assert_eq!(app.igt_timer.current_time(), ms(12_000));
+
+    app.toggle_igt_pause();
+    clock.advance_ms(5_000);
+    app.split(); // finish the run
+    assert!(app.timer.is_ended());
+    assert_eq!(app.splits_display[2].last_time, Some(ms(50_000)));
+    assert_eq!(app.splits_display[2].last_time_game, Some(ms(17_000)));
+    assert_eq!(app.run.splits[2].segment_history[0].run_index, 1);
+    assert!(AttemptJournal::load(&dir).is_none());
+
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn an_interrupted_attempt_can_be_recorded_as_a_reset() {
+    let dir = scratch_dir("as_reset");
+    let clock = FakeClock::default();
+    interrupted_attempt(&dir, &clock);
+
+    clock.advance_ms(30_000);
+    let mut app = app_from_disk(&dir, &clock);
+    app.record_interrupted_attempt_as_reset();
+
+    assert!(AttemptJournal::load(&dir).is_none());
+    let saved = Run::load_from_file(dir.join("split.json").to_str().unwrap()).unwrap();
+    assert_eq!(saved.attempts, 1);
+    let attempt = &saved.attempt_history[0];
+    assert_eq!(attempt.run_index, 1);
+    assert!(!attempt.ended);
+    assert_eq!(attempt.real_time, Some(ms(15_000)));
+    assert_eq!(attempt.reset_split_index, Some(2));
+    assert_eq!(
+        attempt.date,
+        Some(chrono::DateTime::UNIX_EPOCH + ms(15_000))
+    );
+
+    // The next attempt gets the next number.
+    app.split();
+    assert_eq!(app.run.attempts, 2);
+
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn starting_a_new_attempt_records_an_unresolved_one_as_reset() {
+    let dir = scratch_dir("start_over");
+    let clock = FakeClock::default();
+    interrupted_attempt(&dir, &clock);
+
+    let mut app = app_from_disk(&dir, &clock);
+    app.split();
+
+    assert!(app.pending_journal.is_none());
+    assert_eq!(app.run.attempts, 2);
+    assert_eq!(app.run.attempt_history.len(), 1);
+    assert_eq!(AttemptJournal::load(&dir).unwrap().run_index, 2);
+
+    std::fs::remove_dir_all(&dir).ok();
+}
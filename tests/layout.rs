@@ -0,0 +1,125 @@
+use openspeedrun::config::layout::{
+    Component, FooterSettings, GraphSettings, LayoutConfig, SplitsSettings, TimerSettings,
+    TitleSettings,
+};
+
+fn load(name: &str, json: &str) -> LayoutConfig {
+    let dir = std::env::temp_dir().join(format!("osr_layout_{name}_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("theme.json");
+    std::fs::write(&path, json).unwrap();
+    let layout = LayoutConfig::load_or_default(path.to_str().unwrap());
+    std::fs::remove_dir_all(&dir).ok();
+    layout
+}
+
+#[test]
+fn legacy_sections_and_show_options_become_components() {
+    let layout = load(
+        "legacy",
+        r#"{
+            "options": {
+                "show_title": false,
+                "show_category": true,
+                "show_footer": false,
+                "show_graph": true,
+                "show_info": false,
+                "show_relative_times": true,
+                "section_order": ["Graph", "Timer", "Title", "Splits", "Footer"],
+                "titlebar": false
+            }
+        }"#,
+    );
+
+    assert_eq!(
+        layout.components,
+        [
+            Component::Graph(GraphSettings::default()),
+            Component::Timer(TimerSettings::default()),
+            Component::Title(TitleSettings {
+                show_title: false,
+                show_category: true,
+            }),
+            Component::Splits(SplitsSettings {
+                show_rows: true,
+                show_relative_times: true,
+                show_last_relative_time: false,
+                collapse_sections: true,
+            }),
+        ]
+    );
+    // Everything else in `options` still loads.
+    assert!(!layout.options.titlebar);
+}
+
+#[test]
+fn legacy_theme_without_graph_or_body_keeps_the_splits_slot() {
+    let layout = load(
+        "legacy_no_graph",
+        r#"{
+            "options": {
+                "show_body": false,
+                "show_graph": true,
+                "section_order": ["Title", "Timer", "Splits", "Footer"]
+            }
+        }"#,
+    );
+
+    assert_eq!(
+        layout.components,
+        [
+            Component::Title(TitleSettings::default()),
+            Component::Timer(TimerSettings::default()),
+            Component::Splits(SplitsSettings {
+                show_rows: false,
+                ..SplitsSettings::default()
+            }),
+            Component::Footer(FooterSettings::default()),
+            Component::Graph(GraphSettings::default()),
+        ]
+    );
+}
+
+#[test]
+fn legacy_defaults_match_the_new_default_layout() {
+    let layout = load("legacy_defaults", r#"{ "options": {} }"#);
+    assert_eq!(layout.components, LayoutConfig::default().components);
+}
+
+#[test]
+fn components_round_trip_with_repeats_and_partial_settings() {
+    let layout = load(
+        "components",
+        r#"{
+            "components": [
+                { "type": "Timer", "show_secondary": false },
+                { "type": "Graph", "height": 120.0 },
+                { "type": "Splits" },
+                { "type": "Graph" },
+                { "type": "Title", "show_title": false }
+            ],
+            "options": { "section_order": ["Title"], "show_title": true }
+        }"#,
+    );
+
+    let expected = [
+        Component::Timer(TimerSettings {
+            show_secondary: false,
+        }),
+        Component::Graph(GraphSettings { height: 120.0 }),
+        Component::Splits(SplitsSettings::default()),
+        Component::Graph(GraphSettings::default()),
+        Component::Title(TitleSettings {
+            show_title: false,
+            show_category: true,
+        }),
+    ];
+    // An explicit `components` wins over any leftover legacy options.
+    assert_eq!(layout.components, expected);
+
+    let json = serde_json::to_value(&layout).unwrap();
+    assert!(json["options"].get("section_order").is_none());
+    assert_eq!(json["components"][1]["type"], "Graph");
+    let reloaded = load("components_reload", &json.to_string());
+    assert_eq!(reloaded.components, expected);
+}
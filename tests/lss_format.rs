@@ -33,6 +33,8 @@ fn sample_run() -> Run {
         game_time: None,
         ended: true,
         date: Some(whole_second_now()),
+        reset_split_index: None,
+        ..Default::default()
     });
 
     run.splits[0]
@@ -49,6 +51,7 @@ fn sample_run() -> Run {
         run_index: 0,
         real_time: Some(ms(30_000)),
         game_time: None,
+        combined: false,
     });
 
     run.splits[1]
@@ -65,6 +68,7 @@ fn sample_run() -> Run {
         run_index: 0,
         real_time: Some(ms(60_000)),
         game_time: None,
+        combined: false,
     });
 
     run
@@ -318,3 +322,213 @@ fn import_rejects_a_run_with_no_segments() {
 
     std::fs::remove_dir_all(&dir).ok();
 }
+
+#[test]
+fn skipped_splits_round_trip_as_empty_times_and_combined_segments() {
+    let mut run = Run::new("Test Game", "Any%", &["A", "B"]);
+    run.splits[0].segment_history.push(SegmentHistoryEntry {
+        run_index: 3,
+        real_time: None,
+        game_time: None,
+        combined: false,
+    });
+    run.splits[1].segment_history.push(SegmentHistoryEntry {
+        run_index: 3,
+        real_time: Some(ms(45_000)),
+        game_time: None,
+        combined: true,
+    });
+
+    let dir = scratch_dir("skipped");
+    let lss_path = dir.join("test.lss");
+    lss::export(&run, &lss_path, &dir).expect("export failed");
+
+    let xml = std::fs::read_to_string(&lss_path).unwrap();
+    assert!(xml.contains("<Time id=\"3\" />"));
+
+    let imported = lss::import(&lss_path, &dir.join("icons"))
+        .expect("import failed")
+        .run;
+    let a = &imported.splits[0].segment_history;
+    assert_eq!(a.len(), 1);
+    assert_eq!(a[0].real_time, None);
+    assert!(!a[0].combined);
+
+    let b = &imported.splits[1].segment_history;
+    assert_eq!(b[0].real_time, Some(ms(45_000)));
+    assert!(b[0].combined);
+
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn reset_attempts_round_trip_with_elapsed_time_and_reset_split() {
+    let mut run = Run::new("Test Game", "Any%", &["A", "B", "C"]);
+    run.attempts = 1;
+    run.attempt_history.push(AttemptHistoryEntry {
+        run_index: 1,
+        real_time: Some(ms(42_400)),
+        game_time: Some(ms(40_150)),
+        ended: false,
+        date: Some(whole_second_now()),
+        reset_split_index: Some(1),
+        ..Default::default()
+    });
+    run.splits[0].segment_history.push(SegmentHistoryEntry {
+        run_index: 1,
+        real_time: Some(ms(30_000)),
+        game_time: None,
+        combined: false,
+    });
+
+    let dir = scratch_dir("reset");
+    let lss_path = dir.join("test.lss");
+    lss::export(&run, &lss_path, &dir).expect("export failed");
+
+    let imported = lss::import(&lss_path, &dir.join("icons"))
+        .expect("import failed")
+        .run;
+    let attempt = &imported.attempt_history[0];
+    assert!(!attempt.ended);
+    assert_eq!(attempt.date, run.attempt_history[0].date);
+    assert_eq!(attempt.real_time, Some(ms(42_400)));
+    assert_eq!(attempt.game_time, Some(ms(40_150)));
+    assert_eq!(attempt.reset_split_index, Some(1));
+
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn livesplit_reset_attempts_take_their_time_from_the_stamps() {
+    let dir = scratch_dir("livesplit_reset");
+    let lss_path = dir.join("test.lss");
+    std::fs::write(
+        &lss_path,
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<Run version="1.7.0">
+  <GameName>Test Game</GameName>
+  <CategoryName>Any%</CategoryName>
+  <AttemptCount>1</AttemptCount>
+  <AttemptHistory>
+    <Attempt id="1" started="01/02/2024 10:00:00" isStartedSynced="True" ended="01/02/2024 10:01:05" isEndedSynced="True" />
+  </AttemptHistory>
+  <Segments>
+    <Segment>
+      <Name>A</Name>
+      <SegmentHistory>
+        <Time id="1"><RealTime>00:00:30</RealTime></Time>
+      </SegmentHistory>
+    </Segment>
+    <Segment>
+      <Name>B</Name>
+      <SegmentHistory>
+        <Time id="1"><RealTime>00:00:30</RealTime></Time>
+      </SegmentHistory>
+    </Segment>
+  </Segments>
+</Run>
+"#,
+    )
+    .unwrap();
+
+    let imported = lss::import(&lss_path, &dir.join("icons"))
+        .expect("import failed")
+        .run;
+    let attempt = &imported.attempt_history[0];
+    assert!(!attempt.ended);
+    assert_eq!(attempt.real_time, Some(ms(65_000)));
+    // A time on every split still leaves it reset on a real split.
+    assert_eq!(attempt.reset_split_index, Some(1));
+
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+fn sectioned_run() -> Run {
+    let mut run = Run::new(
+        "Test Game",
+        "Any%",
+        &["Cave", "Boss 1", "Town", "Climb", "Tower", "Ending"],
+    );
+    for (index, section) in [
+        (0, "World 1"),
+        (1, "World 1"),
+        (3, "Tower"),
+        (4, "Tower"),
+        (5, "Finale"),
+    ] {
+        run.splits[index].section = Some(section.to_string());
+    }
+    run
+}
+
+#[test]
+fn sections_export_as_livesplit_subsplits_and_round_trip() {
+    let run = sectioned_run();
+    let dir = scratch_dir("sections");
+    let lss_path = dir.join("test.lss");
+    lss::export(&run, &lss_path, &dir).expect("export failed");
+
+    let xml = std::fs::read_to_string(&lss_path).unwrap();
+    let names: Vec<&str> = xml
+        .lines()
+        .filter_map(|l| l.trim().strip_prefix("<Name>"))
+        .filter_map(|l| l.strip_suffix("</Name>"))
+        .collect();
+    assert_eq!(
+        names,
+        [
+            "-Cave",
+            "{World 1} Boss 1",
+            "Town",
+            "-Climb",
+            "Tower",
+            "{Finale} Ending"
+        ]
+    );
+
+    let imported = lss::import(&lss_path, &dir.join("icons"))
+        .expect("import failed")
+        .run;
+    for (imported, original) in imported.splits.iter().zip(&run.splits) {
+        assert_eq!(imported.name, original.name);
+        assert_eq!(imported.section, original.section);
+    }
+
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn import_groups_dash_prefixed_subsplits_under_the_closing_split() {
+    let dir = scratch_dir("subsplits");
+    let path = dir.join("subsplits.lss");
+    let segments: String = ["-Key", "- Door", "Dungeon", "Overworld", "-Loose", "-End"]
+        .iter()
+        .map(|name| format!("<Segment><Name>{name}</Name></Segment>"))
+        .collect();
+    std::fs::write(
+        &path,
+        format!("<Run version=\"1.7.0\"><Segments>{segments}</Segments></Run>"),
+    )
+    .unwrap();
+
+    let run = lss::import(&path, &dir.join("icons")).unwrap().run;
+    let names: Vec<(&str, Option<&str>)> = run
+        .splits
+        .iter()
+        .map(|s| (s.name.as_str(), s.section.as_deref()))
+        .collect();
+    assert_eq!(
+        names,
+        [
+            ("Key", Some("Dungeon")),
+            ("Door", Some("Dungeon")),
+            ("Dungeon", Some("Dungeon")),
+            ("Overworld", None),
+            // Never closed: the group is named after its last subsplit.
+            ("Loose", Some("End")),
+            ("End", Some("End")),
+        ]
+    );
+
+    std::fs::remove_dir_all(&dir).ok();
+}
@@ -0,0 +1,134 @@
+use chrono::Duration;
+use openspeedrun::Run;
+use openspeedrun::core::outliers::OutlierReason;
+use openspeedrun::core::split::{
+    AttemptHistoryEntry, COMPARISON_BEST_SEGMENTS, COMPARISON_PERSONAL_BEST, SegmentHistoryEntry,
+    TimingMethod,
+};
+use openspeedrun::core::stats::robust_z_score;
+
+fn ms(n: i64) -> Duration {
+    Duration::milliseconds(n)
+}
+
+/// Splits A, B, C; one finished attempt per row of segment times in ms
+/// (`None` = skipped, making the next segment a combined one).
+fn run_with_attempts(attempts: &[[Option<i64>; 3]]) -> Run {
+    let mut run = Run::new("Game", "Any%", &["A", "B", "C"]);
+    for (attempt, segments) in attempts.iter().enumerate() {
+        let run_index = attempt as u32 + 1;
+        for (i, (split, segment)) in run.splits.iter_mut().zip(segments).enumerate() {
+            split.segment_history.push(SegmentHistoryEntry {
+                run_index,
+                real_time: segment.map(ms),
+                combined: i > 0 && segments[i - 1].is_none(),
+                ..Default::default()
+            });
+        }
+        run.attempt_history.push(AttemptHistoryEntry {
+            run_index,
+            real_time: Some(ms(segments.iter().flatten().sum())),
+            ended: true,
+            ..Default::default()
+        });
+    }
+    for split in &mut run.splits {
+        split.recompute_best_segment();
+    }
+    run.recompute_personal_best();
+    run.attempts = attempts.len() as u32;
+    run
+}
+
+#[test]
+fn robust_z_score_ignores_the_outlier_itself() {
+    let mut times: Vec<Duration> = [30_000, 31_000, 29_500, 30_500, 32_000, 200]
+        .into_iter()
+        .map(ms)
+        .collect();
+    times.sort_unstable();
+
+    let z = robust_z_score(ms(200), &times).unwrap();
+    assert!(z < -10.0, "z = {z}");
+    let z = robust_z_score(ms(31_000), &times).unwrap();
+    assert!(z.abs() < 1.5, "z = {z}");
+
+    assert_eq!(robust_z_score(ms(1), &[]), None);
+    assert_eq!(robust_z_score(ms(1), &[ms(5), ms(5), ms(5)]), None);
+}
+
+#[test]
+fn a_double_tapped_split_is_flagged() {
+    let run = run_with_attempts(&[
+        [Some(10_000), Some(30_000), Some(20_000)],
+        [Some(10_500), Some(31_000), Some(21_000)],
+        [Some(9_800), Some(29_500), Some(19_000)],
+        [Some(10_200), Some(150), Some(50_000)],
+        [Some(10_100), Some(30_500), Some(20_500)],
+        [Some(9_900), Some(32_000), Some(20_000)],
+    ]);
+
+    let outliers = run.segment_outliers(TimingMethod::RealTime);
+    let flagged: Vec<_> = outliers
+        .iter()
+        .map(|o| (o.split_index, o.run_index, o.time))
+        .collect();
+    assert_eq!(flagged, [(1, 4, ms(150)), (2, 4, ms(50_000))]);
+    match &outliers[0].reason {
+        OutlierReason::FarFromMedian { z, median } => {
+            assert!(*z < -3.5);
+            assert_eq!(*median, ms(30_250));
+        }
+        other => panic!("unexpected reason {other:?}"),
+    }
+}
+
+#[test]
+fn too_little_history_flags_nothing() {
+    let run = run_with_attempts(&[
+        [Some(10_000), Some(30_000), Some(20_000)],
+        [Some(10_000), Some(150), Some(20_000)],
+        [Some(10_000), Some(31_000), Some(20_000)],
+    ]);
+    assert!(run.segment_outliers(TimingMethod::RealTime).is_empty());
+}
+
+#[test]
+fn combined_segment_faster_than_its_best_segments_is_flagged() {
+    let run = run_with_attempts(&[
+        [Some(10_000), Some(30_000), Some(20_000)],
+        [Some(10_000), None, Some(45_000)],
+        [Some(10_000), None, Some(52_000)],
+    ]);
+
+    let outliers = run.segment_outliers(TimingMethod::RealTime);
+    assert_eq!(outliers.len(), 1);
+    assert_eq!((outliers[0].split_index, outliers[0].run_index), (2, 2));
+    assert_eq!(
+        outliers[0].reason,
+        OutlierReason::FasterThanBestSegments {
+            span_start: 1,
+            sum_of_best: ms(50_000),
+        }
+    );
+}
+
+#[test]
+fn removing_an_outlier_recomputes_golds_and_pb() {
+    let mut run = run_with_attempts(&[
+        [Some(10_000), Some(30_000), Some(20_000)],
+        [Some(10_000), Some(150), Some(20_000)],
+    ]);
+    let gold =
+        |run: &Run| run.splits[1].comparison_time(COMPARISON_BEST_SEGMENTS, TimingMethod::RealTime);
+    assert_eq!(gold(&run), Some(ms(150)));
+
+    run.remove_segment_entries(&[(1, 2)]);
+    assert_eq!(gold(&run), Some(ms(30_000)));
+    assert_eq!(run.splits[1].segment_history.len(), 1);
+    // Attempt 2 no longer has a time on every split, so attempt 1 is the PB.
+    assert_eq!(
+        run.splits[1].comparison_time(COMPARISON_PERSONAL_BEST, TimingMethod::RealTime),
+        Some(ms(30_000))
+    );
+}
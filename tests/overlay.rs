@@ -211,6 +211,11 @@ fn snapshot_serializes_to_the_documented_json_field_names() {
         "best_possible_time_ms",
         "pb_time_ms",
         "previous_segment_delta_ms",
+        "previous_segment_is_gold",
+        "possible_time_save_ms",
+        "current_pace_ms",
+        "total_playtime_ms",
+        "pb_chance",
         "splits",
     ] {
         assert!(
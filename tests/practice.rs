@@ -0,0 +1,148 @@
+mod common;
+
+use openspeedrun::Run;
+use openspeedrun::core::protocol::{Reply, handle_command};
+use openspeedrun::core::split::{
+    COMPARISON_AVERAGE_SEGMENTS, COMPARISON_BEST_SEGMENTS, TimingMethod,
+};
+use openspeedrun::core::timer::TimerState;
+
+use common::{app_with_run, run_from_attempts, secs};
+
+/// Splits A, B, C with two finished attempts: 10/20/30 and 12/16/26.
+fn two_finished_runs() -> Run {
+    run_from_attempts(
+        &["A", "B", "C"],
+        &[
+            (&[Some(10), Some(20), Some(30)], true),
+            (&[Some(12), Some(16), Some(26)], true),
+        ],
+    )
+}
+
+fn saved_run(dir: &std::path::Path) -> Run {
+    Run::load_from_file(dir.join("split.json").to_str().unwrap()).unwrap()
+}
+
+#[test]
+fn practice_segments_go_to_practice_history_only() {
+    let (mut app, clock, dir) = app_with_run("history", two_finished_runs());
+
+    app.start_practice(1);
+    assert!(app.in_practice());
+    assert_eq!(app.current_split, 1);
+    clock.advance_ms(14_000);
+    app.split();
+    assert!(app.last_segment_is_gold);
+    clock.advance_ms(29_000);
+    app.split();
+    assert!(!app.last_segment_is_gold);
+    assert_eq!(app.timer.state, TimerState::Ended);
+
+    let run = saved_run(&dir);
+    assert!(run.splits[0].practice_history.is_empty());
+    let practiced: Vec<_> = run.splits[1..]
+        .iter()
+        .map(|s| s.practice_history[0].get(TimingMethod::RealTime))
+        .collect();
+    assert_eq!(practiced, [Some(secs(14)), Some(secs(29))]);
+    assert!(run.splits[1].practice_history[0].date.is_some());
+
+    // Nothing about the real attempts changed, and 14s didn't become a gold.
+    assert_eq!(run.attempts, 2);
+    assert_eq!(run.attempt_history.len(), 2);
+    assert!(run.splits.iter().all(|s| s.segment_history.len() == 2));
+    assert_eq!(
+        run.splits[1].comparison_time(COMPARISON_BEST_SEGMENTS, TimingMethod::RealTime),
+        Some(secs(16))
+    );
+
+    // A reset just ends the session.
+    app.reset_splits();
+    assert!(!app.in_practice());
+    let run = saved_run(&dir);
+    assert_eq!(run.attempt_history.len(), 2);
+    assert_eq!(run.attempts, 2);
+    assert_eq!(run.splits[1].practice_history.len(), 1);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn practice_golds_are_promoted_only_when_enabled() {
+    let (mut app, clock, dir) = app_with_run("promote", two_finished_runs());
+    app.run.promote_practice_golds = true;
+
+    app.start_practice(0);
+    clock.advance_ms(9_000);
+    app.split();
+    clock.advance_ms(50_000);
+    app.split();
+    app.reset_splits();
+
+    let run = saved_run(&dir);
+    let golds: Vec<_> = run
+        .splits
+        .iter()
+        .map(|s| s.comparison_time(COMPARISON_BEST_SEGMENTS, TimingMethod::RealTime))
+        .collect();
+    assert_eq!(golds, [Some(secs(9)), Some(secs(16)), Some(secs(26))]);
+    assert_eq!(run.splits[1].practice_history.len(), 1);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn practice_deltas_compare_against_gold_and_average() {
+    let (mut app, clock, dir) = app_with_run("deltas", two_finished_runs());
+    assert_eq!(app.practice_deltas(0), None);
+
+    app.start_practice(1);
+    clock.advance_ms(15_000);
+    let live = app.practice_deltas(1).unwrap();
+    assert_eq!(live.segment, secs(15));
+    assert_eq!(live.vs_gold, Some(secs(-1)));
+    assert_eq!(live.vs_average, Some(secs(-3)));
+
+    clock.advance_ms(2_000);
+    app.split();
+    let done = app.practice_deltas(1).unwrap();
+    assert_eq!(done.segment, secs(17));
+    assert_eq!(done.vs_gold, Some(secs(1)));
+    assert_eq!(
+        app.run.splits[1]
+            .comparison_time(COMPARISON_AVERAGE_SEGMENTS, TimingMethod::RealTime)
+            .map(|avg| done.segment - avg),
+        done.vs_average
+    );
+    // Before the practice start there's nothing to show.
+    assert_eq!(app.practice_deltas(0), None);
+
+    app.reset_splits();
+    assert_eq!(app.practice_deltas(1), None);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn practice_command_checks_its_argument_and_state() {
+    let (mut app, _clock, dir) = app_with_run("command", two_finished_runs());
+
+    assert_eq!(
+        handle_command(&mut app, "practice"),
+        Reply::err("practice needs a split index")
+    );
+    assert_eq!(
+        handle_command(&mut app, "practice 3"),
+        Reply::err("split index out of range (0-2)")
+    );
+    assert_eq!(handle_command(&mut app, "practice 2"), Reply::ok());
+    assert!(app.in_practice());
+    assert_eq!(handle_command(&mut app, "getsplitindex"), Reply::value(2));
+    assert_eq!(
+        handle_command(&mut app, "practice 0"),
+        Reply::err("can't start practice during a run")
+    );
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,206 @@
+mod common;
+
+use chrono::Duration;
+use openspeedrun::Run;
+use openspeedrun::core::split::{COMPARISON_BEST_SEGMENTS, COMPARISON_PERSONAL_BEST, TimingMethod};
+
+use common::{run_with_history, secs};
+
+/// Each split's time in attempt `run_index`, summed from its segments.
+fn split_times(run: &Run, run_index: u32) -> Vec<Option<Duration>> {
+    let mut total = Duration::zero();
+    run.splits
+        .iter()
+        .map(|split| {
+            let segment = split
+                .segment_history
+                .iter()
+                .find(|e| e.run_index == run_index)?
+                .real_time?;
+            total += segment;
+            Some(total)
+        })
+        .collect()
+}
+
+fn comparison(run: &Run, name: &str) -> Vec<Option<Duration>> {
+    run.splits
+        .iter()
+        .map(|s| s.comparison_time(name, TimingMethod::RealTime))
+        .collect()
+}
+
+fn names(run: &Run) -> Vec<&str> {
+    run.splits.iter().map(|s| s.name.as_str()).collect()
+}
+
+fn reset_index(run: &Run, run_index: u32) -> Option<usize> {
+    run.attempt_history
+        .iter()
+        .find(|a| a.run_index == run_index)
+        .and_then(|a| a.reset_split_index)
+}
+
+#[test]
+fn removing_a_split_merges_its_times_into_the_next() {
+    let mut run = run_with_history();
+    run.remove_split(1);
+
+    assert_eq!(names(&run), ["A", "C"]);
+    assert_eq!(split_times(&run, 1), [Some(secs(10)), Some(secs(60))]);
+    assert_eq!(split_times(&run, 2), [Some(secs(12)), Some(secs(52))]);
+    // The skipped B no longer exists, so attempt 4 is whole again...
+    assert_eq!(split_times(&run, 4), [Some(secs(11)), Some(secs(51))]);
+    assert!(!run.splits[1].segment_history.iter().any(|e| e.combined));
+    // ...and, being faster, the new PB.
+    assert_eq!(
+        comparison(&run, COMPARISON_PERSONAL_BEST),
+        [Some(secs(11)), Some(secs(40))]
+    );
+    assert_eq!(
+        comparison(&run, COMPARISON_BEST_SEGMENTS),
+        [Some(secs(8)), Some(secs(40))]
+    );
+    // Attempt 3 was reset during C, which is now split 1.
+    assert_eq!(reset_index(&run, 3), Some(1));
+    assert_eq!(split_times(&run, 3), [Some(secs(8)), None]);
+}
+
+#[test]
+fn removing_the_final_split_merges_it_into_the_previous_one() {
+    let mut run = run_with_history();
+    run.remove_split(2);
+
+    assert_eq!(names(&run), ["A", "B"]);
+    assert_eq!(split_times(&run, 1), [Some(secs(10)), Some(secs(60))]);
+    assert_eq!(split_times(&run, 4), [Some(secs(11)), Some(secs(51))]);
+    assert_eq!(
+        run.comparison_total(COMPARISON_PERSONAL_BEST, TimingMethod::RealTime),
+        Some(secs(51))
+    );
+    assert_eq!(reset_index(&run, 3), Some(1));
+
+    // The last split standing stays.
+    let mut single = Run::new("Game", "Any%", &["Only"]);
+    single.remove_split(0);
+    assert_eq!(names(&single), ["Only"]);
+}
+
+#[test]
+fn inserting_a_split_records_it_as_skipped_by_past_attempts() {
+    let mut run = run_with_history();
+    run.insert_split(1, "New");
+
+    assert_eq!(names(&run), ["A", "New", "B", "C"]);
+    assert_eq!(
+        split_times(&run, 1),
+        [Some(secs(10)), None, Some(secs(30)), Some(secs(60))]
+    );
+    assert!(
+        run.splits[2]
+            .segment_history
+            .iter()
+            .filter(|e| e.real_time.is_some())
+            .all(|e| e.combined)
+    );
+    // Comparisons stay where they were; the new split has none yet.
+    assert_eq!(
+        comparison(&run, COMPARISON_PERSONAL_BEST),
+        [Some(secs(12)), None, Some(secs(15)), Some(secs(25))]
+    );
+    assert_eq!(reset_index(&run, 3), Some(3));
+}
+
+#[test]
+fn splitting_in_two_divides_the_comparisons() {
+    let mut run = run_with_history();
+    run.split_in_two(1, "B1", 0.4);
+
+    assert_eq!(names(&run), ["A", "B1", "B", "C"]);
+    assert_eq!(
+        comparison(&run, COMPARISON_PERSONAL_BEST),
+        [Some(secs(12)), Some(secs(6)), Some(secs(9)), Some(secs(25))]
+    );
+    assert_eq!(
+        run.comparison_total(COMPARISON_PERSONAL_BEST, TimingMethod::RealTime),
+        Some(secs(52))
+    );
+    assert_eq!(
+        split_times(&run, 2),
+        [Some(secs(12)), None, Some(secs(27)), Some(secs(52))]
+    );
+}
+
+#[test]
+fn moving_a_split_takes_its_history_and_marks_gaps_as_skipped() {
+    let mut run = run_with_history();
+    run.move_split(2, 0);
+
+    assert_eq!(names(&run), ["C", "A", "B"]);
+    assert_eq!(
+        comparison(&run, COMPARISON_PERSONAL_BEST),
+        [Some(secs(25)), Some(secs(12)), Some(secs(15))]
+    );
+    // Finished attempts still add up to the same final time.
+    assert_eq!(split_times(&run, 2)[2], Some(secs(52)));
+    // Attempt 3 never reached C, which now comes first: C is recorded as
+    // skipped, so its A time is a combined segment.
+    assert_eq!(split_times(&run, 3), [None, Some(secs(8)), Some(secs(26))]);
+    assert_eq!(reset_index(&run, 3), Some(2));
+    assert!(
+        run.splits[1]
+            .segment_history
+            .iter()
+            .any(|e| e.run_index == 3 && e.combined)
+    );
+    // Golds stay as they were, including ones history no longer backs.
+    assert_eq!(
+        comparison(&run, COMPARISON_BEST_SEGMENTS),
+        [Some(secs(25)), Some(secs(8)), Some(secs(15))]
+    );
+
+    run.move_split(0, 2);
+    assert_eq!(names(&run), ["A", "B", "C"]);
+    assert_eq!(
+        split_times(&run, 1),
+        [Some(secs(10)), Some(secs(30)), Some(secs(60))]
+    );
+    // The skip recorded for the gap goes again once there's no gap.
+    assert_eq!(split_times(&run, 3), [Some(secs(8)), Some(secs(26)), None]);
+    assert_eq!(reset_index(&run, 3), Some(2));
+    assert!(
+        run.splits[2]
+            .segment_history
+            .iter()
+            .all(|e| e.run_index != 3)
+    );
+    assert!(
+        !run.splits[1]
+            .segment_history
+            .iter()
+            .any(|e| e.run_index == 3 && e.combined)
+    );
+}
+
+#[test]
+fn moving_a_split_keeps_a_hand_entered_gold_unless_history_beats_it() {
+    let mut run = run_with_history();
+    for (i, typed) in [(0, 5), (2, 30)] {
+        run.splits[i]
+            .comparisons
+            .get_mut(COMPARISON_BEST_SEGMENTS)
+            .unwrap()
+            .real_time = Some(secs(typed));
+    }
+
+    run.move_split(0, 1);
+    assert_eq!(names(&run), ["B", "A", "C"]);
+    assert_eq!(comparison(&run, COMPARISON_BEST_SEGMENTS)[1], Some(secs(5)));
+
+    run.move_split(2, 0);
+    assert_eq!(names(&run), ["C", "B", "A"]);
+    assert_eq!(
+        comparison(&run, COMPARISON_BEST_SEGMENTS)[0],
+        Some(secs(25))
+    );
+}
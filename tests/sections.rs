@@ -0,0 +1,101 @@
+mod common;
+
+use std::sync::Arc;
+
+use openspeedrun::core::split::{COMPARISON_PERSONAL_BEST, SplitSection};
+use openspeedrun::core::time_source::FakeClock;
+use openspeedrun::{AppState, Run};
+
+use common::secs;
+
+/// Intro, then "Dungeon" (Key, Boss), then Ending; PB 10s per split.
+fn sectioned_run() -> Run {
+    let mut run = Run::new("Game", "Any%", &["Intro", "Key", "Boss", "Ending"]);
+    for split in &mut run.splits[1..3] {
+        split.section = Some("Dungeon".to_string());
+    }
+    for split in &mut run.splits {
+        split
+            .comparisons
+            .get_mut(COMPARISON_PERSONAL_BEST)
+            .unwrap()
+            .real_time = Some(secs(10));
+    }
+    run
+}
+
+#[test]
+fn sections_cover_every_split_in_order() {
+    let mut run = sectioned_run();
+    assert_eq!(
+        run.sections(),
+        [
+            SplitSection {
+                name: None,
+                range: 0..1
+            },
+            SplitSection {
+                name: Some("Dungeon".to_string()),
+                range: 1..3
+            },
+            SplitSection {
+                name: None,
+                range: 3..4
+            },
+        ]
+    );
+    assert_eq!(run.section_of(2).unwrap().range, 1..3);
+    assert_eq!(run.section_of(4), None);
+
+    // Same name, but not adjacent: two sections.
+    run.splits[3].section = Some("Dungeon".to_string());
+    run.splits[2].section = None;
+    let ranges: Vec<_> = run.sections().into_iter().map(|s| s.range).collect();
+    assert_eq!(ranges, [0..1, 1..2, 2..3, 3..4]);
+}
+
+#[test]
+fn section_times_follow_the_attempt() {
+    let dir = std::env::temp_dir().join(format!("osr_sections_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let run = sectioned_run();
+    run.save_to_file(dir.join("split.json").to_str().unwrap())
+        .unwrap();
+
+    let clock = FakeClock::default();
+    let mut app = AppState {
+        splits_display: run.splits.clone(),
+        splits_backup: run.splits.clone(),
+        run,
+        split_base_path: dir.clone(),
+        ..AppState::empty_for_test()
+    };
+    app.set_clock(Arc::new(clock.clone()));
+    let dungeon = app.run.section_of(1).unwrap();
+
+    let times = app.section_times(&dungeon);
+    assert_eq!(times.segment, None);
+    assert_eq!(times.cumulative, None);
+    assert_eq!(times.comparison, Some(secs(20)));
+    assert_eq!(times.delta, None);
+
+    app.start_timers();
+    clock.advance_ms(12_000);
+    app.split();
+    clock.advance_ms(7_000);
+    app.split();
+    clock.advance_ms(5_000);
+    // Halfway through: live section time, no delta yet.
+    let times = app.section_times(&dungeon);
+    assert_eq!(times.segment, Some(secs(12)));
+    assert_eq!(times.delta, None);
+
+    clock.advance_ms(4_000);
+    app.split();
+    let times = app.section_times(&dungeon);
+    assert_eq!(times.segment, Some(secs(16)));
+    assert_eq!(times.cumulative, Some(secs(28)));
+    assert_eq!(times.delta, Some(secs(-4)));
+
+    std::fs::remove_dir_all(&dir).ok();
+}
@@ -1,3 +1,5 @@
+mod common;
+
 use chrono::Duration;
 use openspeedrun::core::split::{
     AttemptHistoryEntry, COMPARISON_AVERAGE_SEGMENTS, COMPARISON_BEST_SEGMENTS,
@@ -5,6 +7,8 @@ use openspeedrun::core::split::{
 };
 use openspeedrun::{Run, Split};
 
+use common::app_with_run;
+
 fn ms(n: i64) -> Duration {
     Duration::milliseconds(n)
 }
@@ -34,16 +38,19 @@ fn average_and_median_segments_are_computed_from_history() {
                 run_index: 0,
                 real_time: Some(ms(1000)),
                 game_time: None,
+                combined: false,
             },
             SegmentHistoryEntry {
                 run_index: 1,
                 real_time: Some(ms(2000)),
                 game_time: None,
+                combined: false,
             },
             SegmentHistoryEntry {
                 run_index: 2,
                 real_time: Some(ms(3000)),
                 game_time: None,
+                combined: false,
             },
         ],
         ..Default::default()
@@ -67,11 +74,13 @@ fn median_of_an_even_length_history_averages_the_middle_two() {
                 run_index: 0,
                 real_time: Some(ms(1000)),
                 game_time: None,
+                combined: false,
             },
             SegmentHistoryEntry {
                 run_index: 1,
                 real_time: Some(ms(2000)),
                 game_time: None,
+                combined: false,
             },
         ],
         ..Default::default()
@@ -104,11 +113,13 @@ fn recompute_best_segment_picks_the_minimum_and_drops_removed_records() {
                 run_index: 0,
                 real_time: Some(ms(5000)),
                 game_time: None,
+                combined: false,
             },
             SegmentHistoryEntry {
                 run_index: 1,
                 real_time: Some(ms(3000)),
                 game_time: None,
+                combined: false,
             },
         ],
         ..Default::default()
@@ -171,6 +182,8 @@ fn recompute_personal_best_picks_the_fastest_ended_attempt() {
             game_time: None,
             ended: true,
             date: None,
+            reset_split_index: None,
+            ..Default::default()
         },
         AttemptHistoryEntry {
             run_index: 1,
@@ -178,6 +191,8 @@ fn recompute_personal_best_picks_the_fastest_ended_attempt() {
             game_time: None,
             ended: true,
             date: None,
+            reset_split_index: None,
+            ..Default::default()
         },
         // A faster-looking but unfinished attempt must not be picked.
         AttemptHistoryEntry {
@@ -186,6 +201,8 @@ fn recompute_personal_best_picks_the_fastest_ended_attempt() {
             game_time: None,
             ended: false,
             date: None,
+            reset_split_index: None,
+            ..Default::default()
         },
     ];
 
@@ -194,16 +211,19 @@ fn recompute_personal_best_picks_the_fastest_ended_attempt() {
             run_index: 0,
             real_time: Some(ms(4000)),
             game_time: None,
+            combined: false,
         },
         SegmentHistoryEntry {
             run_index: 1,
             real_time: Some(ms(2000)),
             game_time: None,
+            combined: false,
         },
         SegmentHistoryEntry {
             run_index: 2,
             real_time: Some(ms(400)),
             game_time: None,
+            combined: false,
         },
     ];
     run.splits[1].segment_history = vec![
@@ -211,16 +231,19 @@ fn recompute_personal_best_picks_the_fastest_ended_attempt() {
             run_index: 0,
             real_time: Some(ms(5000)),
             game_time: None,
+            combined: false,
         },
         SegmentHistoryEntry {
             run_index: 1,
             real_time: Some(ms(3000)),
             game_time: None,
+            combined: false,
         },
         SegmentHistoryEntry {
             run_index: 2,
             real_time: Some(ms(600)),
             game_time: None,
+            combined: false,
         },
     ];
 
@@ -297,3 +320,188 @@ fn run_save_and_load_round_trips_through_json() {
 
     std::fs::remove_dir_all(&dir).ok();
 }
+
+#[test]
+fn whole_attempt_driven_by_a_fake_clock_records_exact_segments() {
+    let (mut app, clock, dir) =
+        app_with_run("fake_clock_attempt", Run::new("Game", "Any%", &["A", "B"]));
+
+    app.split(); // start
+    clock.advance_ms(10_000);
+    app.toggle_igt_pause(); // a 2s load, excluded from game time
+    clock.advance_ms(2_000);
+    app.toggle_igt_pause();
+    clock.advance_ms(3_000);
+    app.split();
+    clock.advance_ms(7_000);
+    app.split();
+
+    assert!(app.timer.is_ended());
+    assert_eq!(app.timer.current_time(), ms(22_000));
+    assert_eq!(app.igt_timer.current_time(), ms(20_000));
+    assert_eq!(app.splits_display[0].last_time, Some(ms(15_000)));
+    assert_eq!(app.splits_display[0].last_time_game, Some(ms(13_000)));
+
+    assert_eq!(
+        app.run.splits[0].comparison_time(COMPARISON_PERSONAL_BEST, TimingMethod::RealTime),
+        Some(ms(15_000))
+    );
+    assert_eq!(
+        app.run.splits[1].comparison_time(COMPARISON_BEST_SEGMENTS, TimingMethod::GameTime),
+        Some(ms(7_000))
+    );
+
+    let attempt = app.run.attempt_history.last().unwrap();
+    assert_eq!(attempt.real_time, Some(ms(22_000)));
+    assert_eq!(
+        attempt.date,
+        Some(chrono::DateTime::UNIX_EPOCH + ms(22_000))
+    );
+
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn skipped_split_leaves_no_time_and_the_next_segment_is_stored_as_combined() {
+    let (mut app, clock, dir) =
+        app_with_run("skip_split", Run::new("Game", "Any%", &["A", "B", "C"]));
+
+    app.split(); // start
+    clock.advance_ms(10_000);
+    app.skip_split(); // missed A's trigger
+    assert_eq!(app.current_split, 1);
+    assert_eq!(app.splits_display[0].last_time, None);
+
+    clock.advance_ms(5_000);
+    app.split(); // B, 15s after the start
+    assert!(!app.last_segment_is_gold);
+
+    let a = &app.run.splits[0];
+    assert_eq!(a.segment_history.len(), 1);
+    assert_eq!(a.segment_history[0].real_time, None);
+    assert_eq!(
+        a.comparison_time(COMPARISON_BEST_SEGMENTS, TimingMethod::RealTime),
+        None
+    );
+
+    let b = &app.run.splits[1];
+    assert_eq!(b.segment_history.len(), 1);
+    assert!(b.segment_history[0].combined);
+    assert_eq!(b.segment_history[0].real_time, Some(ms(15_000)));
+    // A combined segment spans A and B, so it's no gold for B either.
+    assert_eq!(
+        b.comparison_time(COMPARISON_BEST_SEGMENTS, TimingMethod::RealTime),
+        None
+    );
+
+    clock.advance_ms(4_000);
+    app.split(); // C finishes the run
+    assert!(!app.run.splits[2].segment_history[0].combined);
+    assert_eq!(
+        app.run.splits[2].comparison_time(COMPARISON_BEST_SEGMENTS, TimingMethod::RealTime),
+        Some(ms(4_000))
+    );
+    // A run with a skipped split has no per-split time at A to become the PB.
+    assert!(!app.last_run_is_pb);
+    assert_eq!(
+        app.run.splits[2].comparison_time(COMPARISON_PERSONAL_BEST, TimingMethod::RealTime),
+        None
+    );
+
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn the_final_split_cannot_be_skipped() {
+    let (mut app, clock, dir) = app_with_run("skip_final", Run::new("Game", "Any%", &["A", "B"]));
+
+    app.skip_split(); // not running yet: ignored
+    assert_eq!(app.current_split, 0);
+
+    app.split();
+    clock.advance_ms(1_000);
+    app.skip_split();
+    app.skip_split();
+    assert_eq!(app.current_split, 1);
+    assert!(app.timer.is_running());
+
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn combined_segments_are_ignored_by_golds_and_segment_stats() {
+    let mut split = Split {
+        segment_history: vec![
+            SegmentHistoryEntry {
+                run_index: 0,
+                real_time: Some(ms(5_000)),
+                game_time: None,
+                combined: false,
+            },
+            SegmentHistoryEntry {
+                run_index: 1,
+                real_time: Some(ms(9_000)),
+                game_time: None,
+                combined: false,
+            },
+            // Would be both the gold and skew the average if counted.
+            SegmentHistoryEntry {
+                run_index: 2,
+                real_time: Some(ms(1_000)),
+                game_time: None,
+                combined: true,
+            },
+        ],
+        ..Default::default()
+    };
+
+    split.recompute_best_segment();
+
+    assert_eq!(
+        split.comparison_time(COMPARISON_BEST_SEGMENTS, TimingMethod::RealTime),
+        Some(ms(5_000))
+    );
+    assert_eq!(
+        split.comparison_time(COMPARISON_AVERAGE_SEGMENTS, TimingMethod::RealTime),
+        Some(ms(7_000))
+    );
+    assert_eq!(
+        split.comparison_time(COMPARISON_MEDIAN_SEGMENTS, TimingMethod::RealTime),
+        Some(ms(7_000))
+    );
+}
+
+#[test]
+fn resetting_mid_run_records_an_unfinished_attempt() {
+    let (mut app, clock, dir) =
+        app_with_run("reset_attempt", Run::new("Game", "Any%", &["A", "B", "C"]));
+
+    app.split(); // start
+    clock.advance_ms(10_000);
+    app.split();
+    clock.advance_ms(4_000);
+    app.reset_splits();
+
+    assert_eq!(app.run.attempts, 1);
+    let attempt = app.run.attempt_history.last().unwrap();
+    assert!(!attempt.ended);
+    assert_eq!(attempt.run_index, 1);
+    assert_eq!(attempt.real_time, Some(ms(14_000)));
+    assert_eq!(attempt.reset_split_index, Some(1));
+    assert_eq!(
+        attempt.date,
+        Some(chrono::DateTime::UNIX_EPOCH + ms(14_000))
+    );
+    // The segment it did finish is filed under the same attempt.
+    assert_eq!(app.run.splits[0].segment_history[0].run_index, 1);
+
+    // Resetting an idle timer isn't an attempt.
+    app.reset_splits();
+    assert_eq!(app.run.attempt_history.len(), 1);
+
+    let on_disk = Run::load_from_file(dir.join("split.json").to_str().unwrap()).unwrap();
+    assert_eq!(on_disk.attempt_history.len(), 1);
+    assert_eq!(on_disk.attempts, 1);
+
+    std::fs::remove_dir_all(&dir).ok();
+}
@@ -0,0 +1,156 @@
+mod common;
+
+use chrono::{DateTime, Duration};
+use openspeedrun::Run;
+use openspeedrun::core::split::{AttemptHistoryEntry, SegmentHistoryEntry, TimingMethod};
+use openspeedrun::core::stats::{progression, split_statistics};
+
+use common::secs;
+
+/// Splits A, B, C. Attempts, in order:
+/// 1. finished, 10/20/30
+/// 2. reset during A
+/// 3. reset during B, after 8 on A (a gold)
+/// 4. finished, 12/16/32 (a gold on B)
+/// 5. logged without a reset split — left out of the funnel
+fn run_with_resets() -> Run {
+    let mut run = Run::new("Game", "Any%", &["A", "B", "C"]);
+    let segments: [(u32, &[i64]); 3] = [(1, &[10, 20, 30]), (3, &[8]), (4, &[12, 16, 32])];
+    for (run_index, times) in segments {
+        for (split, time) in run.splits.iter_mut().zip(times) {
+            split.segment_history.push(SegmentHistoryEntry {
+                run_index,
+                real_time: Some(secs(*time)),
+                ..Default::default()
+            });
+        }
+    }
+    let attempts = [
+        (1, true, None),
+        (2, false, Some(0)),
+        (3, false, Some(1)),
+        (4, true, None),
+        (5, false, None),
+    ];
+    for (run_index, ended, reset_split_index) in attempts {
+        run.attempt_history.push(AttemptHistoryEntry {
+            run_index,
+            ended,
+            reset_split_index,
+            ..Default::default()
+        });
+    }
+    run
+}
+
+#[test]
+fn funnel_counts_where_attempts_died() {
+    let stats = split_statistics(&run_with_resets(), TimingMethod::RealTime);
+    let funnel: Vec<_> = stats.iter().map(|s| (s.reached, s.resets)).collect();
+    assert_eq!(funnel, [(4, 1), (3, 1), (2, 0)]);
+    assert_eq!(stats[0].reset_rate, Some(0.25));
+    assert_eq!(stats[2].reset_rate, Some(0.0));
+}
+
+#[test]
+fn segment_spread_and_gold_rate() {
+    let stats = split_statistics(&run_with_resets(), TimingMethod::RealTime);
+
+    // A: 10, 8, 12 — one gold (8) out of the two after the first.
+    assert_eq!(stats[0].mean, Some(secs(10)));
+    assert_eq!(stats[0].median, Some(secs(10)));
+    assert_eq!(stats[0].std_dev, Some(Duration::milliseconds(1_633)));
+    assert_eq!(stats[0].gold_rate, Some(0.5));
+
+    // B: 20, 16 — the second is a gold.
+    assert_eq!(stats[1].mean, Some(secs(18)));
+    assert_eq!(stats[1].std_dev, Some(secs(2)));
+    assert_eq!(stats[1].gold_rate, Some(1.0));
+
+    // Nothing timed on game time.
+    let game = split_statistics(&run_with_resets(), TimingMethod::GameTime);
+    assert_eq!(game[0].mean, None);
+    assert_eq!(game[0].std_dev, None);
+    assert_eq!(game[0].gold_rate, None);
+}
+
+#[test]
+fn combined_segments_are_left_out_of_the_spread() {
+    let mut run = run_with_resets();
+    run.splits[2].segment_history.push(SegmentHistoryEntry {
+        run_index: 6,
+        real_time: Some(secs(1)),
+        combined: true,
+        ..Default::default()
+    });
+    let stats = split_statistics(&run, TimingMethod::RealTime);
+    assert_eq!(stats[2].mean, Some(secs(31)));
+    assert_eq!(stats[2].gold_rate, Some(0.0));
+}
+
+#[test]
+fn no_attempts_means_no_rates() {
+    let run = Run::new("Game", "Any%", &["A"]);
+    let stats = split_statistics(&run, TimingMethod::RealTime);
+    assert_eq!(stats[0].reached, 0);
+    assert_eq!(stats[0].reset_rate, None);
+    assert_eq!(stats[0].median, None);
+}
+
+#[test]
+fn progression_replays_attempts_in_order() {
+    let mut run = run_with_resets();
+    for attempt in &mut run.attempt_history {
+        attempt.date = DateTime::from_timestamp(1_700_000_000 + attempt.run_index as i64, 0);
+    }
+    run.attempt_history[0].real_time = Some(secs(60));
+    run.attempt_history[3].real_time = Some(secs(60));
+    // Logged out of order; replayed by run index.
+    run.attempt_history.swap(0, 3);
+
+    let points = progression(&run, TimingMethod::RealTime);
+    let summary: Vec<_> = points
+        .iter()
+        .map(|p| (p.run_index, p.attempts, p.sum_of_best, p.personal_best))
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            (1, 1, Some(secs(60)), Some(secs(60))),
+            (2, 2, Some(secs(60)), Some(secs(60))),
+            (3, 3, Some(secs(58)), Some(secs(60))),
+            (4, 4, Some(secs(54)), Some(secs(60))),
+            (5, 5, Some(secs(54)), Some(secs(60))),
+        ]
+    );
+    assert_eq!(points[2].date, DateTime::from_timestamp(1_700_000_003, 0));
+}
+
+#[test]
+fn progression_waits_for_every_split_and_a_finish() {
+    let mut run = run_with_resets();
+    run.attempt_history.retain(|a| a.run_index == 3);
+    let points = progression(&run, TimingMethod::RealTime);
+    assert_eq!(points.len(), 1);
+    assert_eq!(points[0].sum_of_best, None);
+    assert_eq!(points[0].personal_best, None);
+}
+
+#[test]
+fn progression_never_takes_a_run_with_skipped_splits_as_the_pb() {
+    // Attempt 4 adds up to 51s, under the 52s PB, but skipped B.
+    let run = common::run_with_history();
+    let personal_bests: Vec<_> = progression(&run, TimingMethod::RealTime)
+        .iter()
+        .map(|p| p.personal_best)
+        .collect();
+    assert_eq!(
+        personal_bests,
+        [
+            Some(secs(60)),
+            Some(secs(52)),
+            Some(secs(52)),
+            Some(secs(52))
+        ]
+    );
+}
@@ -0,0 +1,189 @@
+mod common;
+
+use chrono::Duration;
+use openspeedrun::Run;
+use openspeedrun::core::protocol::{Reply, handle_command};
+use openspeedrun::core::split::{
+    AttemptHistoryEntry, COMPARISON_AVERAGE_SEGMENTS, COMPARISON_LATEST_RUN, SegmentHistoryEntry,
+    TimingMethod,
+};
+
+use common::{app_with_run, secs};
+
+/// Splits A, B; attempts 1 and 3 on the "old route" (20/20), attempt 2 on
+/// the "new route" (10/30).
+fn tagged_run() -> Run {
+    let mut run = Run::new("Game", "Any%", &["A", "B"]);
+    for (run_index, segments, tag) in [
+        (1, [20, 20], "old route"),
+        (2, [10, 30], "new route"),
+        (3, [20, 20], "old route"),
+    ] {
+        for (split, segment) in run.splits.iter_mut().zip(segments) {
+            split.segment_history.push(SegmentHistoryEntry {
+                run_index,
+                real_time: Some(secs(segment)),
+                ..Default::default()
+            });
+        }
+        run.attempt_history.push(AttemptHistoryEntry {
+            run_index,
+            real_time: Some(secs(40)),
+            ended: true,
+            tags: vec![tag.to_string()],
+            ..Default::default()
+        });
+    }
+    run.attempts = 3;
+    run
+}
+
+#[test]
+fn restricting_to_a_tag_keeps_only_its_attempts() {
+    let run = tagged_run();
+    assert_eq!(run.tags(), ["new route", "old route"]);
+
+    let new_route = run.restricted_to_tag("new route");
+    let indices: Vec<_> = new_route
+        .attempt_history
+        .iter()
+        .map(|a| a.run_index)
+        .collect();
+    assert_eq!(indices, [2]);
+    assert!(
+        new_route
+            .splits
+            .iter()
+            .all(|s| s.segment_history.len() == 1 && s.segment_history[0].run_index == 2)
+    );
+    assert!(run.restricted_to_tag("nope").attempt_history.is_empty());
+}
+
+#[test]
+fn comparison_tag_restricts_averages_and_generated_comparisons() {
+    let mut run = tagged_run();
+    run.generate_comparisons();
+    let a = |run: &Run, name: &str| run.splits[0].comparison_time(name, TimingMethod::RealTime);
+    assert_eq!(
+        a(&run, COMPARISON_AVERAGE_SEGMENTS),
+        Some(Duration::milliseconds(16_666))
+    );
+    assert_eq!(a(&run, COMPARISON_LATEST_RUN), Some(secs(20)));
+
+    run.comparison_tag = Some("new route".to_string());
+    run.generate_comparisons();
+    assert_eq!(a(&run, COMPARISON_AVERAGE_SEGMENTS), Some(secs(10)));
+    assert_eq!(a(&run, COMPARISON_LATEST_RUN), Some(secs(10)));
+
+    run.comparison_tag = None;
+    run.generate_comparisons();
+    assert_eq!(a(&run, COMPARISON_LATEST_RUN), Some(secs(20)));
+}
+
+#[test]
+fn attempts_without_notes_or_tags_still_load() {
+    let json = r#"{"run_index": 4, "real_time": 1000, "ended": true}"#;
+    let attempt: AttemptHistoryEntry = serde_json::from_str(json).unwrap();
+    assert_eq!(attempt.note, None);
+    assert!(attempt.tags.is_empty());
+}
+
+fn saved_run(dir: &std::path::Path) -> Run {
+    Run::load_from_file(dir.join("split.json").to_str().unwrap()).unwrap()
+}
+
+#[test]
+fn notes_and_tags_during_a_run_are_saved_with_it() {
+    let (mut app, clock, dir) = app_with_run("live", tagged_run());
+
+    app.start_timers();
+    clock.advance_ms(5_000);
+    assert_eq!(handle_command(&mut app, "note bad cycle"), Reply::ok());
+    assert_eq!(handle_command(&mut app, "tag new route"), Reply::ok());
+    assert_eq!(handle_command(&mut app, "tag new route"), Reply::ok());
+    assert_eq!(
+        handle_command(&mut app, "note died to the boss"),
+        Reply::ok()
+    );
+    app.reset_splits();
+
+    let run = saved_run(&dir);
+    let last = run.attempt_history.last().unwrap();
+    assert!(!last.ended);
+    assert_eq!(last.note.as_deref(), Some("bad cycle\ndied to the boss"));
+    assert_eq!(last.tags, ["new route"]);
+
+    // Between attempts, it goes on the last one logged, right away.
+    assert_eq!(handle_command(&mut app, "tag controller"), Reply::ok());
+    let run = saved_run(&dir);
+    assert_eq!(
+        run.attempt_history.last().unwrap().tags,
+        ["new route", "controller"]
+    );
+
+    // Nothing leaks into the next attempt.
+    app.start_timers();
+    clock.advance_ms(1_000);
+    app.reset_splits();
+    let run = saved_run(&dir);
+    let last = run.attempt_history.last().unwrap();
+    assert_eq!(last.note, None);
+    assert!(last.tags.is_empty());
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn a_new_pbs_tags_stay_on_its_attempt_history_entry() {
+    let (mut app, clock, dir) = app_with_run("pb", tagged_run());
+
+    app.start_timers();
+    assert_eq!(handle_command(&mut app, "tag new route"), Reply::ok());
+    clock.advance_ms(4_000);
+    app.split();
+    clock.advance_ms(4_000);
+    app.split();
+    assert_eq!(handle_command(&mut app, "tag controller"), Reply::ok());
+
+    let run = saved_run(&dir);
+    let pb = run.pb_history.last().unwrap();
+    assert_eq!(pb.run_index, 4);
+    assert!(pb.tags.is_empty());
+    assert_eq!(
+        run.attempt_history.last().unwrap().tags,
+        ["new route", "controller"]
+    );
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn note_and_tag_commands_reject_bad_input() {
+    let (mut app, _clock, dir) = app_with_run("errors", tagged_run());
+    assert_eq!(
+        handle_command(&mut app, "note"),
+        Reply::err("note needs some text")
+    );
+    assert_eq!(
+        handle_command(&mut app, "tag"),
+        Reply::err("tag needs a name")
+    );
+    assert_eq!(
+        handle_command(&mut app, "tag a,b"),
+        Reply::err("tags can't contain commas")
+    );
+
+    app.run.attempt_history.clear();
+    assert_eq!(
+        handle_command(&mut app, "note hello"),
+        Reply::err("no attempt recorded yet")
+    );
+
+    app.start_practice(0);
+    assert_eq!(
+        handle_command(&mut app, "note hello"),
+        Reply::err("practice isn't an attempt")
+    );
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -1,6 +1,14 @@
+use std::sync::Arc;
+
 use chrono::Duration;
+use openspeedrun::core::time_source::{FakeClock, TimeSource};
 use openspeedrun::{Timer, TimerState};
 
+fn fake_timer() -> (Timer, FakeClock) {
+    let clock = FakeClock::default();
+    (Timer::with_clock(Arc::new(clock.clone())), clock)
+}
+
 #[test]
 fn new_timer_is_not_started_at_zero() {
     let timer = Timer::new();
@@ -10,45 +18,47 @@ fn new_timer_is_not_started_at_zero() {
 
 #[test]
 fn start_runs_and_advances_current_time() {
-    let mut timer = Timer::new();
+    let (mut timer, clock) = fake_timer();
     timer.start();
     assert!(timer.is_running());
 
-    std::thread::sleep(std::time::Duration::from_millis(20));
-    assert!(timer.current_time() >= Duration::milliseconds(20));
+    clock.advance_ms(20);
+    assert_eq!(timer.current_time(), Duration::milliseconds(20));
 }
 
 #[test]
 fn pause_freezes_elapsed_and_resume_continues_from_it() {
-    let mut timer = Timer::new();
+    let (mut timer, clock) = fake_timer();
     timer.start();
-    std::thread::sleep(std::time::Duration::from_millis(20));
+    clock.advance_ms(20);
     timer.pause();
     assert!(timer.is_paused());
 
     let frozen = timer.current_time();
-    assert!(frozen >= Duration::milliseconds(20));
+    assert_eq!(frozen, Duration::milliseconds(20));
 
     // Time must not advance while paused.
-    std::thread::sleep(std::time::Duration::from_millis(20));
+    clock.advance_ms(20);
     assert_eq!(timer.current_time(), frozen);
 
     // start_with_offset() on a Paused timer resumes rather than restarting
     // from zero.
     timer.start_with_offset(0);
     assert!(timer.is_running());
-    assert!(timer.current_time() >= frozen);
+    clock.advance_ms(5);
+    assert_eq!(timer.current_time(), Duration::milliseconds(25));
 }
 
 #[test]
 fn end_freezes_permanently_and_blocks_restart() {
-    let mut timer = Timer::new();
+    let (mut timer, clock) = fake_timer();
     timer.start();
+    clock.advance_ms(10);
     timer.end();
     assert!(timer.is_ended());
 
     let frozen = timer.current_time();
-    std::thread::sleep(std::time::Duration::from_millis(10));
+    clock.advance_ms(10);
     assert_eq!(timer.current_time(), frozen);
 
     // An ended run shouldn't start ticking again.
@@ -59,8 +69,9 @@ fn end_freezes_permanently_and_blocks_restart() {
 
 #[test]
 fn reset_clears_state_back_to_not_started() {
-    let mut timer = Timer::new();
+    let (mut timer, clock) = fake_timer();
     timer.start();
+    clock.advance_ms(10);
     timer.pause();
     timer.reset();
 
@@ -70,11 +81,29 @@ fn reset_clears_state_back_to_not_started() {
 
 #[test]
 fn start_with_offset_counts_down_before_the_run_officially_starts() {
-    let mut timer = Timer::new();
+    let (mut timer, clock) = fake_timer();
     timer.start_with_offset(5);
     assert!(timer.is_running());
     // start_time is 5s in the future, so current_time should read as a
     // negative countdown rather than a positive elapsed time.
-    assert!(timer.current_time() < Duration::zero());
-    assert!(timer.current_time() > Duration::seconds(-6));
+    assert_eq!(timer.current_time(), Duration::seconds(-5));
+
+    clock.advance_ms(6_000);
+    assert_eq!(timer.current_time(), Duration::seconds(1));
+}
+
+#[test]
+fn fake_clock_wall_time_moves_with_elapsed_time() {
+    let clock = FakeClock::default();
+    let before = clock.wall_now();
+    clock.advance_ms(1_500);
+    assert_eq!(clock.wall_now() - before, Duration::milliseconds(1_500));
+}
+
+#[test]
+fn real_timer_still_advances_on_the_monotonic_clock() {
+    let mut timer = Timer::new();
+    timer.start();
+    std::thread::sleep(std::time::Duration::from_millis(5));
+    assert!(timer.current_time() >= Duration::milliseconds(5));
 }
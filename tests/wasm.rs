@@ -0,0 +1,354 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+
+use openspeedrun::autosplitter::wasm::{Host, Process, WasmAutosplitter};
+use openspeedrun::core::timer::TimerState;
+
+const BASE: u64 = 0x40_0000;
+const ROOM: usize = 0x10;
+const FRAMES: usize = 0x20;
+
+/// Attaches to "game.exe", and goes by a room id (u8 at `ROOM`) and an IGT
+/// frame counter at 60 fps (u32 at `FRAMES`), both relative to the main
+/// module: starts on leaving room 0 for room 1, splits on entering any
+/// later room, resets on going back to 0, and keeps game time in sync.
+const AUTOSPLITTER: &str = r#"
+(module
+  (import "env" "process_attach" (func $attach (param i32 i32) (result i64)))
+  (import "env" "process_is_open" (func $is_open (param i64) (result i32)))
+  (import "env" "process_detach" (func $detach (param i64)))
+  (import "env" "process_get_module_address" (func $module (param i64 i32 i32) (result i64)))
+  (import "env" "process_read" (func $read (param i64 i64 i32 i32) (result i32)))
+  (import "env" "timer_get_state" (func $state (result i32)))
+  (import "env" "timer_start" (func $start))
+  (import "env" "timer_split" (func $split))
+  (import "env" "timer_reset" (func $reset))
+  (import "env" "timer_set_game_time" (func $set_game_time (param i64 i32)))
+  (import "env" "runtime_set_tick_rate" (func $tick_rate (param f64)))
+  (import "env" "runtime_print_message" (func $print (param i32 i32)))
+  (import "env" "user_settings_add_bool" (func $setting (param i32 i32 i32 i32 i32) (result i32)))
+
+  (memory (export "memory") 1)
+  (data (i32.const 0) "game.exe")
+  (data (i32.const 16) "attached")
+
+  (global $process (mut i64) (i64.const 0))
+  (global $old_room (mut i32) (i32.const 0))
+  (global $sync_igt (mut i32) (i32.const 0))
+
+  (func (export "_initialize")
+    (call $tick_rate (f64.const 120))
+    (global.set $sync_igt
+      (call $setting (i32.const 0) (i32.const 8) (i32.const 0) (i32.const 8) (i32.const 1))))
+
+  (func (export "update")
+    (local $base i64) (local $room i32) (local $state i32) (local $frames i64)
+    (if (i64.ne (global.get $process) (i64.const 0))
+      (then
+        (if (i32.eqz (call $is_open (global.get $process)))
+          (then
+            (call $detach (global.get $process))
+            (global.set $process (i64.const 0))))))
+    (if (i64.eqz (global.get $process))
+      (then
+        (global.set $process (call $attach (i32.const 0) (i32.const 8)))
+        (if (i64.eqz (global.get $process)) (then (return)))
+        (call $print (i32.const 16) (i32.const 8))))
+
+    (local.set $base (call $module (global.get $process) (i32.const 0) (i32.const 8)))
+    (if (i32.eqz
+          (call $read (global.get $process)
+            (i64.add (local.get $base) (i64.const 0x10)) (i32.const 32) (i32.const 1)))
+      (then (return)))
+    (local.set $room (i32.load8_u (i32.const 32)))
+    (local.set $state (call $state))
+
+    (if (i32.and (i32.eqz (local.get $state))
+          (i32.and (i32.eq (local.get $room) (i32.const 1))
+                   (i32.eqz (global.get $old_room))))
+      (then (call $start)))
+    (if (i32.and (i32.eq (local.get $state) (i32.const 1))
+          (i32.and (i32.gt_u (local.get $room) (global.get $old_room))
+                   (i32.gt_u (local.get $room) (i32.const 1))))
+      (then (call $split)))
+    (if (i32.and (i32.ne (local.get $state) (i32.const 0))
+          (i32.and (i32.eqz (local.get $room))
+                   (i32.ne (global.get $old_room) (i32.const 0))))
+      (then (call $reset)))
+    (global.set $old_room (local.get $room))
+
+    (if (i32.and (global.get $sync_igt) (i32.eq (call $state) (i32.const 1)))
+      (then
+        (if (call $read (global.get $process)
+              (i64.add (local.get $base) (i64.const 0x20)) (i32.const 36) (i32.const 4))
+          (then
+            (local.set $frames (i64.load32_u (i32.const 36)))
+            (call $set_game_time
+              (i64.div_u (local.get $frames) (i64.const 60))
+              (i32.wrap_i64
+                (i64.mul (i64.rem_u (local.get $frames) (i64.const 60))
+                         (i64.const 16666666))))))))))
+"#;
+
+/// A game's memory, shared between the test and the runtime's handle.
+#[derive(Clone)]
+struct Game(Rc<RefCell<GameState>>);
+
+struct GameState {
+    image: Vec<u8>,
+    open: bool,
+}
+
+impl Game {
+    fn new() -> Self {
+        Game(Rc::new(RefCell::new(GameState {
+            image: vec![0; 0x100],
+            open: true,
+        })))
+    }
+
+    fn set_room(&self, room: u8) {
+        self.0.borrow_mut().image[ROOM] = room;
+    }
+
+    fn set_frames(&self, frames: u32) {
+        self.0.borrow_mut().image[FRAMES..FRAMES + 4].copy_from_slice(&frames.to_le_bytes());
+    }
+}
+
+impl Process for Game {
+    fn read(&self, address: u64, buf: &mut [u8]) -> bool {
+        let game = self.0.borrow();
+        let Some(start) = address.checked_sub(BASE) else {
+            return false;
+        };
+        let start = start as usize;
+        match game.image.get(start..start + buf.len()) {
+            Some(bytes) if game.open => {
+                buf.copy_from_slice(bytes);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn module_address(&self, name: &str) -> Option<u64> {
+        (name == "game.exe").then_some(BASE)
+    }
+
+    fn module_size(&self, name: &str) -> Option<u64> {
+        (name == "game.exe").then_some(0x100)
+    }
+
+    fn is_open(&self) -> bool {
+        self.0.borrow().open
+    }
+}
+
+/// Stands in for the timer, following the commands it's sent.
+#[derive(Default)]
+struct FakeHost {
+    game: Option<Game>,
+    running: bool,
+    sent: Vec<String>,
+    printed: Vec<String>,
+}
+
+impl Host for FakeHost {
+    fn attach(&mut self, name: &str) -> Option<Box<dyn Process>> {
+        let game = self.game.clone().filter(|g| g.is_open())?;
+        (name == "game.exe").then(|| Box::new(game) as Box<dyn Process>)
+    }
+
+    fn timer_state(&mut self) -> TimerState {
+        if self.running {
+            TimerState::Running
+        } else {
+            TimerState::NotStarted
+        }
+    }
+
+    fn send(&mut self, command: &str) {
+        match command {
+            "start" => self.running = true,
+            "reset" => self.running = false,
+            _ => {}
+        }
+        self.sent.push(command.to_string());
+    }
+
+    fn print(&mut self, message: &str) {
+        self.printed.push(message.to_string());
+    }
+}
+
+fn load(host: FakeHost) -> WasmAutosplitter<FakeHost> {
+    let wasm = wat::parse_str(AUTOSPLITTER).unwrap();
+    WasmAutosplitter::new(&wasm, host).unwrap()
+}
+
+fn sent_after(autosplitter: &mut WasmAutosplitter<FakeHost>, change: impl FnOnce()) -> Vec<String> {
+    change();
+    autosplitter.host_mut().sent.clear();
+    autosplitter.update().unwrap();
+    std::mem::take(&mut autosplitter.host_mut().sent)
+}
+
+#[test]
+fn a_module_starts_splits_and_resets_from_the_games_memory() {
+    let game = Game::new();
+    let mut autosplitter = load(FakeHost {
+        game: Some(game.clone()),
+        ..FakeHost::default()
+    });
+
+    assert!(sent_after(&mut autosplitter, || {}).is_empty());
+    assert_eq!(autosplitter.host().printed, ["attached"]);
+    assert_eq!(
+        sent_after(&mut autosplitter, || game.set_room(1)),
+        ["start", "setgametime 0"]
+    );
+    assert_eq!(
+        sent_after(&mut autosplitter, || game.set_room(2))[0],
+        "split"
+    );
+    assert_eq!(sent_after(&mut autosplitter, || {}), ["setgametime 0"]);
+    assert_eq!(
+        sent_after(&mut autosplitter, || game.set_room(0)),
+        ["reset"]
+    );
+}
+
+#[test]
+fn game_time_is_sent_in_milliseconds() {
+    let game = Game::new();
+    let mut autosplitter = load(FakeHost {
+        game: Some(game.clone()),
+        running: true,
+        ..FakeHost::default()
+    });
+
+    // 2 seconds and 30 frames.
+    assert_eq!(
+        sent_after(&mut autosplitter, || game.set_frames(150)),
+        ["setgametime 2499"]
+    );
+}
+
+#[test]
+fn a_module_waits_for_the_game_and_reattaches_when_it_restarts() {
+    let mut autosplitter = load(FakeHost::default());
+    autosplitter.update().unwrap();
+    assert!(autosplitter.host().printed.is_empty());
+
+    let game = Game::new();
+    autosplitter.host_mut().game = Some(game.clone());
+    autosplitter.update().unwrap();
+    assert_eq!(autosplitter.host().printed.len(), 1);
+
+    game.0.borrow_mut().open = false;
+    autosplitter.update().unwrap();
+    assert_eq!(autosplitter.host().printed.len(), 1);
+
+    let restarted = Game::new();
+    autosplitter.host_mut().game = Some(restarted.clone());
+    autosplitter.update().unwrap();
+    assert_eq!(autosplitter.host().printed.len(), 2);
+    assert_eq!(
+        sent_after(&mut autosplitter, || restarted.set_room(1)),
+        ["start", "setgametime 0"]
+    );
+}
+
+#[test]
+fn the_tick_rate_set_on_initialize_is_kept() {
+    let autosplitter = load(FakeHost::default());
+    assert_eq!(
+        autosplitter.tick_interval(),
+        Some(Duration::from_secs_f64(1.0 / 120.0))
+    );
+}
+
+#[test]
+fn unsupported_imports_only_fail_when_called() {
+    let wasm = wat::parse_str(
+        r#"(module
+          (import "wasi_snapshot_preview1" "fd_write"
+            (func $fd_write (param i32 i32 i32 i32) (result i32)))
+          (memory (export "memory") 1)
+          (global $ticks (mut i32) (i32.const 0))
+          (func (export "update")
+            (global.set $ticks (i32.add (global.get $ticks) (i32.const 1)))
+            (if (i32.eq (global.get $ticks) (i32.const 2))
+              (then (drop (call $fd_write (i32.const 0) (i32.const 0) (i32.const 0) (i32.const 0)))))))"#,
+    )
+    .unwrap();
+    let mut autosplitter = WasmAutosplitter::new(&wasm, FakeHost::default()).unwrap();
+    assert_eq!(autosplitter.tick_interval(), None);
+
+    autosplitter.update().unwrap();
+    let error = autosplitter.update().unwrap_err();
+    assert!(
+        error.contains("'wasi_snapshot_preview1::fd_write' isn't supported"),
+        "{error}"
+    );
+}
+
+#[test]
+fn bad_handles_and_pointers_trap() {
+    let wasm = wat::parse_str(
+        r#"(module
+          (import "env" "process_is_open" (func $is_open (param i64) (result i32)))
+          (import "env" "runtime_print_message" (func $print (param i32 i32)))
+          (memory (export "memory") 1)
+          (global $ticks (mut i32) (i32.const 0))
+          (func (export "update")
+            (global.set $ticks (i32.add (global.get $ticks) (i32.const 1)))
+            (if (i32.eq (global.get $ticks) (i32.const 1))
+              (then (drop (call $is_open (i64.const 3)))))
+            (if (i32.eq (global.get $ticks) (i32.const 2))
+              (then (call $print (i32.const 65530) (i32.const 10))))))"#,
+    )
+    .unwrap();
+    let mut autosplitter = WasmAutosplitter::new(&wasm, FakeHost::default()).unwrap();
+
+    let error = autosplitter.update().unwrap_err();
+    assert!(error.contains("process handle 3 isn't attached"), "{error}");
+    let error = autosplitter.update().unwrap_err();
+    assert!(error.contains("out of bounds"), "{error}");
+}
+
+#[test]
+fn the_os_name_is_written_back_with_its_length() {
+    let wasm = wat::parse_str(
+        r#"(module
+          (import "env" "runtime_get_os" (func $get_os (param i32 i32) (result i32)))
+          (import "env" "runtime_print_message" (func $print (param i32 i32)))
+          (memory (export "memory") 1)
+          (func (export "update")
+            ;; Too small a buffer first: fails, but says how much is needed.
+            (i32.store (i32.const 0) (i32.const 1))
+            (if (call $get_os (i32.const 16) (i32.const 0)) (then (unreachable)))
+            (if (i32.eqz (call $get_os (i32.const 16) (i32.const 0))) (then (unreachable)))
+            (call $print (i32.const 16) (i32.load (i32.const 0)))))"#,
+    )
+    .unwrap();
+    let mut autosplitter = WasmAutosplitter::new(&wasm, FakeHost::default()).unwrap();
+    autosplitter.update().unwrap();
+    assert_eq!(autosplitter.host().printed, [std::env::consts::OS]);
+}
+
+#[test]
+fn modules_without_update_are_refused() {
+    let wasm = wat::parse_str(r#"(module (memory (export "memory") 1))"#).unwrap();
+    let error = WasmAutosplitter::new(&wasm, FakeHost::default())
+        .err()
+        .unwrap();
+    assert_eq!(error, "the module doesn't export an 'update' function");
+
+    let error = WasmAutosplitter::new(b"not wasm", FakeHost::default())
+        .err()
+        .unwrap();
+    assert!(error.starts_with("not a valid module"), "{error}");
+}